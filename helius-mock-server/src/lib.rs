@@ -0,0 +1,468 @@
+//! A local stand-in for the Helius REST (`/v0`, `/v1`) and JSON-RPC APIs.
+//!
+//! [`MockServer::start`] binds to a random local port and serves every endpoint wrapped by `helius-rust-client`
+//! from the fixture files in `fixtures/`. Individual routes and RPC methods can be overridden with programmable
+//! responses, including error statuses and added latency, and every request received is recorded for assertions.
+
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, HeaderMap, Request, Response, Server, StatusCode,
+};
+use serde_json::{json, Value};
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    net::SocketAddr,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::sync::oneshot;
+
+pub use hyper::Method;
+
+/// The REST routes served by default, as (method, path pattern, fixture). `{name}` matches any single path segment.
+const REST_FIXTURES: &[(&str, &str, &str)] = &[
+    (
+        "GET",
+        "/v0/addresses/{address}/balances",
+        include_str!("../fixtures/balances.json"),
+    ),
+    (
+        "GET",
+        "/v0/addresses/{address}/nfts",
+        include_str!("../fixtures/nfts.json"),
+    ),
+    (
+        "GET",
+        "/v0/addresses/{address}/nft-events",
+        include_str!("../fixtures/nft_events.json"),
+    ),
+    (
+        "GET",
+        "/v0/addresses/{address}/names",
+        include_str!("../fixtures/names.json"),
+    ),
+    (
+        "GET",
+        "/v0/addresses/{address}/raw-transactions",
+        include_str!("../fixtures/raw_transactions.json"),
+    ),
+    (
+        "GET",
+        "/v0/addresses/{address}/transactions",
+        include_str!("../fixtures/enriched_transactions.json"),
+    ),
+    (
+        "POST",
+        "/v0/raw-transactions",
+        include_str!("../fixtures/raw_transactions.json"),
+    ),
+    (
+        "POST",
+        "/v0/transactions",
+        include_str!("../fixtures/enriched_transactions.json"),
+    ),
+    (
+        "POST",
+        "/v0/tokens/metadata",
+        include_str!("../fixtures/tokens_metadata.json"),
+    ),
+    (
+        "POST",
+        "/v1/nfts",
+        include_str!("../fixtures/nfts_metadata.json"),
+    ),
+    (
+        "POST",
+        "/v1/active-listings",
+        include_str!("../fixtures/active_listings.json"),
+    ),
+    (
+        "POST",
+        "/v1/mintlist",
+        include_str!("../fixtures/mintlist.json"),
+    ),
+    (
+        "GET",
+        "/v0/webhooks",
+        include_str!("../fixtures/webhooks.json"),
+    ),
+    (
+        "POST",
+        "/v0/webhooks",
+        include_str!("../fixtures/webhook.json"),
+    ),
+    (
+        "GET",
+        "/v0/webhooks/{webhook_id}",
+        include_str!("../fixtures/webhook.json"),
+    ),
+    (
+        "PUT",
+        "/v0/webhooks/{webhook_id}",
+        include_str!("../fixtures/webhook.json"),
+    ),
+];
+
+/// The JSON-RPC results served by default, as (method, fixture).
+const RPC_FIXTURES: &[(&str, &str)] = &[
+    ("getSlot", include_str!("../fixtures/rpc/getSlot.json")),
+    ("getHealth", include_str!("../fixtures/rpc/getHealth.json")),
+    (
+        "getVersion",
+        include_str!("../fixtures/rpc/getVersion.json"),
+    ),
+    (
+        "getBalance",
+        include_str!("../fixtures/rpc/getBalance.json"),
+    ),
+    (
+        "getAssetsByOwner",
+        include_str!("../fixtures/rpc/getAssetsByOwner.json"),
+    ),
+];
+
+/// A programmable response. For REST routes `body` is the response body; for RPC methods it is the JSON-RPC `result`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockResponse {
+    status: u16,
+    body: Value,
+    delay: Duration,
+    rpc_error: Option<(i64, String)>,
+}
+
+impl MockResponse {
+    /// A `200 OK` response with `body`.
+    pub fn json(body: Value) -> Self {
+        MockResponse {
+            status: 200,
+            body,
+            delay: Duration::ZERO,
+            rpc_error: None,
+        }
+    }
+
+    /// A `200 OK` response whose body is read from the JSON file at `path`.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Self {
+        let contents = std::fs::read_to_string(path.as_ref())
+            .unwrap_or_else(|e| panic!("failed to read fixture {:?}: {}", path.as_ref(), e));
+        MockResponse::json(parse_fixture(&contents))
+    }
+
+    /// An error response with `status` and a `{"error": message}` body, as returned by the Helius REST API.
+    pub fn error(status: u16, message: &str) -> Self {
+        MockResponse::json(json!({ "error": message })).with_status(status)
+    }
+
+    /// A JSON-RPC error object with `code` and `message`, sent with a `200 OK` status.
+    pub fn rpc_error(code: i64, message: &str) -> Self {
+        MockResponse {
+            rpc_error: Some((code, message.to_string())),
+            ..MockResponse::json(Value::Null)
+        }
+    }
+
+    pub fn with_status(mut self, status: u16) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Delays the response by `delay`.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+}
+
+/// A request received by the [`MockServer`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedRequest {
+    pub method: Method,
+    /// The path without a trailing slash.
+    pub path: String,
+    pub query: Option<String>,
+    pub headers: HeaderMap,
+    /// The JSON body, or `Value::Null` if the request had none.
+    pub body: Value,
+}
+
+struct Route {
+    method: Method,
+    pattern: Vec<String>,
+    response: MockResponse,
+    /// Whether the route is removed after answering one request.
+    once: bool,
+}
+
+impl Route {
+    fn matches(&self, method: &Method, path: &str) -> bool {
+        let segments: Vec<&str> = path.split('/').collect();
+        self.method == method
+            && self.pattern.len() == segments.len()
+            && self
+                .pattern
+                .iter()
+                .zip(segments)
+                .all(|(pattern, segment)| pattern.starts_with('{') || pattern == segment)
+    }
+}
+
+#[derive(Default)]
+struct State {
+    routes: Vec<Route>,
+    rpc_methods: HashMap<String, MockResponse>,
+    rejected_keys: HashMap<String, MockResponse>,
+    latency: Duration,
+    requests: Vec<RecordedRequest>,
+}
+
+/// A running stand-in server. It shuts down when dropped.
+pub struct MockServer {
+    url: String,
+    state: Arc<Mutex<State>>,
+    _shutdown: oneshot::Sender<()>,
+}
+
+impl MockServer {
+    /// Starts a server that serves the bundled fixtures for every REST route and RPC method.
+    /// Must be called from within a tokio runtime.
+    pub async fn start() -> MockServer {
+        let server = MockServer::start_empty().await;
+        for (method, path, fixture) in REST_FIXTURES {
+            let method = Method::from_bytes(method.as_bytes()).unwrap();
+            server.mock(method, path, MockResponse::json(parse_fixture(fixture)));
+        }
+        server.mock(
+            Method::DELETE,
+            "/v0/webhooks/{webhook_id}",
+            MockResponse::json(Value::Null),
+        );
+        for (method, fixture) in RPC_FIXTURES {
+            server.mock_rpc(method, MockResponse::json(parse_fixture(fixture)));
+        }
+        server
+    }
+
+    /// Starts a server with no routes; every request is answered with `404 Not Found` until mocked.
+    /// Must be called from within a tokio runtime.
+    pub async fn start_empty() -> MockServer {
+        let state = Arc::new(Mutex::new(State::default()));
+        let service_state = state.clone();
+        let make_service = make_service_fn(move |_| {
+            let state = service_state.clone();
+            async move { Ok::<_, Infallible>(service_fn(move |request| handle(state.clone(), request))) }
+        });
+
+        let address: SocketAddr = ([127, 0, 0, 1], 0).into();
+        let server = Server::bind(&address).serve(make_service);
+        let url = format!("http://{}", server.local_addr());
+        let (shutdown, shutdown_signal) = oneshot::channel::<()>();
+        tokio::spawn(server.with_graceful_shutdown(async {
+            let _ = shutdown_signal.await;
+        }));
+
+        MockServer {
+            url,
+            state,
+            _shutdown: shutdown,
+        }
+    }
+
+    /// The base URL of the server, e.g. `http://127.0.0.1:41234`. Serves both the REST and the JSON-RPC API.
+    pub fn url(&self) -> String {
+        self.url.clone()
+    }
+
+    /// Responds to `method` requests whose path matches `path` with `response`, replacing any earlier mock for the same route.
+    /// `{name}` in `path` matches any single path segment.
+    pub fn mock(&self, method: Method, path: &str, response: MockResponse) {
+        let pattern = route_pattern(path);
+        let mut state = self.state.lock().unwrap();
+        state
+            .routes
+            .retain(|route| !(route.method == method && route.pattern == pattern));
+        state.routes.push(Route {
+            method,
+            pattern,
+            response,
+            once: false,
+        });
+    }
+
+    /// Responds to the next `method` request whose path matches `path` with `response`. Later requests get the
+    /// route's earlier response again. Several calls queue their responses in order.
+    pub fn mock_once(&self, method: Method, path: &str, response: MockResponse) {
+        let pattern = route_pattern(path);
+        let mut state = self.state.lock().unwrap();
+        let position = state
+            .routes
+            .iter()
+            .position(|route| route.once && route.method == method && route.pattern == pattern)
+            .unwrap_or(state.routes.len());
+        state.routes.insert(
+            position,
+            Route {
+                method,
+                pattern,
+                response,
+                once: true,
+            },
+        );
+    }
+
+    /// Responds to JSON-RPC requests for `rpc_method` with `response`.
+    pub fn mock_rpc(&self, rpc_method: &str, response: MockResponse) {
+        self.state
+            .lock()
+            .unwrap()
+            .rpc_methods
+            .insert(rpc_method.to_string(), response);
+    }
+
+    /// Responds to every REST and RPC request carrying `api_key`, in the `api-key` query parameter or the `x-api-key`
+    /// header, with `response`, e.g. a `401` or `429` to simulate a revoked or exhausted key.
+    pub fn reject_key(&self, api_key: &str, response: MockResponse) {
+        self.state
+            .lock()
+            .unwrap()
+            .rejected_keys
+            .insert(api_key.to_string(), response);
+    }
+
+    /// Delays every response by `latency`, in addition to any per-response delay.
+    pub fn set_latency(&self, latency: Duration) {
+        self.state.lock().unwrap().latency = latency;
+    }
+
+    /// Returns every request received so far, in order.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    /// Returns the requests received so far with `method` whose path is `path`.
+    pub fn requests_to(&self, method: Method, path: &str) -> Vec<RecordedRequest> {
+        let path = path.trim_end_matches('/');
+        self.requests()
+            .into_iter()
+            .filter(|request| request.method == method && request.path == path)
+            .collect()
+    }
+
+    /// Returns the JSON-RPC requests received so far for `rpc_method`.
+    pub fn rpc_requests(&self, rpc_method: &str) -> Vec<RecordedRequest> {
+        self.requests()
+            .into_iter()
+            .filter(|request| request.body["method"] == rpc_method)
+            .collect()
+    }
+}
+
+async fn handle(
+    state: Arc<Mutex<State>>,
+    request: Request<Body>,
+) -> Result<Response<Body>, Infallible> {
+    let method = request.method().clone();
+    let path = request.uri().path().trim_end_matches('/').to_string();
+    let query = request.uri().query().map(str::to_string);
+    let headers = request.headers().clone();
+    let api_key = request_api_key(query.as_deref(), &headers);
+    let bytes = hyper::body::to_bytes(request.into_body())
+        .await
+        .unwrap_or_default();
+    let body: Value = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+    let is_rpc = method == Method::POST && body.get("jsonrpc").is_some();
+
+    let (response, latency) = {
+        let mut state = state.lock().unwrap();
+        state.requests.push(RecordedRequest {
+            method: method.clone(),
+            path: path.clone(),
+            query,
+            headers,
+            body: body.clone(),
+        });
+        let rejection = api_key.and_then(|key| state.rejected_keys.get(&key)).cloned();
+        let response = if rejection.is_some() {
+            rejection
+        } else if is_rpc {
+            body["method"]
+                .as_str()
+                .and_then(|rpc_method| state.rpc_methods.get(rpc_method))
+                .cloned()
+        } else {
+            let position = state
+                .routes
+                .iter()
+                .rposition(|route| route.matches(&method, &path));
+            match position {
+                Some(position) if state.routes[position].once => {
+                    Some(state.routes.remove(position).response)
+                }
+                Some(position) => Some(state.routes[position].response.clone()),
+                None => None,
+            }
+        };
+        (response, state.latency)
+    };
+
+    let response = match response {
+        Some(response) => response,
+        None if is_rpc => MockResponse::rpc_error(-32601, "Method not found"),
+        None => MockResponse::error(404, "Not found"),
+    };
+    let delay = latency + response.delay;
+    if !delay.is_zero() {
+        tokio::time::sleep(delay).await;
+    }
+
+    let body = match (is_rpc, &response.rpc_error) {
+        (true, Some((code, message))) => json!({
+            "jsonrpc": "2.0",
+            "id": body["id"],
+            "error": { "code": code, "message": message },
+        }),
+        (true, None) if response.status < 300 => json!({
+            "jsonrpc": "2.0",
+            "id": body["id"],
+            "result": response.body,
+        }),
+        _ => response.body,
+    };
+    let body = match body {
+        Value::Null if !is_rpc => Body::empty(),
+        body => Body::from(body.to_string()),
+    };
+
+    Ok(Response::builder()
+        .status(StatusCode::from_u16(response.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR))
+        .header("Content-Type", "application/json")
+        .body(body)
+        .unwrap())
+}
+
+/// The API key of a request, from the `api-key` query parameter or the `x-api-key` header.
+fn request_api_key(query: Option<&str>, headers: &HeaderMap) -> Option<String> {
+    query
+        .into_iter()
+        .flat_map(|query| query.split('&'))
+        .find_map(|pair| pair.strip_prefix("api-key=").map(str::to_string))
+        .or_else(|| {
+            headers
+                .get("x-api-key")
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        })
+}
+
+/// Splits a path pattern into its segments, ignoring a trailing slash.
+fn route_pattern(path: &str) -> Vec<String> {
+    path.trim_end_matches('/')
+        .split('/')
+        .map(str::to_string)
+        .collect()
+}
+
+fn parse_fixture(contents: &str) -> Value {
+    serde_json::from_str(contents).expect("fixture must be valid JSON")
+}
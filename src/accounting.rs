@@ -0,0 +1,536 @@
+//! Cost basis and profit and loss of a wallet, from its enriched transactions.
+//!
+//! A [`Ledger`] turns the balance changes of its owner in each transaction into acquisitions and disposals:
+//! * assets received and given in the same transaction are a trade, such as a swap or an NFT purchase or sale, valued
+//!   at the market value of what was received, or of what was given when that is not priced;
+//! * assets only received are transfers in, acquired at their market value;
+//! * assets only given are transfers out, disposed of at their market value;
+//! * the network fee paid by the owner is a disposal of SOL without proceeds.
+//!
+//! Acquisitions open lots, and disposals close them in the order of the ledger's [`LotMethod`], realizing the
+//! difference between the proceeds and the cost of the closed lots. Market values come from a [`PriceSource`].
+//! Values are in the currency of the price source, and quantities are raw: lamports for SOL and base units for tokens.
+//! SOL is the mint `None`.
+
+use async_trait::async_trait;
+use futures::{pin_mut, Stream, StreamExt};
+use solana_program::clock::UnixTimestamp;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::error::{ClientError, ClientErrorKind, ClientResult};
+use crate::models::{enriched_transaction::EnrichedTransaction, enums::NftEventType};
+use crate::types::NATIVE_DECIMALS;
+
+/// Prices of SOL and tokens over time.
+#[async_trait]
+pub trait PriceSource: Send + Sync {
+    /// The price of one whole token of `mint`, or of one SOL for `None`, at `timestamp`. `None` if it is unknown.
+    async fn price(
+        &self,
+        mint: Option<&str>,
+        timestamp: UnixTimestamp,
+    ) -> ClientResult<Option<f64>>;
+}
+
+/// A [`PriceSource`] from a table of prices. The price of a mint at a time is the last one set at or before it.
+#[derive(Clone, Debug, Default)]
+pub struct PriceTable {
+    prices: HashMap<Option<String>, BTreeMap<UnixTimestamp, f64>>,
+}
+
+impl PriceTable {
+    pub fn new() -> Self {
+        PriceTable::default()
+    }
+
+    pub fn insert(&mut self, mint: Option<&str>, timestamp: UnixTimestamp, price: f64) {
+        self.prices
+            .entry(mint.map(str::to_string))
+            .or_default()
+            .insert(timestamp, price);
+    }
+
+    pub fn with_price(mut self, mint: Option<&str>, timestamp: UnixTimestamp, price: f64) -> Self {
+        self.insert(mint, timestamp, price);
+        self
+    }
+
+    pub fn get(&self, mint: Option<&str>, timestamp: UnixTimestamp) -> Option<f64> {
+        self.prices
+            .get(&mint.map(str::to_string))?
+            .range(..=timestamp)
+            .next_back()
+            .map(|(_, price)| *price)
+    }
+}
+
+#[async_trait]
+impl PriceSource for PriceTable {
+    async fn price(
+        &self,
+        mint: Option<&str>,
+        timestamp: UnixTimestamp,
+    ) -> ClientResult<Option<f64>> {
+        Ok(self.get(mint, timestamp))
+    }
+}
+
+/// The order in which disposals close lots.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LotMethod {
+    /// First in, first out.
+    Fifo,
+    /// Last in, first out.
+    Lifo,
+    /// Highest cost per unit first.
+    Hifo,
+    /// The lots acquired in the transactions listed for the signature of each disposal, in the order listed, then
+    /// first in, first out.
+    SpecificId(HashMap<String, Vec<String>>),
+}
+
+/// What a transaction was to the owner, as far as its balance changes tell.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Activity {
+    /// Fungible assets received and given in the same transaction.
+    Swap,
+    NftPurchase,
+    NftSale,
+    TransferIn,
+    TransferOut,
+    Fee,
+}
+
+/// Holdings of a mint acquired in a single transaction, of which `quantity` is still held.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Lot {
+    pub mint: Option<String>,
+    /// The signature of the acquiring transaction.
+    pub signature: String,
+    pub timestamp: UnixTimestamp,
+    pub activity: Activity,
+    pub quantity: u128,
+    /// The cost of a single raw unit.
+    pub unit_cost: f64,
+}
+
+impl Lot {
+    pub fn cost(&self) -> f64 {
+        self.unit_cost * self.quantity as f64
+    }
+}
+
+/// The part of a lot closed by a disposal.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LotMatch {
+    /// The signature of the transaction that acquired the lot.
+    pub signature: String,
+    pub quantity: u128,
+    pub cost: f64,
+}
+
+/// A disposal and its gain or loss.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RealizedGain {
+    pub signature: String,
+    pub timestamp: UnixTimestamp,
+    pub activity: Activity,
+    pub mint: Option<String>,
+    pub quantity: u128,
+    pub proceeds: f64,
+    /// The cost of the closed lots.
+    pub cost_basis: f64,
+    pub gain: f64,
+    pub lots: Vec<LotMatch>,
+    /// The quantity disposed of beyond the held lots, e.g. because the history is incomplete. It has no cost.
+    pub unmatched_quantity: u128,
+}
+
+/// The holdings of a mint and their gain or loss at a price.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnrealizedPosition {
+    pub mint: Option<String>,
+    pub quantity: u128,
+    pub cost_basis: f64,
+    /// `None` if the mint has no price.
+    pub market_value: Option<f64>,
+    pub gain: Option<f64>,
+}
+
+/// A balance change that could not be valued because its mint had no price. It was valued at zero.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Unpriced {
+    pub signature: String,
+    pub mint: Option<String>,
+}
+
+/// A balance change of the owner in a transaction, before it is booked.
+struct Leg {
+    mint: Option<String>,
+    quantity: u128,
+    market_value: Option<f64>,
+}
+
+/// The lots and realized gains of `owner`. See the [module documentation](self).
+pub struct Ledger {
+    owner: String,
+    method: LotMethod,
+    /// The open lots of each mint, in acquisition order.
+    lots: HashMap<Option<String>, Vec<Lot>>,
+    realized: Vec<RealizedGain>,
+    unpriced: Vec<Unpriced>,
+    decimals: HashMap<Option<String>, u8>,
+    signatures: HashSet<String>,
+}
+
+impl Ledger {
+    pub fn new<S: Into<String>>(owner: S, method: LotMethod) -> Self {
+        Ledger {
+            owner: owner.into(),
+            method,
+            lots: HashMap::new(),
+            realized: Vec::new(),
+            unpriced: Vec::new(),
+            decimals: HashMap::from([(None, NATIVE_DECIMALS)]),
+            signatures: HashSet::new(),
+        }
+    }
+
+    /// Books the balance changes of the owner in `transaction`, valued with `prices`. Transactions must be applied
+    /// oldest first; a transaction applied before is ignored.
+    pub async fn apply(
+        &mut self,
+        transaction: &EnrichedTransaction,
+        prices: &dyn PriceSource,
+    ) -> ClientResult<()> {
+        if self.signatures.contains(&transaction.signature) {
+            return Ok(());
+        }
+        let timestamp = transaction.timestamp.unwrap_or_default();
+
+        let mut changes: BTreeMap<Option<String>, i128> = BTreeMap::new();
+        let mut decimals = Vec::new();
+        for account in &transaction.account_data {
+            if account.account == self.owner {
+                *changes.entry(None).or_default() += account.native_balance_change;
+            }
+            for change in &account.token_balance_changes {
+                if change.user_account == self.owner {
+                    let token_amount = &change.raw_token_amount.token_amount;
+                    let amount: i128 = token_amount.parse().map_err(|e| {
+                        ClientError::from(ClientErrorKind::Custom(format!(
+                            "invalid amount {} of {} in transaction {}: {}",
+                            token_amount, change.mint, transaction.signature, e
+                        )))
+                    })?;
+                    *changes.entry(Some(change.mint.clone())).or_default() += amount;
+                    decimals.push((Some(change.mint.clone()), change.raw_token_amount.decimals));
+                }
+            }
+        }
+        self.signatures.insert(transaction.signature.clone());
+        self.decimals.extend(decimals);
+        let fee = if transaction.fee_payer == self.owner {
+            transaction.fee
+        } else {
+            0
+        };
+        if fee > 0 {
+            *changes.entry(None).or_default() += fee as i128;
+        }
+
+        let mut received = Vec::new();
+        let mut given = Vec::new();
+        for (mint, change) in changes {
+            if change == 0 {
+                continue;
+            }
+            let market_value = self
+                .market_value(
+                    prices,
+                    &transaction.signature,
+                    mint.as_deref(),
+                    change.unsigned_abs(),
+                    timestamp,
+                )
+                .await?;
+            let leg = Leg {
+                mint,
+                quantity: change.unsigned_abs(),
+                market_value,
+            };
+            if change > 0 {
+                received.push(leg);
+            } else {
+                given.push(leg);
+            }
+        }
+
+        let activity = self.activity(transaction, &received, &given);
+        let (costs, proceeds) = if !received.is_empty() && !given.is_empty() {
+            let value = trade_value(&received, &given);
+            (allocate(value, &received), allocate(value, &given))
+        } else {
+            (market_values(&received), market_values(&given))
+        };
+        for (leg, proceeds) in given.iter().zip(proceeds) {
+            self.dispose(
+                transaction,
+                timestamp,
+                activity,
+                leg.mint.clone(),
+                leg.quantity,
+                proceeds,
+            );
+        }
+        for (leg, cost) in received.into_iter().zip(costs) {
+            self.lots.entry(leg.mint.clone()).or_default().push(Lot {
+                mint: leg.mint,
+                signature: transaction.signature.clone(),
+                timestamp,
+                activity,
+                quantity: leg.quantity,
+                unit_cost: cost / leg.quantity as f64,
+            });
+        }
+        if fee > 0 {
+            self.dispose(
+                transaction,
+                timestamp,
+                Activity::Fee,
+                None,
+                fee as u128,
+                0.0,
+            );
+        }
+        Ok(())
+    }
+
+    /// Applies every transaction of `transactions` in slot order and returns how many there were.
+    pub async fn replay<S>(
+        &mut self,
+        transactions: S,
+        prices: &dyn PriceSource,
+    ) -> ClientResult<usize>
+    where
+        S: Stream<Item = ClientResult<EnrichedTransaction>>,
+    {
+        pin_mut!(transactions);
+        let mut collected = Vec::new();
+        while let Some(transaction) = transactions.next().await {
+            collected.push(transaction?);
+        }
+        collected.sort_by_key(|transaction| transaction.slot);
+        for transaction in &collected {
+            self.apply(transaction, prices).await?;
+        }
+        Ok(collected.len())
+    }
+
+    pub fn owner(&self) -> &str {
+        &self.owner
+    }
+
+    /// The open lots of `mint`, in acquisition order.
+    pub fn lots(&self, mint: Option<&str>) -> &[Lot] {
+        self.lots
+            .get(&mint.map(str::to_string))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Every disposal so far, in the order they happened.
+    pub fn realized(&self) -> &[RealizedGain] {
+        &self.realized
+    }
+
+    pub fn realized_gain(&self) -> f64 {
+        self.realized.iter().map(|gain| gain.gain).sum()
+    }
+
+    /// The balance changes valued at zero for lack of a price.
+    pub fn unpriced(&self) -> &[Unpriced] {
+        &self.unpriced
+    }
+
+    /// The open lots of every mint, valued at `timestamp`, SOL first.
+    pub async fn unrealized(
+        &self,
+        prices: &dyn PriceSource,
+        timestamp: UnixTimestamp,
+    ) -> ClientResult<Vec<UnrealizedPosition>> {
+        let mut mints: Vec<&Option<String>> = self
+            .lots
+            .iter()
+            .filter(|(_, lots)| !lots.is_empty())
+            .map(|(mint, _)| mint)
+            .collect();
+        mints.sort();
+
+        let mut positions = Vec::new();
+        for mint in mints {
+            let lots = &self.lots[mint];
+            let quantity: u128 = lots.iter().map(|lot| lot.quantity).sum();
+            let cost_basis: f64 = lots.iter().map(Lot::cost).sum();
+            let market_value = prices
+                .price(mint.as_deref(), timestamp)
+                .await?
+                .map(|price| price * self.whole_units(mint, quantity));
+            positions.push(UnrealizedPosition {
+                mint: mint.clone(),
+                quantity,
+                cost_basis,
+                market_value,
+                gain: market_value.map(|value| value - cost_basis),
+            });
+        }
+        Ok(positions)
+    }
+
+    async fn market_value(
+        &mut self,
+        prices: &dyn PriceSource,
+        signature: &str,
+        mint: Option<&str>,
+        quantity: u128,
+        timestamp: UnixTimestamp,
+    ) -> ClientResult<Option<f64>> {
+        let key = mint.map(str::to_string);
+        let price = prices.price(mint, timestamp).await?;
+        if price.is_none() {
+            self.unpriced.push(Unpriced {
+                signature: signature.to_string(),
+                mint: key.clone(),
+            });
+        }
+        Ok(price.map(|price| price * self.whole_units(&key, quantity)))
+    }
+
+    fn whole_units(&self, mint: &Option<String>, quantity: u128) -> f64 {
+        let decimals = self.decimals.get(mint).copied().unwrap_or_default();
+        quantity as f64 / 10f64.powi(decimals as i32)
+    }
+
+    fn activity(
+        &self,
+        transaction: &EnrichedTransaction,
+        received: &[Leg],
+        given: &[Leg],
+    ) -> Activity {
+        if let Some(event) = transaction
+            .events
+            .nft
+            .as_ref()
+            .filter(|event| event.event_type == NftEventType::NFT_SALE)
+        {
+            if event.seller == self.owner {
+                return Activity::NftSale;
+            }
+            if event.buyer == self.owner {
+                return Activity::NftPurchase;
+            }
+        }
+        match (received.is_empty(), given.is_empty()) {
+            (false, false) => Activity::Swap,
+            (false, true) => Activity::TransferIn,
+            _ => Activity::TransferOut,
+        }
+    }
+
+    fn dispose(
+        &mut self,
+        transaction: &EnrichedTransaction,
+        timestamp: UnixTimestamp,
+        activity: Activity,
+        mint: Option<String>,
+        quantity: u128,
+        proceeds: f64,
+    ) {
+        let lots = self.lots.entry(mint.clone()).or_default();
+        let order = lot_order(&self.method, lots, &transaction.signature);
+
+        let mut remaining = quantity;
+        let mut matches = Vec::new();
+        for index in order {
+            if remaining == 0 {
+                break;
+            }
+            let lot = &mut lots[index];
+            let closed = remaining.min(lot.quantity);
+            lot.quantity -= closed;
+            remaining -= closed;
+            matches.push(LotMatch {
+                signature: lot.signature.clone(),
+                quantity: closed,
+                cost: lot.unit_cost * closed as f64,
+            });
+        }
+        lots.retain(|lot| lot.quantity > 0);
+
+        let cost_basis = matches.iter().map(|lot| lot.cost).sum();
+        self.realized.push(RealizedGain {
+            signature: transaction.signature.clone(),
+            timestamp,
+            activity,
+            mint,
+            quantity,
+            proceeds,
+            cost_basis,
+            gain: proceeds - cost_basis,
+            lots: matches,
+            unmatched_quantity: remaining,
+        });
+    }
+}
+
+/// The indices of `lots` in the order a disposal in the transaction `signature` closes them.
+fn lot_order(method: &LotMethod, lots: &[Lot], signature: &str) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..lots.len()).collect();
+    match method {
+        LotMethod::Fifo => {}
+        LotMethod::Lifo => order.reverse(),
+        LotMethod::Hifo => {
+            order.sort_by(|&a, &b| lots[b].unit_cost.total_cmp(&lots[a].unit_cost));
+        }
+        LotMethod::SpecificId(selections) => {
+            let selected = selections
+                .get(signature)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            let rank = |index: &usize| {
+                selected
+                    .iter()
+                    .position(|signature| *signature == lots[*index].signature)
+                    .unwrap_or(selected.len())
+            };
+            order.sort_by_key(rank);
+        }
+    }
+    order
+}
+
+/// The value of a trade: the market value of what was received, or of what was given if that is not fully priced.
+fn trade_value(received: &[Leg], given: &[Leg]) -> f64 {
+    let total = |legs: &[Leg]| -> Option<f64> { legs.iter().map(|leg| leg.market_value).sum() };
+    total(received)
+        .or_else(|| total(given))
+        .unwrap_or_else(|| market_values(received).iter().sum())
+}
+
+/// Splits `value` between `legs` in proportion to their market values, or evenly if they are not all priced.
+fn allocate(value: f64, legs: &[Leg]) -> Vec<f64> {
+    let values: Option<Vec<f64>> = legs.iter().map(|leg| leg.market_value).collect();
+    match values {
+        Some(values) if values.iter().sum::<f64>() > 0.0 => {
+            let total: f64 = values.iter().sum();
+            values.iter().map(|v| value * v / total).collect()
+        }
+        _ => vec![value / legs.len() as f64; legs.len()],
+    }
+}
+
+fn market_values(legs: &[Leg]) -> Vec<f64> {
+    legs.iter()
+        .map(|leg| leg.market_value.unwrap_or_default())
+        .collect()
+}
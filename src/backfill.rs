@@ -0,0 +1,503 @@
+//! Resumable walks over the raw transaction history of addresses.
+//!
+//! A [`Backfill`] job pages through [`get_transactions_for_address`](HeliusApi::get_transactions_for_address) from the
+//! newest transaction of each address to its first one, hands every transaction to a [`BackfillHandler`], and records
+//! its progress in a [`CursorStore`] under the job name and address. A job that fails or is stopped resumes from its
+//! last checkpoint when it is run again.
+//!
+//! Delivery is at-least-once: a transaction is only checkpointed once the handler returned for it and every transaction
+//! before it. Transactions acknowledged with [`Ack::Defer`] are checkpointed at the end of their page or when the job
+//! fails or stops, so a crashed process redelivers the rest of the page. Those acknowledged with [`Ack::Commit`] are
+//! checkpointed before the next one is delivered, which makes delivery exactly-once unless the process dies between
+//! the handler returning and the checkpoint being saved.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use solana_program::pubkey::Pubkey;
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, AtomicU8, Ordering},
+        Arc, Mutex,
+    },
+};
+use tokio::sync::Notify;
+
+use crate::client::api::HeliusApi;
+use crate::error::{ClientError, ClientErrorKind, ClientResult};
+use crate::models::enriched_transaction::{parse_signature, MAX_TRANSACTIONS_PER_PAGE};
+use crate::models::raw_transaction::{GetRawTransactionsRequestConfig, RawTransaction};
+use crate::types::CommitmentLevel;
+
+/// The progress of a job over the history of an address.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackfillCursor {
+    /// The signature of the last checkpointed transaction. The job resumes with the transactions before it.
+    pub before: Option<String>,
+    /// Whether the job reached the first transaction of the address.
+    pub completed: bool,
+}
+
+/// Persists [`BackfillCursor`]s by job name and address.
+#[async_trait]
+pub trait CursorStore: Send + Sync {
+    async fn load(&self, job: &str, address: &str) -> ClientResult<Option<BackfillCursor>>;
+
+    async fn save(&self, job: &str, address: &str, cursor: &BackfillCursor) -> ClientResult<()>;
+
+    /// Forgets the progress of `job` for `address`, so the next run starts over.
+    async fn clear(&self, job: &str, address: &str) -> ClientResult<()>;
+}
+
+fn cursor_key(job: &str, address: &str) -> String {
+    format!("{}/{}", job, address)
+}
+
+/// A [`CursorStore`] in memory, which forgets every cursor on restart.
+#[derive(Default)]
+pub struct InMemoryCursorStore {
+    cursors: Mutex<HashMap<String, BackfillCursor>>,
+}
+
+impl InMemoryCursorStore {
+    pub fn new() -> Self {
+        InMemoryCursorStore::default()
+    }
+}
+
+#[async_trait]
+impl CursorStore for InMemoryCursorStore {
+    async fn load(&self, job: &str, address: &str) -> ClientResult<Option<BackfillCursor>> {
+        Ok(self
+            .cursors
+            .lock()
+            .unwrap()
+            .get(&cursor_key(job, address))
+            .cloned())
+    }
+
+    async fn save(&self, job: &str, address: &str, cursor: &BackfillCursor) -> ClientResult<()> {
+        self.cursors
+            .lock()
+            .unwrap()
+            .insert(cursor_key(job, address), cursor.clone());
+        Ok(())
+    }
+
+    async fn clear(&self, job: &str, address: &str) -> ClientResult<()> {
+        self.cursors
+            .lock()
+            .unwrap()
+            .remove(&cursor_key(job, address));
+        Ok(())
+    }
+}
+
+/// A [`CursorStore`] in a JSON file. Every save rewrites the file through a temporary file and a rename, so a crash
+/// leaves either the old or the new cursors.
+pub struct FileCursorStore {
+    path: PathBuf,
+    /// Held while the file is written, so concurrent saves reach the file in order.
+    cursors: tokio::sync::Mutex<HashMap<String, BackfillCursor>>,
+}
+
+impl FileCursorStore {
+    /// Opens the store at `path`, which is created on the first save if it does not exist.
+    pub fn open<P: Into<PathBuf>>(path: P) -> ClientResult<Self> {
+        let path = path.into();
+        let cursors = match fs::read(&path) {
+            Ok(contents) => serde_json::from_slice(&contents)
+                .map_err(|e| ClientError::from(ClientErrorKind::SerdeJson(e)))?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => return Err(ClientError::from(ClientErrorKind::Io(e))),
+        };
+        Ok(FileCursorStore {
+            path,
+            cursors: tokio::sync::Mutex::new(cursors),
+        })
+    }
+
+    async fn write(&self, cursors: &HashMap<String, BackfillCursor>) -> ClientResult<()> {
+        let contents = serde_json::to_vec_pretty(cursors)
+            .map_err(|e| ClientError::from(ClientErrorKind::SerdeJson(e)))?;
+        let temp_path = self.path.with_extension("tmp");
+        tokio::fs::write(&temp_path, contents)
+            .await
+            .map_err(|e| ClientError::from(ClientErrorKind::Io(e)))?;
+        tokio::fs::rename(&temp_path, &self.path)
+            .await
+            .map_err(|e| ClientError::from(ClientErrorKind::Io(e)))
+    }
+}
+
+#[async_trait]
+impl CursorStore for FileCursorStore {
+    async fn load(&self, job: &str, address: &str) -> ClientResult<Option<BackfillCursor>> {
+        Ok(self
+            .cursors
+            .lock()
+            .await
+            .get(&cursor_key(job, address))
+            .cloned())
+    }
+
+    async fn save(&self, job: &str, address: &str, cursor: &BackfillCursor) -> ClientResult<()> {
+        let mut cursors = self.cursors.lock().await;
+        cursors.insert(cursor_key(job, address), cursor.clone());
+        self.write(&cursors).await
+    }
+
+    async fn clear(&self, job: &str, address: &str) -> ClientResult<()> {
+        let mut cursors = self.cursors.lock().await;
+        if cursors.remove(&cursor_key(job, address)).is_some() {
+            self.write(&cursors).await?;
+        }
+        Ok(())
+    }
+}
+
+/// A [`CursorStore`] in a `backfill_cursors` table of a SQLite database, which can be the database of a
+/// [`SqliteIndexStore`](crate::indexer::sqlite::SqliteIndexStore). Enabled with the `sqlite` feature.
+#[cfg(feature = "sqlite")]
+#[derive(Clone)]
+pub struct SqliteCursorStore {
+    connection: crate::sqlite::SqliteConnection,
+}
+
+#[cfg(feature = "sqlite")]
+const SQLITE_CURSOR_SCHEMA: &str = "CREATE TABLE IF NOT EXISTS backfill_cursors (
+    job TEXT NOT NULL,
+    address TEXT NOT NULL,
+    before TEXT,
+    completed BOOLEAN NOT NULL,
+    PRIMARY KEY (job, address)
+);";
+
+#[cfg(feature = "sqlite")]
+impl SqliteCursorStore {
+    /// Opens (or creates) the database at `path`, creating the `backfill_cursors` table if needed.
+    pub fn open<P: AsRef<std::path::Path>>(path: P) -> ClientResult<Self> {
+        Ok(SqliteCursorStore {
+            connection: crate::sqlite::SqliteConnection::open(path, SQLITE_CURSOR_SCHEMA)?,
+        })
+    }
+
+    /// Opens a database in memory, which is lost when the store is dropped.
+    pub fn open_in_memory() -> ClientResult<Self> {
+        Ok(SqliteCursorStore {
+            connection: crate::sqlite::SqliteConnection::open_in_memory(SQLITE_CURSOR_SCHEMA)?,
+        })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+#[async_trait]
+impl CursorStore for SqliteCursorStore {
+    async fn load(&self, job: &str, address: &str) -> ClientResult<Option<BackfillCursor>> {
+        use rusqlite::OptionalExtension;
+
+        let (job, address) = (job.to_string(), address.to_string());
+        self.connection
+            .call(move |connection| {
+                connection
+                    .query_row(
+                        "SELECT before, completed FROM backfill_cursors WHERE job = ?1 AND address = ?2",
+                        rusqlite::params![job, address],
+                        |row| {
+                            Ok(BackfillCursor {
+                                before: row.get(0)?,
+                                completed: row.get(1)?,
+                            })
+                        },
+                    )
+                    .optional()
+            })
+            .await
+    }
+
+    async fn save(&self, job: &str, address: &str, cursor: &BackfillCursor) -> ClientResult<()> {
+        let (job, address, cursor) = (job.to_string(), address.to_string(), cursor.clone());
+        self.connection
+            .call(move |connection| {
+                connection.execute(
+                    "INSERT INTO backfill_cursors (job, address, before, completed) VALUES (?1, ?2, ?3, ?4)
+                     ON CONFLICT (job, address) DO UPDATE SET
+                        before = excluded.before, completed = excluded.completed",
+                    rusqlite::params![job, address, cursor.before, cursor.completed],
+                )?;
+                Ok(())
+            })
+            .await
+    }
+
+    async fn clear(&self, job: &str, address: &str) -> ClientResult<()> {
+        let (job, address) = (job.to_string(), address.to_string());
+        self.connection
+            .call(move |connection| {
+                connection.execute(
+                    "DELETE FROM backfill_cursors WHERE job = ?1 AND address = ?2",
+                    rusqlite::params![job, address],
+                )?;
+                Ok(())
+            })
+            .await
+    }
+}
+
+/// How a [`BackfillHandler`] acknowledges a transaction.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Ack {
+    /// Checkpoint this transaction before delivering the next one.
+    Commit,
+    /// Checkpoint this transaction with the rest of its page.
+    Defer,
+}
+
+/// Receives the transactions of a [`Backfill`], newest first for each address. An error stops the job; the transaction
+/// and those after it are delivered again on the next run.
+#[async_trait]
+pub trait BackfillHandler: Send + Sync {
+    async fn handle(&self, address: &Pubkey, transaction: &RawTransaction) -> ClientResult<Ack>;
+}
+
+const RUNNING: u8 = 0;
+const PAUSED: u8 = 1;
+const STOPPED: u8 = 2;
+
+/// Pauses, resumes or stops a running [`Backfill`] from another task. The job checks it between transactions.
+#[derive(Clone)]
+pub struct BackfillControl {
+    state: Arc<AtomicU8>,
+    changed: Arc<Notify>,
+}
+
+impl BackfillControl {
+    fn new() -> Self {
+        BackfillControl {
+            state: Arc::new(AtomicU8::new(RUNNING)),
+            changed: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Holds the job before its next transaction until [`resume`](Self::resume) or [`stop`](Self::stop).
+    pub fn pause(&self) {
+        let _ = self
+            .state
+            .compare_exchange(RUNNING, PAUSED, Ordering::SeqCst, Ordering::SeqCst);
+    }
+
+    pub fn resume(&self) {
+        let _ = self
+            .state
+            .compare_exchange(PAUSED, RUNNING, Ordering::SeqCst, Ordering::SeqCst);
+        self.changed.notify_waiters();
+    }
+
+    /// Checkpoints the job and makes it return before its next transaction. Stopped jobs can be run again.
+    pub fn stop(&self) {
+        self.state.store(STOPPED, Ordering::SeqCst);
+        self.changed.notify_waiters();
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.state.load(Ordering::SeqCst) == PAUSED
+    }
+
+    /// Waits while paused and returns whether the job should go on.
+    async fn proceed(&self) -> bool {
+        loop {
+            let changed = self.changed.notified();
+            match self.state.load(Ordering::SeqCst) {
+                PAUSED => changed.await,
+                state => return state == RUNNING,
+            }
+        }
+    }
+}
+
+/// The result of a [`Backfill::run`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BackfillOutcome {
+    /// The number of transactions delivered by this run.
+    pub delivered: usize,
+    /// Whether every address was walked to its first transaction, rather than the job being stopped.
+    pub completed: bool,
+}
+
+/// A named, resumable walk over the history of `addresses`. See the [module documentation](self).
+pub struct Backfill {
+    api: Arc<dyn HeliusApi>,
+    store: Arc<dyn CursorStore>,
+    job: String,
+    addresses: Vec<Pubkey>,
+    page_size: usize,
+    commitment: Option<CommitmentLevel>,
+    control: BackfillControl,
+    running: AtomicBool,
+}
+
+/// Clears the running flag of a [`Backfill`] when its run ends, also when the future of the run is dropped.
+struct RunningGuard<'a>(&'a AtomicBool);
+
+impl Drop for RunningGuard<'_> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+}
+
+impl Backfill {
+    /// Creates the job `job`, whose cursors are kept apart from those of other jobs over the same addresses.
+    pub fn new<S: Into<String>>(
+        api: Arc<dyn HeliusApi>,
+        store: Arc<dyn CursorStore>,
+        job: S,
+        addresses: Vec<Pubkey>,
+    ) -> Self {
+        Backfill {
+            api,
+            store,
+            job: job.into(),
+            addresses,
+            page_size: MAX_TRANSACTIONS_PER_PAGE,
+            commitment: None,
+            control: BackfillControl::new(),
+            running: AtomicBool::new(false),
+        }
+    }
+
+    pub fn with_page_size(mut self, page_size: usize) -> Self {
+        self.page_size = page_size.max(1);
+        self
+    }
+
+    pub fn with_commitment(mut self, commitment: CommitmentLevel) -> Self {
+        self.commitment = Some(commitment);
+        self
+    }
+
+    pub fn job(&self) -> &str {
+        &self.job
+    }
+
+    pub fn control(&self) -> BackfillControl {
+        self.control.clone()
+    }
+
+    /// Delivers the transactions of every address that the job has not completed yet, resuming from the saved cursors.
+    /// Fails if the job is already running.
+    pub async fn run(&self, handler: &dyn BackfillHandler) -> ClientResult<BackfillOutcome> {
+        if self.running.swap(true, Ordering::SeqCst) {
+            return Err(ClientError::from(ClientErrorKind::Custom(format!(
+                "backfill {} is already running",
+                self.job
+            ))));
+        }
+        let _running = RunningGuard(&self.running);
+        let _ = self.control.state.compare_exchange(
+            STOPPED,
+            RUNNING,
+            Ordering::SeqCst,
+            Ordering::SeqCst,
+        );
+        self.run_addresses(handler).await
+    }
+
+    /// Forgets the progress of the job, so the next run starts over.
+    pub async fn reset(&self) -> ClientResult<()> {
+        for address in &self.addresses {
+            self.store.clear(&self.job, &address.to_string()).await?;
+        }
+        Ok(())
+    }
+
+    async fn run_addresses(&self, handler: &dyn BackfillHandler) -> ClientResult<BackfillOutcome> {
+        let mut outcome = BackfillOutcome {
+            delivered: 0,
+            completed: true,
+        };
+        for address in &self.addresses {
+            let completed = self
+                .run_address(address, handler, &mut outcome.delivered)
+                .await?;
+            if !completed {
+                outcome.completed = false;
+                break;
+            }
+        }
+        Ok(outcome)
+    }
+
+    /// Walks the history of `address` and returns whether it reached the first transaction.
+    async fn run_address(
+        &self,
+        address: &Pubkey,
+        handler: &dyn BackfillHandler,
+        delivered: &mut usize,
+    ) -> ClientResult<bool> {
+        let key = address.to_string();
+        let mut cursor = self.store.load(&self.job, &key).await?.unwrap_or_default();
+        while !cursor.completed {
+            let page = self.fetch_page(address, cursor.before.as_deref()).await?;
+            // The signature of the last transaction handled but not yet checkpointed.
+            let mut pending = None;
+            for transaction in &page {
+                if !self.control.proceed().await {
+                    self.checkpoint(&key, &mut cursor, pending.take()).await?;
+                    return Ok(false);
+                }
+                let ack = match handler.handle(address, transaction).await {
+                    Ok(ack) => ack,
+                    Err(e) => {
+                        self.checkpoint(&key, &mut cursor, pending.take()).await?;
+                        return Err(e);
+                    }
+                };
+                *delivered += 1;
+                pending = transaction.transaction.signatures.first().cloned();
+                if ack == Ack::Commit {
+                    self.checkpoint(&key, &mut cursor, pending.take()).await?;
+                }
+            }
+            cursor.completed = page.len() < self.page_size;
+            let last = page
+                .last()
+                .and_then(|transaction| transaction.transaction.signatures.first().cloned());
+            cursor.before = last.or(cursor.before.take());
+            self.store.save(&self.job, &key, &cursor).await?;
+        }
+        Ok(true)
+    }
+
+    async fn checkpoint(
+        &self,
+        address: &str,
+        cursor: &mut BackfillCursor,
+        signature: Option<String>,
+    ) -> ClientResult<()> {
+        match signature {
+            Some(signature) => {
+                cursor.before = Some(signature);
+                self.store.save(&self.job, address, cursor).await
+            }
+            None => Ok(()),
+        }
+    }
+
+    async fn fetch_page(
+        &self,
+        address: &Pubkey,
+        before: Option<&str>,
+    ) -> ClientResult<Vec<RawTransaction>> {
+        let before = before.map(parse_signature).transpose()?;
+        let config = GetRawTransactionsRequestConfig {
+            address: *address,
+            before,
+            until: None,
+            limit: Some(self.page_size),
+            commitment: self.commitment,
+        };
+        self.api.get_transactions_for_address(config).await
+    }
+}
@@ -0,0 +1,258 @@
+//! Reconstructs the SOL and token balances of a wallet over time from the `account_data` of its enriched transactions.
+//!
+//! A [`BalanceTracker`] collects the balance changes of its owner from transactions in any order, and answers the
+//! balance of SOL or of a mint after any slot or at any time. Balances are relative to the opening balances, zero
+//! unless set, so replaying the full history of a wallet gives its actual balances. For a partial history,
+//! [`anchor`](BalanceTracker::anchor) the tracker to the current balances from `get_token_balances` instead.
+//!
+//! Amounts are raw: lamports for SOL and base units for tokens. SOL is the mint `None`.
+
+use futures::{pin_mut, Stream, StreamExt};
+use solana_program::{clock::UnixTimestamp, slot_history::Slot};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::client::api::HeliusApi;
+use crate::error::{ClientError, ClientErrorKind, ClientResult};
+use crate::models::{enriched_transaction::EnrichedTransaction, nft::TokenBalancesResponse};
+
+/// A change of a balance of the owner in a transaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Change {
+    slot: Slot,
+    timestamp: Option<UnixTimestamp>,
+    signature: String,
+    mint: Option<String>,
+    amount: i128,
+}
+
+/// A balance of the owner right after a transaction that changed it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BalancePoint {
+    pub slot: Slot,
+    pub timestamp: Option<UnixTimestamp>,
+    pub signature: String,
+    pub change: i128,
+    pub balance: i128,
+}
+
+/// A balance that differs between a [`BalanceTracker`] and the API.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BalanceMismatch {
+    /// The mint, or `None` for SOL.
+    pub mint: Option<String>,
+    pub reconstructed: i128,
+    pub actual: i128,
+}
+
+/// Replays the balance changes of `owner`. See the [module documentation](self).
+#[derive(Clone, Debug)]
+pub struct BalanceTracker {
+    owner: String,
+    /// Ordered by slot, then by the order the transactions were applied in.
+    changes: Vec<Change>,
+    signatures: HashSet<String>,
+    opening: HashMap<Option<String>, i128>,
+}
+
+impl BalanceTracker {
+    pub fn new<S: Into<String>>(owner: S) -> Self {
+        BalanceTracker {
+            owner: owner.into(),
+            changes: Vec::new(),
+            signatures: HashSet::new(),
+            opening: HashMap::new(),
+        }
+    }
+
+    pub fn owner(&self) -> &str {
+        &self.owner
+    }
+
+    /// Records the balance changes of the owner in `transaction`. Returns `false` if it was applied before.
+    /// Fails without recording anything if a token amount of the owner is not an integer.
+    pub fn apply(&mut self, transaction: &EnrichedTransaction) -> ClientResult<bool> {
+        if self.signatures.contains(&transaction.signature) {
+            return Ok(false);
+        }
+        let mut amounts: Vec<(Option<String>, i128)> = Vec::new();
+        for account in &transaction.account_data {
+            if account.account == self.owner && account.native_balance_change != 0 {
+                amounts.push((None, account.native_balance_change));
+            }
+            for change in &account.token_balance_changes {
+                if change.user_account == self.owner {
+                    let token_amount = &change.raw_token_amount.token_amount;
+                    let amount = token_amount.parse().map_err(|e| {
+                        ClientError::from(ClientErrorKind::Custom(format!(
+                            "invalid amount {} of {} in transaction {}: {}",
+                            token_amount, change.mint, transaction.signature, e
+                        )))
+                    })?;
+                    amounts.push((Some(change.mint.clone()), amount));
+                }
+            }
+        }
+        self.signatures.insert(transaction.signature.clone());
+
+        let position = self
+            .changes
+            .partition_point(|change| change.slot <= transaction.slot);
+        let changes = amounts.into_iter().map(|(mint, amount)| Change {
+            slot: transaction.slot,
+            timestamp: transaction.timestamp,
+            signature: transaction.signature.clone(),
+            mint,
+            amount,
+        });
+        self.changes.splice(position..position, changes);
+        Ok(true)
+    }
+
+    /// Applies every transaction of `transactions` and returns the number of new ones. Stops at the first error.
+    pub async fn replay<S>(&mut self, transactions: S) -> ClientResult<usize>
+    where
+        S: Stream<Item = ClientResult<EnrichedTransaction>>,
+    {
+        pin_mut!(transactions);
+        let mut count = 0;
+        while let Some(transaction) = transactions.next().await {
+            if self.apply(&transaction?)? {
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// The mints whose balance changed or was set, in order.
+    pub fn mints(&self) -> Vec<String> {
+        self.changes
+            .iter()
+            .filter_map(|change| change.mint.clone())
+            .chain(self.opening.keys().flatten().cloned())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// Sets the balance of `mint` before the first applied transaction.
+    pub fn set_opening_balance(&mut self, mint: Option<&str>, balance: i128) {
+        self.opening.insert(mint.map(str::to_string), balance);
+    }
+
+    pub fn opening_balance(&self, mint: Option<&str>) -> i128 {
+        self.opening
+            .get(&mint.map(str::to_string))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// The balance of `mint` after every applied transaction.
+    pub fn balance(&self, mint: Option<&str>) -> i128 {
+        self.balance_where(mint, |_| true)
+    }
+
+    /// The balance of `mint` after the applied transactions up to and including `slot`.
+    pub fn balance_at_slot(&self, mint: Option<&str>, slot: Slot) -> i128 {
+        self.balance_where(mint, |change| change.slot <= slot)
+    }
+
+    /// The balance of `mint` at `timestamp`, after the applied transactions of the slots up to the last one with a
+    /// transaction at or before `timestamp`.
+    pub fn balance_at_time(&self, mint: Option<&str>, timestamp: UnixTimestamp) -> i128 {
+        let slot = self
+            .changes
+            .iter()
+            .filter(|change| change.timestamp.is_some_and(|time| time <= timestamp))
+            .map(|change| change.slot)
+            .max();
+        match slot {
+            Some(slot) => self.balance_at_slot(mint, slot),
+            None => self.opening_balance(mint),
+        }
+    }
+
+    /// The balance of `mint` after each applied transaction that changed it, oldest first.
+    pub fn history(&self, mint: Option<&str>) -> Vec<BalancePoint> {
+        let mut balance = self.opening_balance(mint);
+        self.changes
+            .iter()
+            .filter(|change| change.mint.as_deref() == mint)
+            .map(|change| {
+                balance += change.amount;
+                BalancePoint {
+                    slot: change.slot,
+                    timestamp: change.timestamp,
+                    signature: change.signature.clone(),
+                    change: change.amount,
+                    balance,
+                }
+            })
+            .collect()
+    }
+
+    /// Sets the opening balances so that the current balances match `head`, the response of `get_token_balances` for
+    /// the owner after the last applied transaction. Mints missing from `head` end at zero.
+    pub fn anchor(&mut self, head: &TokenBalancesResponse) {
+        let actual = actual_balances(head);
+        for mint in self.balance_keys(&actual) {
+            let change = self.balance(mint.as_deref()) - self.opening_balance(mint.as_deref());
+            let balance = actual.get(&mint).copied().unwrap_or_default();
+            self.opening.insert(mint, balance - change);
+        }
+    }
+
+    /// Compares the current balances with `head`, the response of `get_token_balances` for the owner after the last
+    /// applied transaction, and returns those that differ.
+    pub fn validate(&self, head: &TokenBalancesResponse) -> Vec<BalanceMismatch> {
+        let actual = actual_balances(head);
+        self.balance_keys(&actual)
+            .into_iter()
+            .filter_map(|mint| {
+                let reconstructed = self.balance(mint.as_deref());
+                let actual = actual.get(&mint).copied().unwrap_or_default();
+                (reconstructed != actual).then_some(BalanceMismatch {
+                    mint,
+                    reconstructed,
+                    actual,
+                })
+            })
+            .collect()
+    }
+
+    /// Fetches the current balances of the owner and [`validate`](Self::validate)s against them.
+    pub async fn validate_with(&self, api: &dyn HeliusApi) -> ClientResult<Vec<BalanceMismatch>> {
+        let head = api.get_token_balances(self.owner.clone()).await?;
+        Ok(self.validate(&head))
+    }
+
+    fn balance_where<F: Fn(&Change) -> bool>(&self, mint: Option<&str>, include: F) -> i128 {
+        self.opening_balance(mint)
+            + self
+                .changes
+                .iter()
+                .filter(|change| change.mint.as_deref() == mint && include(change))
+                .map(|change| change.amount)
+                .sum::<i128>()
+    }
+
+    /// SOL, then every mint of the tracker or of `actual`.
+    fn balance_keys(&self, actual: &HashMap<Option<String>, i128>) -> Vec<Option<String>> {
+        let mints: BTreeSet<String> = self
+            .mints()
+            .into_iter()
+            .chain(actual.keys().flatten().cloned())
+            .collect();
+        std::iter::once(None)
+            .chain(mints.into_iter().map(Some))
+            .collect()
+    }
+}
+
+/// The balances of `head` by mint, summing the token accounts of each mint.
+fn actual_balances(head: &TokenBalancesResponse) -> HashMap<Option<String>, i128> {
+    let mut balances = HashMap::from([(None, head.native_balance as i128)]);
+    for token in &head.tokens {
+        *balances.entry(Some(token.mint.clone())).or_default() += token.amount as i128;
+    }
+    balances
+}
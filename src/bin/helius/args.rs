@@ -0,0 +1,189 @@
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use helius_rust_client::{
+    client::init::{DEVNET_RPC_URL, MAINNET_RPC_URL},
+    models::enums::{TransactionSource, TransactionType},
+    types::{ClusterType, CommitmentLevel, Pubkey, Signature},
+};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::Value;
+use std::path::PathBuf;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "helius",
+    version,
+    about = "Query the Helius APIs and manage webhooks"
+)]
+pub struct Cli {
+    /// The Helius API key. Takes precedence over the config file.
+    #[arg(long, env = "HELIUS_API_KEY", hide_env_values = true, global = true)]
+    pub api_key: Option<String>,
+    /// A YAML or JSON file with `api_key`, `cluster` and `api_url`. Defaults to `~/.config/helius/config.yaml`.
+    #[arg(long, env = "HELIUS_CONFIG", global = true)]
+    pub config: Option<PathBuf>,
+    /// Defaults to mainnet.
+    #[arg(long, value_enum, global = true)]
+    pub cluster: Option<Cluster>,
+    /// The base URL of the REST API, e.g. a proxy.
+    #[arg(long, env = "HELIUS_API_URL", global = true)]
+    pub api_url: Option<String>,
+    #[arg(long, short, value_enum, default_value_t = OutputFormat::Table, global = true)]
+    pub output: OutputFormat,
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Cluster {
+    Mainnet,
+    Devnet,
+}
+
+impl Cluster {
+    /// The RPC URL of the cluster without the `api-key` query parameter.
+    pub fn rpc_url(&self) -> String {
+        let url = match self {
+            Cluster::Mainnet => MAINNET_RPC_URL,
+            Cluster::Devnet => DEVNET_RPC_URL,
+        };
+        url.trim_end_matches("/?api-key=").to_string()
+    }
+}
+
+impl From<Cluster> for ClusterType {
+    fn from(cluster: Cluster) -> Self {
+        match cluster {
+            Cluster::Mainnet => ClusterType::MainnetBeta,
+            Cluster::Devnet => ClusterType::Devnet,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Aligned columns with the most useful fields.
+    Table,
+    /// The full response as pretty-printed JSON.
+    Json,
+    /// One JSON object per line for every item of the response.
+    Ndjson,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// The native and token balances of an address.
+    Balances { address: Pubkey },
+    /// The NFTs owned by an address.
+    Nfts {
+        address: Pubkey,
+        /// The page to fetch, starting at 1.
+        #[arg(long, conflicts_with = "all")]
+        page: Option<usize>,
+        /// Fetch every page.
+        #[arg(long)]
+        all: bool,
+    },
+    /// The metadata of token mints.
+    TokenMetadata {
+        #[arg(required = true)]
+        mints: Vec<Pubkey>,
+    },
+    /// The enriched transaction history of an address.
+    Transactions {
+        address: Pubkey,
+        #[command(flatten)]
+        history: HistoryArgs,
+        /// Only transactions of this type, e.g. `NFT_SALE`.
+        #[arg(long = "type", value_parser = parse_enum::<TransactionType>)]
+        transaction_type: Option<TransactionType>,
+        /// Only transactions from this source, e.g. `MAGIC_EDEN`.
+        #[arg(long, value_parser = parse_enum::<TransactionSource>)]
+        source: Option<TransactionSource>,
+    },
+    /// The raw transaction history of an address.
+    RawTransactions {
+        address: Pubkey,
+        #[command(flatten)]
+        history: HistoryArgs,
+    },
+    /// The Solana Naming Service names of an address.
+    Names { address: Pubkey },
+    /// Manage the webhooks of the API key.
+    #[command(subcommand)]
+    Webhooks(WebhookCommand),
+}
+
+/// Pagination of transaction history. Pages run from newest to oldest, each starting before the last transaction of
+/// the previous one.
+#[derive(Debug, Args)]
+pub struct HistoryArgs {
+    /// Only transactions before this signature.
+    #[arg(long)]
+    pub before: Option<Signature>,
+    /// Only transactions after this signature.
+    #[arg(long)]
+    pub until: Option<Signature>,
+    /// The number of transactions per page.
+    #[arg(long)]
+    pub limit: Option<usize>,
+    #[arg(long, value_enum)]
+    pub commitment: Option<Commitment>,
+    /// The number of pages to fetch.
+    #[arg(long, default_value_t = 1, conflicts_with = "all")]
+    pub pages: usize,
+    /// Fetch pages until the history is exhausted.
+    #[arg(long)]
+    pub all: bool,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum Commitment {
+    Confirmed,
+    Finalized,
+}
+
+impl From<Commitment> for CommitmentLevel {
+    fn from(commitment: Commitment) -> Self {
+        match commitment {
+            Commitment::Confirmed => CommitmentLevel::Confirmed,
+            Commitment::Finalized => CommitmentLevel::Finalized,
+        }
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum WebhookCommand {
+    /// Every webhook of the API key.
+    List,
+    Get {
+        webhook_id: String,
+    },
+    /// Creates a webhook from a YAML or JSON file in the format of the API's create request.
+    Create {
+        #[arg(long, short)]
+        file: PathBuf,
+    },
+    /// Replaces the settings of a webhook with those of a YAML or JSON file.
+    Edit {
+        webhook_id: String,
+        #[arg(long, short)]
+        file: PathBuf,
+    },
+    Delete {
+        webhook_id: String,
+    },
+    /// Adds account addresses to a webhook, keeping its other settings.
+    AddAddress {
+        webhook_id: String,
+        #[arg(required = true)]
+        addresses: Vec<Pubkey>,
+    },
+}
+
+/// Parses an API enum by its serialized name, ignoring case and accepting `-` for `_`.
+fn parse_enum<T: DeserializeOwned>(name: &str) -> Result<T, String> {
+    let name = name.to_uppercase().replace('-', "_");
+    serde_json::from_value(Value::String(name.clone()))
+        .map_err(|_| format!("unknown value `{}`", name))
+}
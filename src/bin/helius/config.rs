@@ -0,0 +1,45 @@
+use serde::Deserialize;
+use std::{env, error::Error, fs, io, path::PathBuf};
+
+use crate::args::Cluster;
+
+/// The config file, read from `--config` or `~/.config/helius/config.yaml`. Flags and environment variables take
+/// precedence over it.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub api_key: Option<String>,
+    pub cluster: Option<Cluster>,
+    pub api_url: Option<String>,
+}
+
+impl Config {
+    /// Reads the config file at `path`, or the default one if it exists.
+    pub fn load(path: Option<PathBuf>) -> Result<Config, Box<dyn Error>> {
+        let (path, required) = match path {
+            Some(path) => (path, true),
+            None => match default_path() {
+                Some(path) => (path, false),
+                None => return Ok(Config::default()),
+            },
+        };
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound && !required => {
+                return Ok(Config::default())
+            }
+            Err(e) => return Err(format!("cannot read {}: {}", path.display(), e).into()),
+        };
+        serde_yaml::from_str(&contents)
+            .map_err(|e| format!("invalid config file {}: {}", path.display(), e).into())
+    }
+}
+
+fn default_path() -> Option<PathBuf> {
+    let config_dir = match env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(env::var_os("HOME").or_else(|| env::var_os("USERPROFILE"))?)
+            .join(".config"),
+    };
+    Some(config_dir.join("helius").join("config.yaml"))
+}
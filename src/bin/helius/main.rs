@@ -0,0 +1,258 @@
+//! `helius`, a command-line client for the Helius REST APIs built on [`blocking::HeliusClient`], so it sends requests
+//! the same way services using the crate do. Built with the `cli` feature.
+//!
+//! The API key is read from `--api-key`, the `HELIUS_API_KEY` environment variable or the `api_key` of the config
+//! file, in that order.
+
+mod args;
+mod config;
+mod output;
+
+use clap::Parser;
+use helius_rust_client::{
+    blocking::HeliusClient,
+    client::{
+        batch::BatchResponse,
+        init::API_URL,
+        webhooks::{CreateWebhookRequest, Webhook},
+    },
+    error::ClientResult,
+    models::{
+        enriched_transaction::RequestConfig, raw_transaction::GetRawTransactionsRequestConfig,
+    },
+    types::Signature,
+};
+use serde::de::DeserializeOwned;
+use serde_json::json;
+use std::{error::Error, fs, path::Path, process::ExitCode};
+
+use self::{
+    args::{Cli, Cluster, Command, HistoryArgs, WebhookCommand},
+    config::Config,
+    output::{Column, Report},
+};
+
+const TOKEN_COLUMNS: &[Column] = &[
+    ("MINT", "/mint"),
+    ("AMOUNT", "/amount"),
+    ("DECIMALS", "/decimals"),
+    ("TOKEN ACCOUNT", "/tokenAccount"),
+];
+const NFT_COLUMNS: &[Column] = &[
+    ("TOKEN ADDRESS", "/tokenAddress"),
+    ("NAME", "/name"),
+    ("COLLECTION", "/collectionName"),
+];
+const TOKEN_METADATA_COLUMNS: &[Column] = &[
+    ("MINT", "/mint"),
+    ("NAME", "/onChainData/data/name"),
+    ("SYMBOL", "/onChainData/data/symbol"),
+];
+const ENRICHED_TRANSACTION_COLUMNS: &[Column] = &[
+    ("SIGNATURE", "/signature"),
+    ("TIMESTAMP", "/timestamp"),
+    ("TYPE", "/type"),
+    ("SOURCE", "/source"),
+    ("FEE", "/fee"),
+    ("DESCRIPTION", "/description"),
+];
+const RAW_TRANSACTION_COLUMNS: &[Column] = &[
+    ("SIGNATURE", "/transaction/signatures/0"),
+    ("SLOT", "/slot"),
+    ("BLOCK TIME", "/blockTime"),
+    ("FEE", "/meta/fee"),
+    ("ERROR", "/meta/err"),
+];
+const NAME_COLUMNS: &[Column] = &[("NAME", "")];
+const WEBHOOK_COLUMNS: &[Column] = &[
+    ("WEBHOOK ID", "/webhookID"),
+    ("TYPE", "/webhookType"),
+    ("URL", "/webhookURL"),
+    ("TRANSACTION TYPES", "/transactionTypes"),
+    ("ADDRESSES", "/accountAddresses"),
+];
+const DELETED_WEBHOOK_COLUMNS: &[Column] = &[("DELETED WEBHOOK ID", "/webhookID")];
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+    let client = client(&cli)?;
+    let mut failed_chunks = 0;
+    let report = match cli.command {
+        Command::Balances { address } => {
+            let balances = client.get_token_balances(address.to_string())?;
+            Report::nested(&balances, "/tokens", TOKEN_COLUMNS)?.with_summary(format!(
+                "Native balance: {} lamports",
+                balances.native_balance
+            ))
+        }
+        Command::Nfts { address, page, all } => {
+            if all {
+                Report::list(&client.get_all_nfts(address.to_string())?, NFT_COLUMNS)?
+            } else {
+                Report::list(&client.get_nfts(address.to_string(), page)?, NFT_COLUMNS)?
+            }
+        }
+        Command::TokenMetadata { mints } => {
+            let mints = mints.iter().map(ToString::to_string).collect();
+            let response = client.get_tokens_metadata(mints)?;
+            failed_chunks = report_failed_chunks(&response);
+            Report::list(&response.results, TOKEN_METADATA_COLUMNS)?
+        }
+        Command::Transactions {
+            address,
+            history,
+            transaction_type,
+            source,
+        } => {
+            let transactions = fetch_pages(
+                &history,
+                |before| {
+                    client.get_enriched_transactions(RequestConfig {
+                        address,
+                        before,
+                        until: history.until,
+                        limit: history.limit,
+                        source,
+                        transaction_type,
+                        commitment: history.commitment.map(Into::into),
+                    })
+                },
+                |transaction| transaction.signature.parse().ok(),
+            )?;
+            Report::list(&transactions, ENRICHED_TRANSACTION_COLUMNS)?
+        }
+        Command::RawTransactions { address, history } => {
+            let transactions = fetch_pages(
+                &history,
+                |before| {
+                    client.get_transactions_for_address(GetRawTransactionsRequestConfig {
+                        address,
+                        before,
+                        until: history.until,
+                        limit: history.limit,
+                        commitment: history.commitment.map(Into::into),
+                    })
+                },
+                |transaction| transaction.transaction.signatures.first()?.parse().ok(),
+            )?;
+            Report::list(&transactions, RAW_TRANSACTION_COLUMNS)?
+        }
+        Command::Names { address } => Report::list(
+            &client.get_naming_service_names(address.to_string())?,
+            NAME_COLUMNS,
+        )?,
+        Command::Webhooks(command) => run_webhook_command(&client, command)?,
+    };
+    report.print(cli.output)?;
+    if failed_chunks > 0 {
+        return Err(format!("{} of the batched requests failed", failed_chunks).into());
+    }
+    Ok(())
+}
+
+fn run_webhook_command(
+    client: &HeliusClient,
+    command: WebhookCommand,
+) -> Result<Report, Box<dyn Error>> {
+    let webhook = match command {
+        WebhookCommand::List => return Report::list(&client.get_webhooks()?, WEBHOOK_COLUMNS),
+        WebhookCommand::Get { webhook_id } => client.get_webhook(webhook_id)?,
+        WebhookCommand::Create { file } => client.create_webhook(read_file(&file)?)?,
+        WebhookCommand::Edit { webhook_id, file } => {
+            client.edit_webhook(webhook_id, read_file(&file)?)?
+        }
+        WebhookCommand::Delete { webhook_id } => {
+            client.delete_webhook(webhook_id.clone())?;
+            return Report::list(&json!({ "webhookID": webhook_id }), DELETED_WEBHOOK_COLUMNS);
+        }
+        WebhookCommand::AddAddress {
+            webhook_id,
+            addresses,
+        } => {
+            let webhook = client.get_webhook(webhook_id.clone())?;
+            let mut account_addresses = webhook.account_addresses;
+            for address in addresses {
+                let address = address.to_string();
+                if !account_addresses.contains(&address) {
+                    account_addresses.push(address);
+                }
+            }
+            let request = CreateWebhookRequest {
+                webhook_url: webhook.webhook_url,
+                transaction_types: webhook.transaction_types,
+                account_addresses,
+                webhook_type: webhook.webhook_type,
+                auth_header: webhook.auth_header,
+            };
+            client.edit_webhook(webhook_id, request)?
+        }
+    };
+    Report::list::<Webhook>(&webhook, WEBHOOK_COLUMNS)
+}
+
+/// Creates the client from the flags, falling back to the config file.
+fn client(cli: &Cli) -> Result<HeliusClient, Box<dyn Error>> {
+    let config = Config::load(cli.config.clone())?;
+    let api_key = cli.api_key.clone().or(config.api_key).ok_or(
+        "no API key: pass --api-key, set HELIUS_API_KEY or add `api_key` to the config file",
+    )?;
+    let cluster = cli.cluster.or(config.cluster).unwrap_or(Cluster::Mainnet);
+    Ok(match cli.api_url.clone().or(config.api_url) {
+        Some(api_url) => {
+            HeliusClient::new_with_urls(api_key, cluster.into(), api_url, cluster.rpc_url())
+        }
+        None if cluster == Cluster::Mainnet => HeliusClient::new(api_key, cluster.into()),
+        None => HeliusClient::new_with_urls(
+            api_key,
+            cluster.into(),
+            API_URL.to_string(),
+            cluster.rpc_url(),
+        ),
+    })
+}
+
+/// Fetches the pages of transaction history asked for by `history`. `fetch` returns the page before a signature, and
+/// `signature` the signature of a transaction of a page.
+fn fetch_pages<T>(
+    history: &HistoryArgs,
+    fetch: impl Fn(Option<Signature>) -> ClientResult<Vec<T>>,
+    signature: impl Fn(&T) -> Option<Signature>,
+) -> ClientResult<Vec<T>> {
+    let mut transactions = Vec::new();
+    let mut before = history.before;
+    let mut pages = 0;
+    loop {
+        let page = fetch(before)?;
+        pages += 1;
+        let last_page = page.is_empty() || history.limit.is_some_and(|limit| page.len() < limit);
+        before = page.last().and_then(&signature);
+        transactions.extend(page);
+        if last_page || before.is_none() || (!history.all && pages >= history.pages) {
+            return Ok(transactions);
+        }
+    }
+}
+
+/// Prints the error of every failed chunk of a batched request to stderr, and returns how many failed.
+fn report_failed_chunks<T>(response: &BatchResponse<T>) -> usize {
+    for chunk in &response.failed_chunks {
+        eprintln!("error: {} for {}", chunk.error, chunk.inputs.join(","));
+    }
+    response.failed_chunks.len()
+}
+
+/// Reads a YAML or JSON file.
+fn read_file<T: DeserializeOwned>(path: &Path) -> Result<T, Box<dyn Error>> {
+    let contents =
+        fs::read_to_string(path).map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+    serde_yaml::from_str(&contents).map_err(|e| format!("invalid {}: {}", path.display(), e).into())
+}
@@ -0,0 +1,143 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::{
+    error::Error,
+    io::{self, Write},
+};
+
+use crate::args::OutputFormat;
+
+/// A table column: its header and the JSON pointer of its value within a row.
+pub type Column = (&'static str, &'static str);
+
+/// A command's result, printed according to the `--output` format.
+pub struct Report {
+    /// The full response, printed as JSON.
+    value: Value,
+    /// The items of the response, printed as table rows or NDJSON lines.
+    rows: Vec<Value>,
+    columns: &'static [Column],
+    /// A line printed above the table.
+    summary: Option<String>,
+}
+
+impl Report {
+    /// A report of a list response, whose items are the rows.
+    pub fn list<T: Serialize>(
+        items: &T,
+        columns: &'static [Column],
+    ) -> Result<Report, Box<dyn Error>> {
+        let value = serde_json::to_value(items)?;
+        let rows = match &value {
+            Value::Array(rows) => rows.clone(),
+            value => vec![value.clone()],
+        };
+        Ok(Report {
+            value,
+            rows,
+            columns,
+            summary: None,
+        })
+    }
+
+    /// A report of a response whose items are at `pointer`.
+    pub fn nested<T: Serialize>(
+        response: &T,
+        pointer: &str,
+        columns: &'static [Column],
+    ) -> Result<Report, Box<dyn Error>> {
+        let value = serde_json::to_value(response)?;
+        let rows = match value.pointer(pointer) {
+            Some(Value::Array(rows)) => rows.clone(),
+            _ => Vec::new(),
+        };
+        Ok(Report {
+            value,
+            rows,
+            columns,
+            summary: None,
+        })
+    }
+
+    pub fn with_summary(mut self, summary: String) -> Self {
+        self.summary = Some(summary);
+        self
+    }
+
+    pub fn print(&self, format: OutputFormat) -> Result<(), Box<dyn Error>> {
+        let mut out = io::stdout().lock();
+        match format {
+            OutputFormat::Json => writeln!(out, "{}", serde_json::to_string_pretty(&self.value)?)?,
+            OutputFormat::Ndjson => {
+                for row in &self.rows {
+                    writeln!(out, "{}", serde_json::to_string(row)?)?;
+                }
+            }
+            OutputFormat::Table => {
+                if let Some(summary) = &self.summary {
+                    writeln!(out, "{}", summary)?;
+                }
+                write_table(&mut out, self.columns, &self.rows)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn write_table(out: &mut impl Write, columns: &[Column], rows: &[Value]) -> io::Result<()> {
+    let cells: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| {
+            columns
+                .iter()
+                .map(|(_, pointer)| cell(row.pointer(pointer)))
+                .collect()
+        })
+        .collect();
+    let widths: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(index, (header, _))| {
+            cells
+                .iter()
+                .map(|row| row[index].chars().count())
+                .chain([header.len()])
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+
+    let headers: Vec<String> = columns
+        .iter()
+        .map(|(header, _)| header.to_string())
+        .collect();
+    for row in std::iter::once(&headers).chain(&cells) {
+        let line: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        writeln!(out, "{}", line.join("  ").trim_end())?;
+    }
+    Ok(())
+}
+
+/// Formats a value for a table cell. Lists of plain values are joined with commas.
+fn cell(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => "-".to_string(),
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Array(items))
+            if items
+                .iter()
+                .all(|item| !item.is_array() && !item.is_object()) =>
+        {
+            items
+                .iter()
+                .map(|item| cell(Some(item)))
+                .collect::<Vec<_>>()
+                .join(",")
+        }
+        Some(value) => value.to_string(),
+    }
+}
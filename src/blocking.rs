@@ -0,0 +1,353 @@
+//! A synchronous [`HeliusClient`] for code that does not run inside an async runtime, such as CLI tools and build
+//! scripts. Enabled with the `blocking` feature.
+//!
+//! Every REST method blocks on the matching method of the [async client](AsyncHeliusClient) on a runtime owned by
+//! the client, so caching, coalescing, credit accounting, key pools and cassettes behave the same. Configure them on
+//! the async client and convert it with [`HeliusClient::from_async`]. With the `rpc` feature, RPC requests go through
+//! a blocking `solana_client::rpc_client::RpcClient` that forwards to the async client's RPC sender.
+//!
+//! Each client owns a single-threaded tokio runtime, which cannot block inside another runtime. Calling a REST method
+//! from within an async runtime returns an error instead of blocking, and dropping the client there shuts its runtime
+//! down in the background. RPC requests made through [`HeliusClient::rpc_client`] from within an async runtime panic,
+//! like the ones of `solana_client::rpc_client::RpcClient`. Async code should use the async client instead.
+
+#[cfg(feature = "rpc")]
+use async_trait::async_trait;
+#[cfg(feature = "rpc")]
+use serde_json::Value;
+#[cfg(feature = "rpc")]
+use solana_client::{
+    client_error::Result as RpcResult,
+    rpc_client::{Mocks, RpcClient, RpcClientConfig},
+    rpc_request::RpcRequest,
+    rpc_sender::{RpcSender, RpcTransportStats},
+};
+#[cfg(feature = "rpc")]
+use solana_sdk::commitment_config::CommitmentConfig;
+use std::{future::Future, sync::Arc};
+#[cfg(feature = "rpc")]
+use std::time::Duration;
+use tokio::runtime::{Builder, Handle, Runtime};
+
+use crate::{
+    client::{
+        batch::BatchResponse,
+        init::HeliusClient as AsyncHeliusClient,
+        webhooks::{CreateWebhookRequest, Webhook},
+    },
+    error::{ClientError, ClientErrorKind, ClientResult},
+    models::{
+        das::AssetsByOwner,
+        enriched_transaction::{EnrichedTransaction, RequestConfig},
+        nft::{
+            ActiveListingsRequestConfig, ActiveListingsResponse, MintListRequestConfig,
+            MintListResponse, NftEvent, NftInfo, NftMetadata, TokenBalancesResponse,
+        },
+        raw_transaction::{GetRawTransactionsRequestConfig, RawTransaction},
+        structs::TokenMetadata,
+    },
+    types::{ClusterType, CommitmentLevel},
+};
+
+/// A blocking Helius client. See the [module documentation](self) for the restrictions within async runtimes.
+pub struct HeliusClient {
+    /// A blocking RPC client sharing the key pool, failover, credit accounting and tracing of the async client.
+    #[cfg(feature = "rpc")]
+    pub rpc_client: RpcClient,
+    inner: Arc<AsyncHeliusClient>,
+    /// Only `None` once the client is dropped.
+    runtime: Option<Runtime>,
+}
+
+/// Forwards the requests of the blocking [`RpcClient`] to the RPC client of the async [`HeliusClient`].
+#[cfg(feature = "rpc")]
+struct AsyncSender(Arc<AsyncHeliusClient>);
+
+#[cfg(feature = "rpc")]
+#[async_trait]
+impl RpcSender for AsyncSender {
+    async fn send(&self, request: RpcRequest, params: Value) -> RpcResult<Value> {
+        self.0.rpc_client.send(request, params).await
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        RpcTransportStats::default()
+    }
+
+    fn url(&self) -> String {
+        self.0.rpc_client.url()
+    }
+}
+
+impl HeliusClient {
+    pub fn new(api_key: String, cluster_type: ClusterType) -> Self {
+        HeliusClient::from_async(AsyncHeliusClient::new(api_key, cluster_type))
+    }
+
+    #[cfg(feature = "rpc")]
+    pub fn new_with_commitment(
+        api_key: String,
+        cluster_type: ClusterType,
+        commitment_config: CommitmentConfig,
+    ) -> Self {
+        HeliusClient::from_async(AsyncHeliusClient::new_with_commitment(
+            api_key,
+            cluster_type,
+            commitment_config,
+        ))
+    }
+
+    #[cfg(feature = "rpc")]
+    pub fn new_with_timeout(api_key: String, cluster_type: ClusterType, timeout: Duration) -> Self {
+        HeliusClient::from_async(AsyncHeliusClient::new_with_timeout(
+            api_key,
+            cluster_type,
+            timeout,
+        ))
+    }
+
+    #[cfg(feature = "rpc")]
+    pub fn new_with_timeout_and_commitment(
+        api_key: String,
+        cluster_type: ClusterType,
+        timeout: Duration,
+        commitment_config: CommitmentConfig,
+    ) -> Self {
+        HeliusClient::from_async(AsyncHeliusClient::new_with_timeout_and_commitment(
+            api_key,
+            cluster_type,
+            timeout,
+            commitment_config,
+        ))
+    }
+
+    #[cfg(feature = "rpc")]
+    pub fn new_with_timeouts_and_commitment(
+        api_key: String,
+        cluster_type: ClusterType,
+        timeout: Duration,
+        commitment_config: CommitmentConfig,
+        confirm_transaction_initial_timeout: Duration,
+    ) -> Self {
+        HeliusClient::from_async(AsyncHeliusClient::new_with_timeouts_and_commitment(
+            api_key,
+            cluster_type,
+            timeout,
+            commitment_config,
+            confirm_transaction_initial_timeout,
+        ))
+    }
+
+    /// See [`AsyncHeliusClient::new_with_urls`].
+    pub fn new_with_urls(
+        api_key: String,
+        cluster_type: ClusterType,
+        api_url: String,
+        rpc_url: String,
+    ) -> Self {
+        HeliusClient::from_async(AsyncHeliusClient::new_with_urls(
+            api_key,
+            cluster_type,
+            api_url,
+            rpc_url,
+        ))
+    }
+
+    #[cfg(feature = "rpc")]
+    pub fn new_mock(api_key: String, cluster_type: ClusterType) -> Self {
+        HeliusClient::from_async(AsyncHeliusClient::new_mock(api_key, cluster_type))
+    }
+
+    #[cfg(feature = "rpc")]
+    pub fn new_mock_with_mocks(api_key: String, cluster_type: ClusterType, mocks: Mocks) -> Self {
+        HeliusClient::from_async(AsyncHeliusClient::new_mock_with_mocks(
+            api_key,
+            cluster_type,
+            mocks,
+        ))
+    }
+
+    /// Wraps `client`, keeping every layer configured on it.
+    ///
+    /// # Panics
+    ///
+    /// If the runtime of the client cannot be created.
+    pub fn from_async(client: AsyncHeliusClient) -> Self {
+        let runtime = Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("failed to create the runtime of the blocking Helius client");
+        #[cfg(feature = "rpc")]
+        let config = RpcClientConfig::with_commitment(client.rpc_client.commitment());
+        let inner = Arc::new(client);
+        HeliusClient {
+            #[cfg(feature = "rpc")]
+            rpc_client: RpcClient::new_sender(AsyncSender(inner.clone()), config),
+            inner,
+            runtime: Some(runtime),
+        }
+    }
+
+    /// Runs `future` to completion on the runtime of the client, or fails if called from within an async runtime,
+    /// where blocking would panic.
+    fn block_on<T>(&self, future: impl Future<Output = ClientResult<T>>) -> ClientResult<T> {
+        if Handle::try_current().is_ok() {
+            return Err(ClientError::from(ClientErrorKind::Custom(
+                "the blocking Helius client cannot be used from within an async runtime".to_string(),
+            )));
+        }
+        self.runtime.as_ref().unwrap().block_on(future)
+    }
+
+    /// Returns the async client behind this one, e.g. to read its credit usage or key stats.
+    pub fn inner(&self) -> &AsyncHeliusClient {
+        &self.inner
+    }
+
+    /// Blocking version of [`AsyncHeliusClient::get_naming_service_names`].
+    pub fn get_naming_service_names(&self, address: String) -> ClientResult<Vec<String>> {
+        self.block_on(self.inner.get_naming_service_names(address))
+    }
+
+    /// Blocking version of [`AsyncHeliusClient::get_token_balances`].
+    pub fn get_token_balances(&self, address: String) -> ClientResult<TokenBalancesResponse> {
+        self.block_on(self.inner.get_token_balances(address))
+    }
+
+    /// Blocking version of [`AsyncHeliusClient::get_nfts`].
+    pub fn get_nfts(&self, address: String, page_number: Option<usize>) -> ClientResult<Vec<NftInfo>> {
+        self.block_on(self.inner.get_nfts(address, page_number))
+    }
+
+    /// Blocking version of [`AsyncHeliusClient::get_all_nfts`].
+    pub fn get_all_nfts(&self, address: String) -> ClientResult<Vec<NftInfo>> {
+        self.block_on(self.inner.get_all_nfts(address))
+    }
+
+    /// Blocking version of [`AsyncHeliusClient::get_nfts_metadata`].
+    pub fn get_nfts_metadata(
+        &self,
+        token_mints: Vec<String>,
+    ) -> ClientResult<BatchResponse<NftMetadata>> {
+        self.block_on(self.inner.get_nfts_metadata(token_mints))
+    }
+
+    /// Blocking version of [`AsyncHeliusClient::get_nft_events_for_address`].
+    pub fn get_nft_events_for_address(&self, config: RequestConfig) -> ClientResult<Vec<NftEvent>> {
+        self.block_on(self.inner.get_nft_events_for_address(config))
+    }
+
+    /// Blocking version of [`AsyncHeliusClient::get_nft_events`].
+    pub fn get_nft_events(&self, config: RequestConfig) -> ClientResult<Vec<NftEvent>> {
+        self.block_on(self.inner.get_nft_events(config))
+    }
+
+    /// Blocking version of [`AsyncHeliusClient::get_active_nft_listings`].
+    pub fn get_active_nft_listings(
+        &self,
+        config: ActiveListingsRequestConfig,
+    ) -> ClientResult<ActiveListingsResponse> {
+        self.block_on(self.inner.get_active_nft_listings(config))
+    }
+
+    /// Blocking version of [`AsyncHeliusClient::get_mint_list`].
+    pub fn get_mint_list(&self, config: MintListRequestConfig) -> ClientResult<MintListResponse> {
+        self.block_on(self.inner.get_mint_list(config))
+    }
+
+    /// Blocking version of [`AsyncHeliusClient::get_tokens_metadata`].
+    pub fn get_tokens_metadata(
+        &self,
+        token_mints: Vec<String>,
+    ) -> ClientResult<BatchResponse<TokenMetadata>> {
+        self.block_on(self.inner.get_tokens_metadata(token_mints))
+    }
+
+    /// Blocking version of [`AsyncHeliusClient::get_assets_by_owner`].
+    pub fn get_assets_by_owner(&self, owner: String, page: u32) -> ClientResult<AssetsByOwner> {
+        self.block_on(self.inner.get_assets_by_owner(owner, page))
+    }
+
+    /// Blocking version of [`AsyncHeliusClient::get_transactions_for_address`].
+    pub fn get_transactions_for_address(
+        &self,
+        config: GetRawTransactionsRequestConfig,
+    ) -> ClientResult<Vec<RawTransaction>> {
+        self.block_on(self.inner.get_transactions_for_address(config))
+    }
+
+    /// Blocking version of [`AsyncHeliusClient::get_transactions_by_hash`].
+    pub fn get_transactions_by_hash(
+        &self,
+        transaction_hashes: Vec<String>,
+        commitment: Option<CommitmentLevel>,
+    ) -> ClientResult<BatchResponse<RawTransaction>> {
+        self.block_on(self.inner.get_transactions_by_hash(transaction_hashes, commitment))
+    }
+
+    /// Blocking version of [`AsyncHeliusClient::get_enriched_transactions`].
+    pub fn get_enriched_transactions(
+        &self,
+        config: RequestConfig,
+    ) -> ClientResult<Vec<EnrichedTransaction>> {
+        self.block_on(self.inner.get_enriched_transactions(config))
+    }
+
+    /// Blocking version of [`AsyncHeliusClient::get_enriched_transactions_by_hash`].
+    pub fn get_enriched_transactions_by_hash(
+        &self,
+        transaction_hashes: Vec<String>,
+        commitment: Option<CommitmentLevel>,
+    ) -> ClientResult<BatchResponse<EnrichedTransaction>> {
+        self.block_on(
+            self.inner
+                .get_enriched_transactions_by_hash(transaction_hashes, commitment),
+        )
+    }
+
+    /// Blocking version of [`AsyncHeliusClient::create_webhook`].
+    pub fn create_webhook(&self, webhook_request: CreateWebhookRequest) -> ClientResult<Webhook> {
+        self.block_on(self.inner.create_webhook(webhook_request))
+    }
+
+    /// Blocking version of [`AsyncHeliusClient::get_webhooks`].
+    pub fn get_webhooks(&self) -> ClientResult<Vec<Webhook>> {
+        self.block_on(self.inner.get_webhooks())
+    }
+
+    /// Blocking version of [`AsyncHeliusClient::get_webhook`].
+    pub fn get_webhook(&self, webhook_id: String) -> ClientResult<Webhook> {
+        self.block_on(self.inner.get_webhook(webhook_id))
+    }
+
+    /// Blocking version of [`AsyncHeliusClient::edit_webhook`].
+    pub fn edit_webhook(
+        &self,
+        webhook_id: String,
+        new_webhook: CreateWebhookRequest,
+    ) -> ClientResult<Webhook> {
+        self.block_on(self.inner.edit_webhook(webhook_id, new_webhook))
+    }
+
+    /// Blocking version of [`AsyncHeliusClient::delete_webhook`].
+    pub fn delete_webhook(&self, webhook_id: String) -> ClientResult<()> {
+        self.block_on(self.inner.delete_webhook(webhook_id))
+    }
+}
+
+impl Drop for HeliusClient {
+    fn drop(&mut self) {
+        // Dropping a runtime blocks on its tasks, which panics within another runtime.
+        if let Some(runtime) = self.runtime.take() {
+            if Handle::try_current().is_ok() {
+                runtime.shutdown_background();
+            }
+        }
+    }
+}
+
+impl From<AsyncHeliusClient> for HeliusClient {
+    fn from(client: AsyncHeliusClient) -> Self {
+        HeliusClient::from_async(client)
+    }
+}
@@ -0,0 +1,217 @@
+use async_trait::async_trait;
+
+use crate::error::ClientResult;
+use crate::models::{
+    das::AssetsByOwner,
+    enriched_transaction::{EnrichedTransaction, RequestConfig},
+    nft::{
+        ActiveListingsRequestConfig, ActiveListingsResponse, MintListRequestConfig,
+        MintListResponse, NftEvent, NftInfo, NftMetadata, TokenBalancesResponse,
+    },
+    raw_transaction::{GetRawTransactionsRequestConfig, RawTransaction},
+    structs::TokenMetadata,
+};
+use crate::types::CommitmentLevel;
+
+use super::{
+    batch::BatchResponse,
+    init::HeliusClient,
+    webhooks::{CreateWebhookRequest, Webhook},
+};
+
+/// The Helius REST API. Implemented by [`HeliusClient`] and by [`MockHeliusClient`](super::mock::MockHeliusClient),
+/// so code that depends on `HeliusApi` rather than on [`HeliusClient`] can be unit tested without network access.
+/// See the [`HeliusClient`] method of the same name for the documentation of each method.
+#[async_trait]
+pub trait HeliusApi: Send + Sync {
+    async fn get_naming_service_names(&self, address: String) -> ClientResult<Vec<String>>;
+
+    async fn get_token_balances(&self, address: String) -> ClientResult<TokenBalancesResponse>;
+
+    async fn get_nfts(
+        &self,
+        address: String,
+        page_number: Option<usize>,
+    ) -> ClientResult<Vec<NftInfo>>;
+
+    async fn get_all_nfts(&self, address: String) -> ClientResult<Vec<NftInfo>>;
+
+    async fn get_nfts_metadata(
+        &self,
+        token_mints: Vec<String>,
+    ) -> ClientResult<BatchResponse<NftMetadata>>;
+
+    async fn get_nft_events_for_address(
+        &self,
+        config: RequestConfig,
+    ) -> ClientResult<Vec<NftEvent>>;
+
+    async fn get_nft_events(&self, config: RequestConfig) -> ClientResult<Vec<NftEvent>>;
+
+    async fn get_active_nft_listings(
+        &self,
+        config: ActiveListingsRequestConfig,
+    ) -> ClientResult<ActiveListingsResponse>;
+
+    async fn get_mint_list(&self, config: MintListRequestConfig) -> ClientResult<MintListResponse>;
+
+    async fn get_tokens_metadata(
+        &self,
+        token_mints: Vec<String>,
+    ) -> ClientResult<BatchResponse<TokenMetadata>>;
+
+    async fn get_assets_by_owner(&self, owner: String, page: u32) -> ClientResult<AssetsByOwner>;
+
+    async fn get_transactions_for_address(
+        &self,
+        config: GetRawTransactionsRequestConfig,
+    ) -> ClientResult<Vec<RawTransaction>>;
+
+    async fn get_transactions_by_hash(
+        &self,
+        transaction_hashes: Vec<String>,
+        commitment: Option<CommitmentLevel>,
+    ) -> ClientResult<BatchResponse<RawTransaction>>;
+
+    async fn get_enriched_transactions(
+        &self,
+        config: RequestConfig,
+    ) -> ClientResult<Vec<EnrichedTransaction>>;
+
+    async fn get_enriched_transactions_by_hash(
+        &self,
+        transaction_hashes: Vec<String>,
+        commitment: Option<CommitmentLevel>,
+    ) -> ClientResult<BatchResponse<EnrichedTransaction>>;
+
+    async fn create_webhook(&self, webhook_request: CreateWebhookRequest) -> ClientResult<Webhook>;
+
+    async fn get_webhooks(&self) -> ClientResult<Vec<Webhook>>;
+
+    async fn get_webhook(&self, webhook_id: String) -> ClientResult<Webhook>;
+
+    async fn edit_webhook(
+        &self,
+        webhook_id: String,
+        new_webhook: CreateWebhookRequest,
+    ) -> ClientResult<Webhook>;
+
+    async fn delete_webhook(&self, webhook_id: String) -> ClientResult<()>;
+}
+
+#[async_trait]
+impl HeliusApi for HeliusClient {
+    async fn get_naming_service_names(&self, address: String) -> ClientResult<Vec<String>> {
+        HeliusClient::get_naming_service_names(self, address).await
+    }
+
+    async fn get_token_balances(&self, address: String) -> ClientResult<TokenBalancesResponse> {
+        HeliusClient::get_token_balances(self, address).await
+    }
+
+    async fn get_nfts(
+        &self,
+        address: String,
+        page_number: Option<usize>,
+    ) -> ClientResult<Vec<NftInfo>> {
+        HeliusClient::get_nfts(self, address, page_number).await
+    }
+
+    async fn get_all_nfts(&self, address: String) -> ClientResult<Vec<NftInfo>> {
+        HeliusClient::get_all_nfts(self, address).await
+    }
+
+    async fn get_nfts_metadata(
+        &self,
+        token_mints: Vec<String>,
+    ) -> ClientResult<BatchResponse<NftMetadata>> {
+        HeliusClient::get_nfts_metadata(self, token_mints).await
+    }
+
+    async fn get_nft_events_for_address(
+        &self,
+        config: RequestConfig,
+    ) -> ClientResult<Vec<NftEvent>> {
+        HeliusClient::get_nft_events_for_address(self, config).await
+    }
+
+    async fn get_nft_events(&self, config: RequestConfig) -> ClientResult<Vec<NftEvent>> {
+        HeliusClient::get_nft_events(self, config).await
+    }
+
+    async fn get_active_nft_listings(
+        &self,
+        config: ActiveListingsRequestConfig,
+    ) -> ClientResult<ActiveListingsResponse> {
+        HeliusClient::get_active_nft_listings(self, config).await
+    }
+
+    async fn get_mint_list(&self, config: MintListRequestConfig) -> ClientResult<MintListResponse> {
+        HeliusClient::get_mint_list(self, config).await
+    }
+
+    async fn get_tokens_metadata(
+        &self,
+        token_mints: Vec<String>,
+    ) -> ClientResult<BatchResponse<TokenMetadata>> {
+        HeliusClient::get_tokens_metadata(self, token_mints).await
+    }
+
+    async fn get_assets_by_owner(&self, owner: String, page: u32) -> ClientResult<AssetsByOwner> {
+        HeliusClient::get_assets_by_owner(self, owner, page).await
+    }
+
+    async fn get_transactions_for_address(
+        &self,
+        config: GetRawTransactionsRequestConfig,
+    ) -> ClientResult<Vec<RawTransaction>> {
+        HeliusClient::get_transactions_for_address(self, config).await
+    }
+
+    async fn get_transactions_by_hash(
+        &self,
+        transaction_hashes: Vec<String>,
+        commitment: Option<CommitmentLevel>,
+    ) -> ClientResult<BatchResponse<RawTransaction>> {
+        HeliusClient::get_transactions_by_hash(self, transaction_hashes, commitment).await
+    }
+
+    async fn get_enriched_transactions(
+        &self,
+        config: RequestConfig,
+    ) -> ClientResult<Vec<EnrichedTransaction>> {
+        HeliusClient::get_enriched_transactions(self, config).await
+    }
+
+    async fn get_enriched_transactions_by_hash(
+        &self,
+        transaction_hashes: Vec<String>,
+        commitment: Option<CommitmentLevel>,
+    ) -> ClientResult<BatchResponse<EnrichedTransaction>> {
+        HeliusClient::get_enriched_transactions_by_hash(self, transaction_hashes, commitment).await
+    }
+
+    async fn create_webhook(&self, webhook_request: CreateWebhookRequest) -> ClientResult<Webhook> {
+        HeliusClient::create_webhook(self, webhook_request).await
+    }
+
+    async fn get_webhooks(&self) -> ClientResult<Vec<Webhook>> {
+        HeliusClient::get_webhooks(self).await
+    }
+
+    async fn get_webhook(&self, webhook_id: String) -> ClientResult<Webhook> {
+        HeliusClient::get_webhook(self, webhook_id).await
+    }
+
+    async fn edit_webhook(
+        &self,
+        webhook_id: String,
+        new_webhook: CreateWebhookRequest,
+    ) -> ClientResult<Webhook> {
+        HeliusClient::edit_webhook(self, webhook_id, new_webhook).await
+    }
+
+    async fn delete_webhook(&self, webhook_id: String) -> ClientResult<()> {
+        HeliusClient::delete_webhook(self, webhook_id).await
+    }
+}
@@ -0,0 +1,88 @@
+use futures::{stream, Future, StreamExt};
+use tracing::Instrument;
+
+use crate::error::{ClientError, ClientResult};
+
+use super::{init::HeliusClient, telemetry::batch_span};
+
+/// The merged result of a request that was split into several chunks to respect the API's batch size limit.
+/// A failed chunk does not discard the results of the chunks that succeeded.
+#[derive(Debug)]
+pub struct BatchResponse<T> {
+    /// Results of every successful chunk, in input order.
+    pub results: Vec<T>,
+    /// Chunks whose request failed, in input order.
+    pub failed_chunks: Vec<FailedChunk>,
+}
+
+/// A chunk of a batched request that failed.
+#[derive(Debug)]
+pub struct FailedChunk {
+    /// The inputs (mint addresses or transaction hashes) that were sent in the failed request.
+    pub inputs: Vec<String>,
+    pub error: ClientError,
+}
+
+impl<T> BatchResponse<T> {
+    /// Returns true if every chunk succeeded.
+    pub fn is_complete(&self) -> bool {
+        self.failed_chunks.is_empty()
+    }
+
+    /// Converts into a plain result, returning the error of the first failed chunk if any chunk failed.
+    pub fn into_result(self) -> ClientResult<Vec<T>> {
+        match self.failed_chunks.into_iter().next() {
+            Some(chunk) => Err(chunk.error),
+            None => Ok(self.results),
+        }
+    }
+}
+
+impl<T> Default for BatchResponse<T> {
+    fn default() -> Self {
+        BatchResponse {
+            results: Vec::new(),
+            failed_chunks: Vec::new(),
+        }
+    }
+}
+
+impl HeliusClient {
+    /// Splits `inputs` into chunks of at most `chunk_size`, issues `request` for each chunk (at most
+    /// [`max_concurrent_requests`](HeliusClient::max_concurrent_requests) at a time) and merges the responses in input order.
+    pub(crate) async fn execute_batched<T, F, Fut>(
+        &self,
+        inputs: Vec<String>,
+        chunk_size: usize,
+        request: F,
+    ) -> BatchResponse<T>
+    where
+        F: Fn(Vec<String>) -> Fut,
+        Fut: Future<Output = ClientResult<Vec<T>>>,
+    {
+        let chunks: Vec<Vec<String>> = inputs
+            .chunks(chunk_size.max(1))
+            .map(|chunk| chunk.to_vec())
+            .collect();
+
+        let span = batch_span(inputs.len(), chunks.len());
+        let responses: Vec<(Vec<String>, ClientResult<Vec<T>>)> = stream::iter(chunks)
+            .map(|chunk| {
+                let response = request(chunk.clone());
+                async move { (chunk, response.await) }
+            })
+            .buffered(self.max_concurrent_requests.max(1))
+            .collect()
+            .instrument(span)
+            .await;
+
+        let mut batch = BatchResponse::default();
+        for (inputs, response) in responses {
+            match response {
+                Ok(results) => batch.results.extend(results),
+                Err(error) => batch.failed_chunks.push(FailedChunk { inputs, error }),
+            }
+        }
+        batch
+    }
+}
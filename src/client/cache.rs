@@ -0,0 +1,314 @@
+use futures::Future;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    collections::HashMap,
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "disk-cache")]
+use crate::error::{ClientError, ClientErrorKind, ClientResult};
+
+use super::{batch::BatchResponse, init::HeliusClient};
+
+/// A key-value store for API responses. Values are the JSON encoding of a single response item and expire after `ttl`.
+/// Implement this to back the [`HeliusClient`] cache with your own store.
+pub trait Cache: Send + Sync {
+    /// Returns the value stored under `key`, or `None` if it is missing or expired.
+    fn get(&self, key: &str) -> Option<Vec<u8>>;
+    /// Stores `value` under `key` for `ttl`.
+    fn insert(&self, key: &str, value: Vec<u8>, ttl: Duration);
+}
+
+/// The endpoint families that are cached, each with its own TTL and metrics.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CacheFamily {
+    TokenMetadata,
+    EnrichedTransactions,
+    RawTransactions,
+    NamingService,
+}
+
+impl CacheFamily {
+    pub const ALL: [CacheFamily; 4] = [
+        CacheFamily::TokenMetadata,
+        CacheFamily::EnrichedTransactions,
+        CacheFamily::RawTransactions,
+        CacheFamily::NamingService,
+    ];
+
+    fn key_prefix(&self) -> &'static str {
+        match self {
+            CacheFamily::TokenMetadata => "token-metadata",
+            CacheFamily::EnrichedTransactions => "enriched-transactions",
+            CacheFamily::RawTransactions => "raw-transactions",
+            CacheFamily::NamingService => "names",
+        }
+    }
+}
+
+/// Time-to-live of cached entries for each [`CacheFamily`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CacheTtls {
+    pub token_metadata: Duration,
+    /// Only finalized transactions are cached, so this can be long.
+    pub enriched_transactions: Duration,
+    /// Only finalized transactions are cached, so this can be long.
+    pub raw_transactions: Duration,
+    pub naming_service: Duration,
+}
+
+impl Default for CacheTtls {
+    fn default() -> Self {
+        CacheTtls {
+            token_metadata: Duration::from_secs(60 * 60),
+            enriched_transactions: Duration::from_secs(7 * 24 * 60 * 60),
+            raw_transactions: Duration::from_secs(7 * 24 * 60 * 60),
+            naming_service: Duration::from_secs(10 * 60),
+        }
+    }
+}
+
+impl CacheTtls {
+    pub fn ttl(&self, family: CacheFamily) -> Duration {
+        match family {
+            CacheFamily::TokenMetadata => self.token_metadata,
+            CacheFamily::EnrichedTransactions => self.enriched_transactions,
+            CacheFamily::RawTransactions => self.raw_transactions,
+            CacheFamily::NamingService => self.naming_service,
+        }
+    }
+}
+
+/// Cache hit and miss counters for each [`CacheFamily`].
+#[derive(Debug)]
+pub struct CacheMetrics {
+    counters: HashMap<CacheFamily, (AtomicU64, AtomicU64)>,
+}
+
+impl CacheMetrics {
+    fn new() -> Self {
+        CacheMetrics {
+            counters: CacheFamily::ALL
+                .iter()
+                .map(|family| (*family, (AtomicU64::new(0), AtomicU64::new(0))))
+                .collect(),
+        }
+    }
+
+    pub fn hits(&self, family: CacheFamily) -> u64 {
+        self.counters[&family].0.load(Ordering::Relaxed)
+    }
+
+    pub fn misses(&self, family: CacheFamily) -> u64 {
+        self.counters[&family].1.load(Ordering::Relaxed)
+    }
+
+    fn record(&self, family: CacheFamily, hit: bool) {
+        let (hits, misses) = &self.counters[&family];
+        if hit {
+            hits.fetch_add(1, Ordering::Relaxed);
+        } else {
+            misses.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// The cache attached to a [`HeliusClient`] with [`HeliusClient::with_cache`].
+pub(crate) struct ResponseCache {
+    store: Arc<dyn Cache>,
+    ttls: CacheTtls,
+    metrics: CacheMetrics,
+}
+
+impl ResponseCache {
+    pub(crate) fn get<T: DeserializeOwned>(&self, family: CacheFamily, key: &str) -> Option<T> {
+        let value = self
+            .store
+            .get(&cache_key(family, key))
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok());
+        self.metrics.record(family, value.is_some());
+        value
+    }
+
+    pub(crate) fn insert<T: Serialize>(&self, family: CacheFamily, key: &str, value: &T) {
+        if let Ok(bytes) = serde_json::to_vec(value) {
+            self.store
+                .insert(&cache_key(family, key), bytes, self.ttls.ttl(family));
+        }
+    }
+}
+
+fn cache_key(family: CacheFamily, key: &str) -> String {
+    format!("{}:{}", family.key_prefix(), key)
+}
+
+impl HeliusClient {
+    /// Caches token metadata, finalized transactions and naming service results in `cache`.
+    /// Transactions requested at `Confirmed` commitment always bypass the cache.
+    pub fn with_cache(mut self, cache: Arc<dyn Cache>, ttls: CacheTtls) -> Self {
+        self.cache = Some(ResponseCache {
+            store: cache,
+            ttls,
+            metrics: CacheMetrics::new(),
+        });
+        self
+    }
+
+    /// Returns the cache hit and miss counters, or `None` if no cache is configured.
+    pub fn cache_metrics(&self) -> Option<&CacheMetrics> {
+        self.cache.as_ref().map(|cache| &cache.metrics)
+    }
+
+    /// Serves the inputs found in the cache without a request, passes the remaining ones to `fetch`
+    /// and caches every fetched item under the input it answers, as returned by `key_of`.
+    pub(crate) async fn execute_cached_batch<T, F, Fut, K>(
+        &self,
+        family: Option<CacheFamily>,
+        inputs: Vec<String>,
+        key_of: K,
+        fetch: F,
+    ) -> BatchResponse<T>
+    where
+        T: Serialize + DeserializeOwned + Clone,
+        F: FnOnce(Vec<String>) -> Fut,
+        Fut: Future<Output = BatchResponse<T>>,
+        K: Fn(&T) -> Option<String>,
+    {
+        let (cache, family) = match (&self.cache, family) {
+            (Some(cache), Some(family)) => (cache, family),
+            _ => return fetch(inputs).await,
+        };
+
+        let mut found: HashMap<String, T> = HashMap::new();
+        let mut misses = Vec::new();
+        for input in &inputs {
+            if found.contains_key(input) || misses.contains(input) {
+                continue;
+            }
+            match cache.get::<T>(family, input) {
+                Some(value) => {
+                    found.insert(input.clone(), value);
+                }
+                None => misses.push(input.clone()),
+            }
+        }
+
+        let fetched = fetch(misses).await;
+        let mut unkeyed = Vec::new();
+        for item in fetched.results {
+            match key_of(&item) {
+                Some(key) => {
+                    cache.insert(family, &key, &item);
+                    found.insert(key, item);
+                }
+                None => unkeyed.push(item),
+            }
+        }
+
+        let mut results: Vec<T> = inputs
+            .iter()
+            .filter_map(|input| found.get(input).cloned())
+            .collect();
+        results.extend(unkeyed);
+        BatchResponse {
+            results,
+            failed_chunks: fetched.failed_chunks,
+        }
+    }
+}
+
+struct CacheEntry {
+    expires_at: Instant,
+    value: Vec<u8>,
+}
+
+/// An in-memory [`Cache`] that evicts the least recently used entry once `capacity` entries are stored.
+pub struct InMemoryCache {
+    entries: Mutex<lru::LruCache<String, CacheEntry>>,
+}
+
+impl InMemoryCache {
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        InMemoryCache {
+            entries: Mutex::new(lru::LruCache::new(capacity)),
+        }
+    }
+}
+
+impl Cache for InMemoryCache {
+    fn get(&self, key: &str) -> Option<Vec<u8>> {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(key) {
+            Some(entry) if entry.expires_at > Instant::now() => Some(entry.value.clone()),
+            Some(_) => {
+                entries.pop(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn insert(&self, key: &str, value: Vec<u8>, ttl: Duration) {
+        self.entries.lock().unwrap().put(
+            key.to_string(),
+            CacheEntry {
+                expires_at: Instant::now() + ttl,
+                value,
+            },
+        );
+    }
+}
+
+/// An on-disk [`Cache`] backed by a sled database, so cached responses survive restarts.
+/// Each value is stored with its expiry time (seconds since the unix epoch) as an 8 byte big-endian prefix.
+#[cfg(feature = "disk-cache")]
+pub struct DiskCache {
+    db: sled::Db,
+}
+
+#[cfg(feature = "disk-cache")]
+impl DiskCache {
+    /// Opens (or creates) the database at `path`.
+    pub fn open<P: AsRef<std::path::Path>>(path: P) -> ClientResult<Self> {
+        let db = sled::open(path)
+            .map_err(|e| ClientError::from(ClientErrorKind::Custom(e.to_string())))?;
+        Ok(DiskCache { db })
+    }
+}
+
+#[cfg(feature = "disk-cache")]
+impl Cache for DiskCache {
+    fn get(&self, key: &str) -> Option<Vec<u8>> {
+        let stored = self.db.get(key).ok().flatten()?;
+        if stored.len() < 8 {
+            return None;
+        }
+        let (expires_at, value) = stored.split_at(8);
+        let expires_at = u64::from_be_bytes(expires_at.try_into().ok()?);
+        if expires_at <= unix_now() {
+            let _ = self.db.remove(key);
+            return None;
+        }
+        Some(value.to_vec())
+    }
+
+    fn insert(&self, key: &str, value: Vec<u8>, ttl: Duration) {
+        let expires_at = unix_now().saturating_add(ttl.as_secs());
+        let mut stored = expires_at.to_be_bytes().to_vec();
+        stored.extend(value);
+        let _ = self.db.insert(key, stored);
+    }
+}
+
+#[cfg(feature = "disk-cache")]
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
@@ -0,0 +1,323 @@
+use futures::Future;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::sync::oneshot;
+
+use crate::error::{ClientError, ClientErrorKind, ClientResult};
+use crate::models::{nft::TokenBalancesResponse, structs::TokenMetadata};
+
+use super::{
+    batch::{BatchResponse, FailedChunk},
+    init::HeliusClient,
+};
+
+/// Configuration for coalescing concurrent requests, see [`HeliusClient::with_coalescing`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CoalescingConfig {
+    /// How long the first caller of a batch endpoint waits for other callers to add their inputs before the merged request is sent.
+    pub batch_window: Duration,
+}
+
+impl Default for CoalescingConfig {
+    fn default() -> Self {
+        CoalescingConfig {
+            batch_window: Duration::from_millis(10),
+        }
+    }
+}
+
+pub(crate) struct Coalescer {
+    pub(crate) config: CoalescingConfig,
+    pub(crate) token_balances: SingleFlight<TokenBalancesResponse>,
+    pub(crate) tokens_metadata: BatchWindow<TokenMetadata>,
+}
+
+impl HeliusClient {
+    /// Coalesces concurrent requests. Concurrent `get_token_balances` calls for the same address share one in-flight request,
+    /// and `get_tokens_metadata` calls made within `config.batch_window` of each other are merged into one batched request
+    /// whose results are fanned back out to each caller.
+    pub fn with_coalescing(mut self, config: CoalescingConfig) -> Self {
+        self.coalescer = Some(Coalescer {
+            config,
+            token_balances: SingleFlight::new(),
+            tokens_metadata: BatchWindow::new(),
+        });
+        self
+    }
+}
+
+/// The callers waiting for the result of each in-flight request, by key.
+type Waiters<T> = Mutex<HashMap<String, Vec<oneshot::Sender<Result<T, Arc<ClientError>>>>>>;
+
+/// Deduplicates concurrent requests with the same key: the first caller (the leader) issues the request and every caller that
+/// arrives while it is in flight receives a copy of its result. An error with waiters is shared as [`ClientErrorKind::Coalesced`].
+pub(crate) struct SingleFlight<T> {
+    in_flight: Waiters<T>,
+}
+
+impl<T: Clone> SingleFlight<T> {
+    fn new() -> Self {
+        SingleFlight {
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub(crate) async fn run<F, Fut>(&self, key: String, request: F) -> ClientResult<T>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = ClientResult<T>>,
+    {
+        let receiver = {
+            let mut in_flight = self.in_flight.lock().unwrap();
+            match in_flight.get_mut(&key) {
+                Some(waiters) => {
+                    let (sender, receiver) = oneshot::channel();
+                    waiters.push(sender);
+                    Some(receiver)
+                }
+                None => {
+                    in_flight.insert(key.clone(), Vec::new());
+                    None
+                }
+            }
+        };
+
+        if let Some(receiver) = receiver {
+            return match receiver.await {
+                Ok(result) => result.map_err(coalesced_error),
+                Err(_) => Err(cancelled_error()),
+            };
+        }
+
+        // If the leader is dropped mid-request the guard removes the entry, which drops the waiters' senders.
+        let guard = FlightGuard {
+            flight: self,
+            key: Some(key),
+        };
+        let result = request().await;
+        let waiters = guard.finish();
+        match result {
+            Ok(value) => {
+                for waiter in waiters {
+                    let _ = waiter.send(Ok(value.clone()));
+                }
+                Ok(value)
+            }
+            Err(error) if waiters.is_empty() => Err(error),
+            Err(error) => {
+                let error = Arc::new(error);
+                for waiter in waiters {
+                    let _ = waiter.send(Err(error.clone()));
+                }
+                Err(coalesced_error(error))
+            }
+        }
+    }
+}
+
+struct FlightGuard<'a, T> {
+    flight: &'a SingleFlight<T>,
+    key: Option<String>,
+}
+
+impl<'a, T> FlightGuard<'a, T> {
+    fn finish(mut self) -> Vec<oneshot::Sender<Result<T, Arc<ClientError>>>> {
+        self.take_waiters()
+    }
+
+    fn take_waiters(&mut self) -> Vec<oneshot::Sender<Result<T, Arc<ClientError>>>> {
+        match self.key.take() {
+            Some(key) => self
+                .flight
+                .in_flight
+                .lock()
+                .unwrap()
+                .remove(&key)
+                .unwrap_or_default(),
+            None => Vec::new(),
+        }
+    }
+}
+
+impl<'a, T> Drop for FlightGuard<'a, T> {
+    fn drop(&mut self) {
+        self.take_waiters();
+    }
+}
+
+/// Merges the inputs of concurrent batch requests: the first caller opens a window, every caller that arrives before it
+/// closes adds its inputs, and the first caller then issues one request for all of them and fans the results out.
+pub(crate) struct BatchWindow<T> {
+    pending: Mutex<Option<PendingBatch<T>>>,
+}
+
+struct PendingBatch<T> {
+    inputs: Vec<String>,
+    waiters: Vec<oneshot::Sender<Arc<MergedBatch<T>>>>,
+}
+
+impl<T: Clone> BatchWindow<T> {
+    fn new() -> Self {
+        BatchWindow {
+            pending: Mutex::new(None),
+        }
+    }
+
+    /// Adds `inputs` to the current window (opening one if there is none) and returns the results for `inputs`.
+    /// `key_of` maps a result back to the input it answers.
+    pub(crate) async fn run<F, Fut, K>(
+        &self,
+        window: Duration,
+        inputs: Vec<String>,
+        key_of: K,
+        fetch: F,
+    ) -> BatchResponse<T>
+    where
+        F: FnOnce(Vec<String>) -> Fut,
+        Fut: Future<Output = BatchResponse<T>>,
+        K: Fn(&T) -> Option<String>,
+    {
+        let receiver = {
+            let mut pending = self.pending.lock().unwrap();
+            match pending.as_mut() {
+                Some(batch) => {
+                    let (sender, receiver) = oneshot::channel();
+                    batch.inputs.extend(inputs.iter().cloned());
+                    batch.waiters.push(sender);
+                    Some(receiver)
+                }
+                None => {
+                    *pending = Some(PendingBatch {
+                        inputs: inputs.clone(),
+                        waiters: Vec::new(),
+                    });
+                    None
+                }
+            }
+        };
+
+        let merged = match receiver {
+            Some(receiver) => match receiver.await {
+                Ok(merged) => merged,
+                Err(_) => {
+                    return BatchResponse {
+                        results: Vec::new(),
+                        failed_chunks: vec![FailedChunk {
+                            inputs,
+                            error: cancelled_error(),
+                        }],
+                    }
+                }
+            },
+            None => {
+                // If the leader is dropped while the window is open the guard discards the batch, which drops the waiters' senders.
+                let guard = WindowGuard {
+                    window: self,
+                    closed: false,
+                };
+                tokio::time::sleep(window).await;
+                let batch = guard.close();
+
+                let mut seen = HashSet::new();
+                let batch_inputs: Vec<String> = batch
+                    .inputs
+                    .into_iter()
+                    .filter(|input| seen.insert(input.clone()))
+                    .collect();
+                let merged = Arc::new(MergedBatch::new(fetch(batch_inputs).await, &key_of));
+                for waiter in batch.waiters {
+                    let _ = waiter.send(merged.clone());
+                }
+                merged
+            }
+        };
+        merged.extract(&inputs)
+    }
+}
+
+struct WindowGuard<'a, T> {
+    window: &'a BatchWindow<T>,
+    closed: bool,
+}
+
+impl<'a, T> WindowGuard<'a, T> {
+    fn close(mut self) -> PendingBatch<T> {
+        self.closed = true;
+        self.window
+            .pending
+            .lock()
+            .unwrap()
+            .take()
+            .expect("batch window closed twice")
+    }
+}
+
+impl<'a, T> Drop for WindowGuard<'a, T> {
+    fn drop(&mut self) {
+        if !self.closed {
+            self.window.pending.lock().unwrap().take();
+        }
+    }
+}
+
+/// The response to a merged batch request, indexed so each caller can pick out the results for its own inputs.
+struct MergedBatch<T> {
+    results: HashMap<String, T>,
+    failures: Vec<(HashSet<String>, Arc<ClientError>)>,
+}
+
+impl<T: Clone> MergedBatch<T> {
+    fn new<K: Fn(&T) -> Option<String>>(response: BatchResponse<T>, key_of: &K) -> Self {
+        MergedBatch {
+            results: response
+                .results
+                .into_iter()
+                .filter_map(|item| key_of(&item).map(|key| (key, item)))
+                .collect(),
+            failures: response
+                .failed_chunks
+                .into_iter()
+                .map(|chunk| (chunk.inputs.into_iter().collect(), Arc::new(chunk.error)))
+                .collect(),
+        }
+    }
+
+    fn extract(&self, inputs: &[String]) -> BatchResponse<T> {
+        let results = inputs
+            .iter()
+            .filter_map(|input| self.results.get(input).cloned())
+            .collect();
+        let failed_chunks = self
+            .failures
+            .iter()
+            .filter_map(|(failed_inputs, error)| {
+                let inputs: Vec<String> = inputs
+                    .iter()
+                    .filter(|input| failed_inputs.contains(*input))
+                    .cloned()
+                    .collect();
+                (!inputs.is_empty()).then(|| FailedChunk {
+                    inputs,
+                    error: coalesced_error(error.clone()),
+                })
+            })
+            .collect();
+        BatchResponse {
+            results,
+            failed_chunks,
+        }
+    }
+}
+
+fn coalesced_error(error: Arc<ClientError>) -> ClientError {
+    ClientError::from(ClientErrorKind::Coalesced(error))
+}
+
+fn cancelled_error() -> ClientError {
+    ClientError::from(ClientErrorKind::Custom(
+        "Coalesced request was cancelled before it completed".to_string(),
+    ))
+}
@@ -0,0 +1,225 @@
+use std::{
+    collections::HashMap,
+    sync::{Mutex, RwLock},
+};
+
+use crate::error::{ClientError, ClientErrorKind, ClientResult};
+
+use super::init::HeliusClient;
+
+/// The billing categories of Helius requests, see [`CreditCostTable`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CreditCategory {
+    /// DAS methods and the token, NFT and naming APIs.
+    Das,
+    /// The enhanced and raw transaction APIs, including NFT events.
+    EnhancedTransactions,
+    /// Webhook management.
+    Webhooks,
+    /// Standard Solana RPC methods.
+    StandardRpc,
+    /// `sendTransaction`.
+    Send,
+}
+
+/// DAS methods served over RPC.
+const DAS_RPC_METHODS: &[&str] = &[
+    "getAsset",
+    "getAssetBatch",
+    "getAssetProof",
+    "getAssetProofBatch",
+    "getAssetsByOwner",
+    "getAssetsByAuthority",
+    "getAssetsByCreator",
+    "getAssetsByGroup",
+    "searchAssets",
+    "getSignaturesForAsset",
+    "getTokenAccounts",
+];
+
+impl CreditCategory {
+    /// Returns the category of `endpoint`, which is either a [`HeliusClient`] REST method name or an RPC method name.
+    pub fn of(endpoint: &str) -> Self {
+        match endpoint {
+            "get_transactions_for_address"
+            | "get_transactions_by_hash"
+            | "get_enriched_transactions"
+            | "get_enriched_transactions_by_hash"
+            | "get_nft_events"
+            | "get_nft_events_for_address" => CreditCategory::EnhancedTransactions,
+            "create_webhook" | "get_webhooks" | "get_webhook" | "edit_webhook" | "delete_webhook" => {
+                CreditCategory::Webhooks
+            }
+            "get_token_balances"
+            | "get_nfts"
+            | "get_nfts_metadata"
+            | "get_tokens_metadata"
+            | "get_active_nft_listings"
+            | "get_mint_list"
+            | "get_naming_service_names"
+            | "get_assets_by_owner" => CreditCategory::Das,
+            "sendTransaction" => CreditCategory::Send,
+            method if DAS_RPC_METHODS.contains(&method) => CreditCategory::Das,
+            _ => CreditCategory::StandardRpc,
+        }
+    }
+}
+
+/// The estimated credit cost of a single request per [`CreditCategory`], with optional per-endpoint overrides.
+/// The defaults are estimates; set them to match your plan.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreditCostTable {
+    pub das: u64,
+    pub enhanced_transactions: u64,
+    pub webhooks: u64,
+    pub standard_rpc: u64,
+    pub send: u64,
+    /// Costs that take precedence over the category cost, keyed by endpoint name, e.g. `getProgramAccounts`.
+    pub overrides: HashMap<String, u64>,
+}
+
+impl Default for CreditCostTable {
+    fn default() -> Self {
+        CreditCostTable {
+            das: 10,
+            enhanced_transactions: 100,
+            webhooks: 100,
+            standard_rpc: 1,
+            send: 1,
+            overrides: HashMap::from([("getProgramAccounts".to_string(), 10)]),
+        }
+    }
+}
+
+impl CreditCostTable {
+    /// Returns the cost of a single request to `endpoint`.
+    pub fn cost(&self, endpoint: &str) -> u64 {
+        if let Some(cost) = self.overrides.get(endpoint) {
+            return *cost;
+        }
+        match CreditCategory::of(endpoint) {
+            CreditCategory::Das => self.das,
+            CreditCategory::EnhancedTransactions => self.enhanced_transactions,
+            CreditCategory::Webhooks => self.webhooks,
+            CreditCategory::StandardRpc => self.standard_rpc,
+            CreditCategory::Send => self.send,
+        }
+    }
+}
+
+/// What happens when a request would take the credits used past [`CreditBudget::limit`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BudgetEnforcement {
+    /// The request is rejected with an error before it is sent.
+    Hard,
+    /// The request is sent and a warning is logged.
+    Soft,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CreditBudget {
+    pub limit: u64,
+    pub enforcement: BudgetEnforcement,
+}
+
+/// Requests sent to an endpoint and the credits they are estimated to have used.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct EndpointCredits {
+    pub requests: u64,
+    pub credits: u64,
+}
+
+struct CreditConfig {
+    table: CreditCostTable,
+    budget: Option<CreditBudget>,
+}
+
+#[derive(Default)]
+struct CreditLedger {
+    total: u64,
+    endpoints: HashMap<String, EndpointCredits>,
+}
+
+/// Tracks the credits used by a [`HeliusClient`], shared by its REST requests and its RPC sender.
+/// Does nothing until [`HeliusClient::with_credit_accounting`] configures it.
+#[derive(Default)]
+pub(crate) struct CreditTracker {
+    config: RwLock<Option<CreditConfig>>,
+    ledger: Mutex<CreditLedger>,
+}
+
+impl CreditTracker {
+    /// Charges a request to `endpoint`, or fails without charging it if it would exceed a hard budget.
+    pub(crate) fn charge(&self, endpoint: &str) -> ClientResult<()> {
+        let config = self.config.read().unwrap();
+        let config = match config.as_ref() {
+            Some(config) => config,
+            None => return Ok(()),
+        };
+        let cost = config.table.cost(endpoint);
+
+        let mut ledger = self.ledger.lock().unwrap();
+        if let Some(budget) = config.budget {
+            let total = ledger.total + cost;
+            if total > budget.limit {
+                match budget.enforcement {
+                    BudgetEnforcement::Hard => {
+                        return Err(ClientError::from(ClientErrorKind::Custom(format!(
+                            "Helius credit budget exceeded: {} costs {} credits, {} of {} already used",
+                            endpoint, cost, ledger.total, budget.limit
+                        ))));
+                    }
+                    BudgetEnforcement::Soft => tracing::warn!(
+                        endpoint,
+                        cost,
+                        used = ledger.total,
+                        limit = budget.limit,
+                        "Helius credit budget exceeded"
+                    ),
+                }
+            }
+        }
+
+        ledger.total += cost;
+        let usage = ledger.endpoints.entry(endpoint.to_string()).or_default();
+        usage.requests += 1;
+        usage.credits += cost;
+        #[cfg(feature = "metrics")]
+        metrics::counter!("helius_credits_total", cost, "endpoint" => endpoint.to_string());
+        Ok(())
+    }
+
+    /// Returns the cost of a request to `endpoint` by the configured table, or the default table if none is configured.
+    pub(crate) fn cost(&self, endpoint: &str) -> u64 {
+        match self.config.read().unwrap().as_ref() {
+            Some(config) => config.table.cost(endpoint),
+            None => CreditCostTable::default().cost(endpoint),
+        }
+    }
+}
+
+impl HeliusClient {
+    /// Tracks the estimated credits used by every REST and RPC request according to `table`, and enforces `budget` if
+    /// one is given. Requests served from the cache or the cassette of a replaying [`Vcr`](super::vcr::Vcr) are free.
+    pub fn with_credit_accounting(self, table: CreditCostTable, budget: Option<CreditBudget>) -> Self {
+        *self.credits.config.write().unwrap() = Some(CreditConfig { table, budget });
+        self
+    }
+
+    /// Returns the estimated credits used since the client was created or [`reset_credit_usage`](HeliusClient::reset_credit_usage) was last called.
+    pub fn credits_used(&self) -> u64 {
+        self.credits.ledger.lock().unwrap().total
+    }
+
+    /// Returns the requests sent and credits used per endpoint, keyed by REST method or RPC method name.
+    pub fn credit_usage(&self) -> HashMap<String, EndpointCredits> {
+        self.credits.ledger.lock().unwrap().endpoints.clone()
+    }
+
+    /// Clears the credit totals, including those counted against the quotas of pooled keys, e.g. at the start of a
+    /// billing period.
+    pub fn reset_credit_usage(&self) {
+        *self.credits.ledger.lock().unwrap() = CreditLedger::default();
+        self.keys.reset_credits();
+    }
+}
@@ -0,0 +1,58 @@
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+use crate::error::{ClientError, ClientErrorKind, ClientResult, RpcResponseError};
+use crate::models::das::AssetsByOwner;
+
+use super::{
+    init::{HeliusClient, MAX_ASSETS_PER_PAGE},
+    parse_response,
+};
+
+impl HeliusClient {
+    /// Returns a page of the assets held by `owner`, with fungible tokens and the native balance shown, e.g. for
+    /// their prices. DAS `getAssetsByOwner` request to the RPC endpoint of the client.
+    /// * `owner` - The address that you want assets for.
+    /// * `page` - The page to fetch, starting at 1. Pages hold up to [`MAX_ASSETS_PER_PAGE`] assets.
+    pub async fn get_assets_by_owner(&self, owner: String, page: u32) -> ClientResult<AssetsByOwner> {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": "helius-rust-client",
+            "method": "getAssetsByOwner",
+            "params": {
+                "ownerAddress": owner,
+                "page": page,
+                "limit": MAX_ASSETS_PER_PAGE,
+                "displayOptions": {
+                    "showFungible": true,
+                    "showNativeBalance": true,
+                },
+            },
+        });
+
+        let response = self
+            .send(
+                "get_assets_by_owner",
+                self.http_client
+                    .post(&self.rpc_url)
+                    .header("accept", "application/json")
+                    .json(&body),
+            )
+            .await;
+
+        rpc_result(parse_response(response).await?)
+    }
+}
+
+/// Returns the result of a JSON-RPC response, or its error as a [`ClientErrorKind::RpcResponse`] error.
+fn rpc_result<T: DeserializeOwned>(mut response: Value) -> ClientResult<T> {
+    if response["error"].is_object() {
+        let kind = match serde_json::from_value::<RpcResponseError>(response["error"].take()) {
+            Ok(error) => ClientErrorKind::RpcResponse(error),
+            Err(e) => ClientErrorKind::Custom(format!("Failed to deserialize RPC error response: {}", e)),
+        };
+        return Err(ClientError::from(kind));
+    }
+    serde_json::from_value(response["result"].take())
+        .map_err(|e| ClientError::from(ClientErrorKind::SerdeJson(e)))
+}
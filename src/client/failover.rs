@@ -0,0 +1,321 @@
+use solana_client::rpc_request::RpcRequest;
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use super::{init::HeliusClient, secret::redact_url_str, sender::HttpSender};
+
+/// Default for [`RpcFailoverConfig::health_check_interval`].
+pub const DEFAULT_HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+/// Default for [`RpcFailoverConfig::max_slot_lag`].
+pub const DEFAULT_MAX_SLOT_LAG: u64 = 50;
+/// Default for [`RpcFailoverConfig::max_consecutive_errors`].
+pub const DEFAULT_MAX_CONSECUTIVE_ERRORS: u32 = 3;
+
+/// Read-only methods hedged by default, see [`RpcFailoverConfig::hedged_methods`].
+const HEDGED_METHODS: &[RpcRequest] = &[
+    RpcRequest::GetAccountInfo,
+    RpcRequest::GetBalance,
+    RpcRequest::GetBlock,
+    RpcRequest::GetBlockHeight,
+    RpcRequest::GetLatestBlockhash,
+    RpcRequest::GetMultipleAccounts,
+    RpcRequest::GetSignatureStatuses,
+    RpcRequest::GetSignaturesForAddress,
+    RpcRequest::GetSlot,
+    RpcRequest::GetTokenAccountBalance,
+    RpcRequest::GetTokenAccountsByOwner,
+    RpcRequest::GetTransaction,
+];
+
+/// Fallback RPC endpoints for [`HeliusClient::rpc_client`], see [`HeliusClient::with_rpc_failover`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpcFailoverConfig {
+    /// Full URLs of the endpoints to use when the Helius endpoint is unhealthy, in order of preference.
+    /// They are sent as given, without a Helius API key.
+    pub fallback_urls: Vec<String>,
+    /// How often every endpoint is checked with `getHealth` and `getSlot`.
+    pub health_check_interval: Duration,
+    /// How many slots an endpoint may trail the most advanced endpoint before it is routed around.
+    pub max_slot_lag: u64,
+    /// How many requests in a row may fail on an endpoint before it is routed around until its next health check.
+    pub max_consecutive_errors: u32,
+    /// If set, requests for [`hedged_methods`](RpcFailoverConfig::hedged_methods) still unanswered after this delay
+    /// are also sent to the next endpoint, and the first successful response is used.
+    pub hedge_delay: Option<Duration>,
+    /// RPC methods to hedge, by name. Defaults to common read-only methods.
+    pub hedged_methods: Vec<String>,
+}
+
+impl Default for RpcFailoverConfig {
+    fn default() -> Self {
+        RpcFailoverConfig {
+            fallback_urls: Vec::new(),
+            health_check_interval: DEFAULT_HEALTH_CHECK_INTERVAL,
+            max_slot_lag: DEFAULT_MAX_SLOT_LAG,
+            max_consecutive_errors: DEFAULT_MAX_CONSECUTIVE_ERRORS,
+            hedge_delay: None,
+            hedged_methods: HEDGED_METHODS.iter().map(ToString::to_string).collect(),
+        }
+    }
+}
+
+impl RpcFailoverConfig {
+    pub fn new(fallback_urls: Vec<String>) -> Self {
+        RpcFailoverConfig {
+            fallback_urls,
+            ..RpcFailoverConfig::default()
+        }
+    }
+
+    pub fn with_hedge_delay(mut self, hedge_delay: Duration) -> Self {
+        self.hedge_delay = Some(hedge_delay);
+        self
+    }
+}
+
+/// The last known state of an RPC endpoint, see [`HeliusClient::rpc_endpoint_health`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EndpointHealth {
+    /// The endpoint URL with any `api-key` query parameter redacted.
+    pub url: String,
+    /// Whether the last health check succeeded. Endpoints are assumed healthy until checked.
+    pub healthy: bool,
+    /// The slot reported by the last health check.
+    pub slot: Option<u64>,
+    /// How many slots the endpoint trails the most advanced endpoint.
+    pub slots_behind: u64,
+    pub consecutive_errors: u32,
+    /// The latency of the last health check.
+    pub latency: Option<Duration>,
+}
+
+/// The outcome of checking a single endpoint.
+pub(crate) struct HealthCheck {
+    pub healthy: bool,
+    pub slot: Option<u64>,
+    pub latency: Duration,
+}
+
+/// How a request is routed, see [`RpcFailover::route`].
+pub(crate) struct Route {
+    /// Endpoint indexes to try in order. Index 0 is the Helius endpoint.
+    pub endpoints: Vec<usize>,
+    pub hedge_delay: Option<Duration>,
+}
+
+/// When health checks should run before routing a request.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum HealthCheckDue {
+    /// No check has run yet, so the request waits for one.
+    Now,
+    /// The last check is older than the interval; it runs in the background.
+    Background,
+}
+
+struct EndpointState {
+    url: String,
+    sender: Option<Arc<HttpSender>>,
+    healthy: bool,
+    slot: Option<u64>,
+    consecutive_errors: u32,
+    latency: Option<Duration>,
+}
+
+struct FailoverState {
+    config: RpcFailoverConfig,
+    endpoints: Vec<EndpointState>,
+    last_check: Option<Instant>,
+}
+
+impl FailoverState {
+    fn max_slot(&self) -> Option<u64> {
+        self.endpoints.iter().filter_map(|endpoint| endpoint.slot).max()
+    }
+
+    fn slots_behind(&self, endpoint: &EndpointState) -> u64 {
+        match (self.max_slot(), endpoint.slot) {
+            (Some(max_slot), Some(slot)) => max_slot.saturating_sub(slot),
+            _ => 0,
+        }
+    }
+
+    fn is_usable(&self, endpoint: &EndpointState) -> bool {
+        endpoint.healthy
+            && endpoint.consecutive_errors < self.config.max_consecutive_errors
+            && self.slots_behind(endpoint) <= self.config.max_slot_lag
+    }
+}
+
+/// The RPC endpoints of a [`HeliusClient`], shared with its RPC sender. Does nothing until
+/// [`HeliusClient::with_rpc_failover`] configures it.
+pub(crate) struct RpcFailover {
+    primary_url: String,
+    timeout: Option<Duration>,
+    state: Mutex<Option<FailoverState>>,
+    checking: AtomicBool,
+}
+
+impl RpcFailover {
+    /// `primary_url` is the redacted URL of the Helius endpoint; fallbacks are sent with `timeout`.
+    pub(crate) fn new(primary_url: String, timeout: Option<Duration>) -> Self {
+        RpcFailover {
+            primary_url,
+            timeout,
+            state: Mutex::new(None),
+            checking: AtomicBool::new(false),
+        }
+    }
+
+    fn configure(&self, config: RpcFailoverConfig) {
+        let primary = EndpointState {
+            url: self.primary_url.clone(),
+            sender: None,
+            healthy: true,
+            slot: None,
+            consecutive_errors: 0,
+            latency: None,
+        };
+        let fallbacks = config.fallback_urls.iter().map(|url| EndpointState {
+            url: redact_url_str(url),
+            sender: Some(Arc::new(HttpSender::new(url.clone(), self.timeout))),
+            healthy: true,
+            slot: None,
+            consecutive_errors: 0,
+            latency: None,
+        });
+        let endpoints = std::iter::once(primary).chain(fallbacks).collect();
+        *self.state.lock().unwrap() = Some(FailoverState {
+            endpoints,
+            config,
+            last_check: None,
+        });
+    }
+
+    /// Returns the endpoints to try for `request`, usable ones first in order of preference,
+    /// or `None` if failover is not configured.
+    pub(crate) fn route(&self, request: RpcRequest) -> Option<Route> {
+        let state = self.state.lock().unwrap();
+        let state = state.as_ref()?;
+        let (mut usable, unusable): (Vec<usize>, Vec<usize>) = (0..state.endpoints.len())
+            .partition(|index| state.is_usable(&state.endpoints[*index]));
+        usable.extend(unusable);
+        let method = request.to_string();
+        Some(Route {
+            endpoints: usable,
+            hedge_delay: state
+                .config
+                .hedge_delay
+                .filter(|_| state.config.hedged_methods.contains(&method)),
+        })
+    }
+
+    /// Returns the sender of the fallback endpoint at `index`, or `None` for the Helius endpoint.
+    pub(crate) fn sender(&self, index: usize) -> Option<Arc<HttpSender>> {
+        let state = self.state.lock().unwrap();
+        state.as_ref()?.endpoints.get(index)?.sender.clone()
+    }
+
+    pub(crate) fn endpoint_count(&self) -> usize {
+        self.state
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map_or(0, |state| state.endpoints.len())
+    }
+
+    /// Records whether a request to the endpoint at `index` succeeded.
+    pub(crate) fn report(&self, index: usize, success: bool) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(endpoint) = state.as_mut().and_then(|state| state.endpoints.get_mut(index)) {
+            if success {
+                endpoint.consecutive_errors = 0;
+            } else {
+                endpoint.consecutive_errors += 1;
+            }
+        }
+    }
+
+    /// Claims the next health check if one is due. The caller must run it and then call [`finish_health_check`].
+    ///
+    /// [`finish_health_check`]: RpcFailover::finish_health_check
+    pub(crate) fn health_check_due(&self) -> Option<HealthCheckDue> {
+        let due = {
+            let state = self.state.lock().unwrap();
+            match state.as_ref()?.last_check {
+                None => HealthCheckDue::Now,
+                Some(last_check) => {
+                    let interval = state.as_ref()?.config.health_check_interval;
+                    if last_check.elapsed() < interval {
+                        return None;
+                    }
+                    HealthCheckDue::Background
+                }
+            }
+        };
+        self.checking
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .ok()
+            .map(|_| due)
+    }
+
+    /// Records the results of a health check, one per endpoint in order. Clears the error counts of healthy endpoints.
+    pub(crate) fn finish_health_check(&self, checks: Vec<HealthCheck>) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(state) = state.as_mut() {
+            for (endpoint, check) in state.endpoints.iter_mut().zip(checks) {
+                endpoint.healthy = check.healthy;
+                endpoint.slot = check.slot;
+                endpoint.latency = Some(check.latency);
+                if check.healthy {
+                    endpoint.consecutive_errors = 0;
+                }
+            }
+            state.last_check = Some(Instant::now());
+        }
+        self.checking.store(false, Ordering::Release);
+    }
+
+    fn health(&self) -> Vec<EndpointHealth> {
+        let state = self.state.lock().unwrap();
+        let state = match state.as_ref() {
+            Some(state) => state,
+            None => return Vec::new(),
+        };
+        state
+            .endpoints
+            .iter()
+            .map(|endpoint| EndpointHealth {
+                url: endpoint.url.clone(),
+                healthy: endpoint.healthy,
+                slot: endpoint.slot,
+                slots_behind: state.slots_behind(endpoint),
+                consecutive_errors: endpoint.consecutive_errors,
+                latency: endpoint.latency,
+            })
+            .collect()
+    }
+}
+
+impl HeliusClient {
+    /// Sends RPC requests to the endpoints of `config` when the Helius endpoint is unhealthy, trails the other endpoints
+    /// by more than [`RpcFailoverConfig::max_slot_lag`] slots, or keeps failing. Endpoints are checked with `getHealth`
+    /// and `getSlot` before the first request and then in the background every
+    /// [`RpcFailoverConfig::health_check_interval`]. A request that fails with a transport error or a node health error
+    /// is retried on the next endpoint. REST requests are not affected. Neither health checks nor requests sent to
+    /// fallback endpoints are counted by credit accounting.
+    pub fn with_rpc_failover(self, config: RpcFailoverConfig) -> Self {
+        self.failover.configure(config);
+        self
+    }
+
+    /// Returns the last known state of every RPC endpoint, the Helius endpoint first,
+    /// or nothing if [`with_rpc_failover`](HeliusClient::with_rpc_failover) was not called.
+    pub fn rpc_endpoint_health(&self) -> Vec<EndpointHealth> {
+        self.failover.health()
+    }
+}
@@ -1,71 +1,113 @@
+use super::{
+    cache::ResponseCache,
+    coalesce::Coalescer,
+    credits::CreditTracker,
+    keys::KeyPool,
+    secret::{ApiKey, ApiKeyPlacement},
+    vcr::Vcr,
+};
+#[cfg(feature = "rpc")]
+use super::{failover::RpcFailover, secret::REDACTED, sender::HeliusSender};
+use crate::types::ClusterType;
 use reqwest::Client as RestClient;
+#[cfg(feature = "rpc")]
 use solana_client::nonblocking::rpc_client::RpcClient;
-use solana_client::rpc_client::Mocks;
-use solana_sdk::{commitment_config::CommitmentConfig, genesis_config::ClusterType};
+#[cfg(feature = "rpc")]
+use solana_client::rpc_client::{Mocks, RpcClientConfig};
+#[cfg(feature = "rpc")]
+use solana_sdk::commitment_config::CommitmentConfig;
+use std::sync::Arc;
+#[cfg(feature = "rpc")]
 use std::time::Duration;
 
+pub const API_URL: &str = "https://api.helius.xyz";
 pub const API_URL_V0: &str = "https://api.helius.xyz/v0";
 pub const API_URL_V1: &str = "https://api.helius.xyz/v1";
 pub const MAINNET_RPC_URL: &str = "https://rpc.helius.xyz/?api-key=";
 pub const DEVNET_RPC_URL: &str = "https://rpc-devnet.helius.xyz/?api-key=";
 
+/// Default for [`HeliusClient::max_concurrent_requests`].
+pub const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 4;
+/// Maximum number of mint addresses the API accepts in a single metadata request.
+pub const MAX_MINTS_PER_REQUEST: usize = 100;
+/// Maximum number of transaction hashes the API accepts in a single transactions request.
+pub const MAX_TRANSACTIONS_PER_REQUEST: usize = 100;
+/// Maximum number of assets the DAS API returns in a single page.
+pub const MAX_ASSETS_PER_PAGE: u32 = 1000;
+
 pub struct HeliusClient {
+    /// The Solana RPC client, sending requests to the Helius RPC endpoint. Requires the `rpc` feature.
+    #[cfg(feature = "rpc")]
     pub rpc_client: RpcClient,
     pub http_client: RestClient,
     pub cluster: ClusterType,
-    pub(crate) api_key: String,
+    /// Upper bound on the number of requests a single client call issues concurrently, e.g. when fetching every page of a paginated endpoint or every chunk of a batched one.
+    pub max_concurrent_requests: usize,
+    pub(crate) keys: Arc<KeyPool>,
+    pub(crate) api_key_placement: ApiKeyPlacement,
+    pub(crate) api_url_v0: String,
+    pub(crate) api_url_v1: String,
+    /// The RPC URL without the API key, for the DAS methods sent like REST requests.
+    pub(crate) rpc_url: String,
+    pub(crate) cache: Option<ResponseCache>,
+    pub(crate) coalescer: Option<Coalescer>,
+    pub(crate) vcr: Option<Vcr>,
+    pub(crate) credits: Arc<CreditTracker>,
+    #[cfg(feature = "rpc")]
+    pub(crate) failover: Arc<RpcFailover>,
 }
 
 impl HeliusClient {
     pub fn new(api_key: String, cluster_type: ClusterType) -> Self {
-        let url = format_url(api_key.clone(), cluster_type);
-        HeliusClient {
-            rpc_client: RpcClient::new(url),
-            cluster: cluster_type,
-            api_key,
-            http_client: reqwest::Client::new(),
-        }
+        HeliusClient::with_url_prefix(api_key, cluster_type, rpc_url_prefix(cluster_type))
     }
 
+    #[cfg(feature = "rpc")]
     pub fn new_with_commitment(
         api_key: String,
         cluster_type: ClusterType,
         commitment_config: CommitmentConfig,
     ) -> Self {
-        let url = format_url(api_key.clone(), cluster_type);
-        HeliusClient {
-            rpc_client: RpcClient::new_with_commitment(url, commitment_config),
-            cluster: cluster_type,
+        HeliusClient::from_url(
             api_key,
-            http_client: reqwest::Client::new(),
-        }
+            cluster_type,
+            rpc_url_prefix(cluster_type),
+            None,
+            RpcClientConfig::with_commitment(commitment_config),
+            None,
+        )
     }
 
+    #[cfg(feature = "rpc")]
     pub fn new_with_timeout(api_key: String, cluster_type: ClusterType, timeout: Duration) -> Self {
-        let url = format_url(api_key.clone(), cluster_type);
-        HeliusClient {
-            rpc_client: RpcClient::new_with_timeout(url, timeout),
-            cluster: cluster_type,
+        HeliusClient::from_url(
             api_key,
-            http_client: reqwest::Client::new(),
-        }
+            cluster_type,
+            rpc_url_prefix(cluster_type),
+            Some(timeout),
+            RpcClientConfig::with_commitment(CommitmentConfig::default()),
+            None,
+        )
     }
 
+    #[cfg(feature = "rpc")]
     pub fn new_with_timeout_and_commitment(
         api_key: String,
         cluster_type: ClusterType,
         timeout: Duration,
         commitment_config: CommitmentConfig,
     ) -> Self {
-        let url = format_url(api_key.clone(), cluster_type);
-        HeliusClient {
-            rpc_client: RpcClient::new_with_timeout_and_commitment(url, timeout, commitment_config),
-            cluster: cluster_type,
+        HeliusClient::from_url(
             api_key,
-            http_client: reqwest::Client::new(),
-        }
+            cluster_type,
+            rpc_url_prefix(cluster_type),
+            Some(timeout),
+            RpcClientConfig::with_commitment(commitment_config),
+            None,
+        )
     }
 
+    #[cfg(feature = "rpc")]
     pub fn new_with_timeouts_and_commitment(
         api_key: String,
         cluster_type: ClusterType,
@@ -73,46 +115,164 @@ impl HeliusClient {
         commitment_config: CommitmentConfig,
         confirm_transaction_initial_timeout: Duration,
     ) -> Self {
-        let url = format_url(api_key.clone(), cluster_type);
-        HeliusClient {
-            rpc_client: RpcClient::new_with_timeouts_and_commitment(
-                url,
-                timeout,
-                commitment_config,
-                confirm_transaction_initial_timeout,
-            ),
-            cluster: cluster_type,
+        HeliusClient::from_url(
             api_key,
-            http_client: reqwest::Client::new(),
-        }
+            cluster_type,
+            rpc_url_prefix(cluster_type),
+            Some(timeout),
+            RpcClientConfig {
+                commitment_config,
+                confirm_transaction_initial_timeout: Some(confirm_transaction_initial_timeout),
+            },
+            None,
+        )
     }
 
+    /// Creates a client that sends REST requests to `api_url` (instead of [`API_URL`]) and RPC requests to `rpc_url`
+    /// (instead of the cluster's Helius RPC URL), e.g. to point it at a local stand-in server or a proxy.
+    /// The API key is appended to `rpc_url` as the `api-key` query parameter.
+    pub fn new_with_urls(
+        api_key: String,
+        cluster_type: ClusterType,
+        api_url: String,
+        rpc_url: String,
+    ) -> Self {
+        let url_prefix = format!("{}/?api-key=", rpc_url.trim_end_matches('/'));
+        let mut client = HeliusClient::with_url_prefix(api_key, cluster_type, url_prefix);
+        let api_url = api_url.trim_end_matches('/');
+        client.api_url_v0 = format!("{}/v0", api_url);
+        client.api_url_v1 = format!("{}/v1", api_url);
+        client
+    }
+
+    #[cfg(feature = "rpc")]
     pub fn new_mock(api_key: String, cluster_type: ClusterType) -> Self {
-        let url = format_url(api_key.clone(), cluster_type);
-        HeliusClient {
-            rpc_client: RpcClient::new_mock(url),
-            cluster: cluster_type,
+        let url_prefix = rpc_url_prefix(cluster_type);
+        let mock = RpcClient::new_mock(format!("{}{}", url_prefix, REDACTED));
+        HeliusClient::from_url(
             api_key,
-            http_client: reqwest::Client::new(),
-        }
+            cluster_type,
+            url_prefix,
+            None,
+            RpcClientConfig::with_commitment(CommitmentConfig::default()),
+            Some(mock),
+        )
     }
 
+    #[cfg(feature = "rpc")]
     pub fn new_mock_with_mocks(api_key: String, cluster_type: ClusterType, mocks: Mocks) -> Self {
-        let url = format_url(api_key.clone(), cluster_type);
+        let url_prefix = rpc_url_prefix(cluster_type);
+        let mock = RpcClient::new_mock_with_mocks(format!("{}{}", url_prefix, REDACTED), mocks);
+        HeliusClient::from_url(
+            api_key,
+            cluster_type,
+            url_prefix,
+            None,
+            RpcClientConfig::with_commitment(CommitmentConfig::default()),
+            Some(mock),
+        )
+    }
+
+    /// Creates a client whose RPC requests go to `url_prefix` followed by the API key of each request, with the
+    /// default commitment.
+    #[cfg(feature = "rpc")]
+    fn with_url_prefix(api_key: String, cluster_type: ClusterType, url_prefix: String) -> Self {
+        HeliusClient::from_url(
+            api_key,
+            cluster_type,
+            url_prefix,
+            None,
+            RpcClientConfig::with_commitment(CommitmentConfig::default()),
+            None,
+        )
+    }
+
+    /// Without the `rpc` feature there is no RPC client, so `url_prefix` is only used by the DAS methods.
+    #[cfg(not(feature = "rpc"))]
+    fn with_url_prefix(api_key: String, cluster_type: ClusterType, url_prefix: String) -> Self {
+        let mut client = HeliusClient::from_parts(api_key, cluster_type);
+        client.rpc_url = rpc_url(&url_prefix);
+        client
+    }
+
+    /// Creates a client whose RPC requests go to `url_prefix` followed by the API key of each request, or are answered
+    /// by `mock`.
+    #[cfg(feature = "rpc")]
+    fn from_url(
+        api_key: String,
+        cluster_type: ClusterType,
+        url_prefix: String,
+        timeout: Option<Duration>,
+        config: RpcClientConfig,
+        mock: Option<RpcClient>,
+    ) -> Self {
+        let credits = Arc::new(CreditTracker::default());
+        let keys = Arc::new(KeyPool::single(ApiKey::from(api_key.clone())));
+        let rpc_url = rpc_url(&url_prefix);
+        let failover = Arc::new(RpcFailover::new(
+            format!("{}{}", url_prefix, REDACTED),
+            timeout,
+        ));
+        let sender = HeliusSender::new(
+            url_prefix,
+            timeout,
+            credits.clone(),
+            keys.clone(),
+            failover.clone(),
+            mock,
+        );
+        let mut client = HeliusClient::from_parts(
+            api_key,
+            cluster_type,
+            RpcClient::new_sender(sender, config),
+        );
+        client.credits = credits;
+        client.keys = keys;
+        client.failover = failover;
+        client.rpc_url = rpc_url;
+        client
+    }
+
+    fn from_parts(
+        api_key: String,
+        cluster_type: ClusterType,
+        #[cfg(feature = "rpc")] rpc_client: RpcClient,
+    ) -> Self {
         HeliusClient {
-            rpc_client: RpcClient::new_mock_with_mocks(url, mocks),
+            #[cfg(feature = "rpc")]
+            failover: Arc::new(RpcFailover::new(rpc_client.url(), None)),
+            #[cfg(feature = "rpc")]
+            rpc_client,
             cluster: cluster_type,
-            api_key,
+            max_concurrent_requests: DEFAULT_MAX_CONCURRENT_REQUESTS,
+            keys: Arc::new(KeyPool::single(ApiKey::from(api_key))),
+            api_key_placement: ApiKeyPlacement::default(),
+            api_url_v0: API_URL_V0.to_string(),
+            api_url_v1: API_URL_V1.to_string(),
+            rpc_url: String::new(),
             http_client: reqwest::Client::new(),
+            cache: None,
+            coalescer: None,
+            vcr: None,
+            credits: Arc::new(CreditTracker::default()),
         }
     }
 }
 
-fn format_url(api_key: String, cluster_type: ClusterType) -> String {
+/// The RPC URL of `cluster_type` without the API key.
+fn rpc_url_prefix(cluster_type: ClusterType) -> String {
     match cluster_type {
         ClusterType::Testnet => panic!("Testnet cluster not supported"),
-        ClusterType::MainnetBeta => format!("{}{}", MAINNET_RPC_URL, api_key),
-        ClusterType::Devnet => format!("{}{}", DEVNET_RPC_URL, api_key),
+        ClusterType::MainnetBeta => MAINNET_RPC_URL.to_string(),
+        ClusterType::Devnet => DEVNET_RPC_URL.to_string(),
         ClusterType::Development => panic!("Local cluster not supported"),
     }
 }
+
+/// The RPC URL of `url_prefix`, without the `api-key` query parameter the key is appended to.
+fn rpc_url(url_prefix: &str) -> String {
+    url_prefix
+        .trim_end_matches("api-key=")
+        .trim_end_matches(['?', '&'])
+        .to_string()
+}
@@ -0,0 +1,340 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::error::{ClientError, ClientErrorKind, ClientResult};
+
+use super::{init::HeliusClient, secret::ApiKey};
+
+/// Default for [`KeyPoolConfig::rate_limit_cooldown`].
+pub const DEFAULT_RATE_LIMIT_COOLDOWN: Duration = Duration::from_secs(10);
+
+/// How a [`HeliusClient`] picks the key for each request from its pool, see [`HeliusClient::with_key_pool`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum RotationStrategy {
+    /// Each request uses the key after the one used by the previous request.
+    #[default]
+    RoundRobin,
+    /// Each request uses the key that has sent the fewest requests.
+    LeastUsed,
+    /// Every request uses the first key, and later keys only while the ones before them are rate limited,
+    /// unauthorized or over quota.
+    Failover,
+}
+
+/// An API key in a pool, with an optional quota of credits it may use.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PooledKey {
+    pub key: ApiKey,
+    /// Credits, as estimated by the client's [`CreditCostTable`](super::credits::CreditCostTable), the key may use
+    /// before it is taken out of rotation. Reset by [`HeliusClient::reset_credit_usage`].
+    pub credit_quota: Option<u64>,
+}
+
+impl PooledKey {
+    pub fn new<K: Into<ApiKey>>(key: K) -> Self {
+        PooledKey {
+            key: key.into(),
+            credit_quota: None,
+        }
+    }
+
+    pub fn with_credit_quota(mut self, credit_quota: u64) -> Self {
+        self.credit_quota = Some(credit_quota);
+        self
+    }
+}
+
+impl From<String> for PooledKey {
+    fn from(key: String) -> Self {
+        PooledKey::new(key)
+    }
+}
+
+impl From<&str> for PooledKey {
+    fn from(key: &str) -> Self {
+        PooledKey::new(key)
+    }
+}
+
+impl From<ApiKey> for PooledKey {
+    fn from(key: ApiKey) -> Self {
+        PooledKey::new(key)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyPoolConfig {
+    pub keys: Vec<PooledKey>,
+    pub strategy: RotationStrategy,
+    /// How long a key is avoided after a `429 Too Many Requests` response without a `Retry-After` header.
+    pub rate_limit_cooldown: Duration,
+}
+
+impl KeyPoolConfig {
+    pub fn new<K: Into<PooledKey>>(
+        keys: impl IntoIterator<Item = K>,
+        strategy: RotationStrategy,
+    ) -> Self {
+        KeyPoolConfig {
+            keys: keys.into_iter().map(Into::into).collect(),
+            strategy,
+            rate_limit_cooldown: DEFAULT_RATE_LIMIT_COOLDOWN,
+        }
+    }
+}
+
+/// The state of a key in the pool, see [`HeliusClient::key_stats`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyStats {
+    pub key: ApiKey,
+    pub requests: u64,
+    /// Estimated credits used since the client was created or its credit usage was last reset.
+    pub credits: u64,
+    pub credit_quota: Option<u64>,
+    /// Responses with status `429 Too Many Requests`.
+    pub rate_limited: u64,
+    /// Whether the key is avoided after a `429` response.
+    pub cooling_down: bool,
+    /// Whether the key received a `401 Unauthorized` response. It is only used again when no other key is usable.
+    pub unauthorized: bool,
+}
+
+/// How a request sent with a key of the pool went, see [`KeyPool::report`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum KeyOutcome {
+    /// The request was not rejected because of its key.
+    Accepted,
+    Unauthorized,
+    RateLimited(Option<Duration>),
+}
+
+impl KeyOutcome {
+    pub(crate) fn of_status(status: u16, retry_after: Option<Duration>) -> Self {
+        match status {
+            401 => KeyOutcome::Unauthorized,
+            429 => KeyOutcome::RateLimited(retry_after),
+            _ => KeyOutcome::Accepted,
+        }
+    }
+
+    /// Whether the request should be retried with another key.
+    pub(crate) fn is_rejection(&self) -> bool {
+        *self != KeyOutcome::Accepted
+    }
+}
+
+struct KeyState {
+    key: PooledKey,
+    requests: u64,
+    credits: u64,
+    rate_limited: u64,
+    cooldown_until: Option<Instant>,
+    unauthorized: bool,
+}
+
+impl KeyState {
+    fn has_quota_for(&self, cost: u64) -> bool {
+        self.key
+            .credit_quota
+            .is_none_or(|quota| self.credits + cost <= quota)
+    }
+
+    fn is_available(&self, now: Instant) -> bool {
+        !self.unauthorized && self.cooldown_until.is_none_or(|until| until <= now)
+    }
+}
+
+struct PoolState {
+    keys: Vec<KeyState>,
+    strategy: RotationStrategy,
+    rate_limit_cooldown: Duration,
+    next: usize,
+}
+
+/// The API keys of a [`HeliusClient`], shared by its REST requests and its RPC sender.
+/// A client created with a single key has a pool of one.
+pub(crate) struct KeyPool {
+    state: Mutex<PoolState>,
+}
+
+impl KeyPool {
+    pub(crate) fn new(config: KeyPoolConfig) -> Self {
+        assert!(
+            !config.keys.is_empty(),
+            "a key pool needs at least one API key"
+        );
+        KeyPool {
+            state: Mutex::new(PoolState {
+                keys: config
+                    .keys
+                    .into_iter()
+                    .map(|key| KeyState {
+                        key,
+                        requests: 0,
+                        credits: 0,
+                        rate_limited: 0,
+                        cooldown_until: None,
+                        unauthorized: false,
+                    })
+                    .collect(),
+                strategy: config.strategy,
+                rate_limit_cooldown: config.rate_limit_cooldown,
+                next: 0,
+            }),
+        }
+    }
+
+    pub(crate) fn single(key: ApiKey) -> Self {
+        KeyPool::new(KeyPoolConfig::new([key], RotationStrategy::default()))
+    }
+
+    /// Replaces the keys and state of the pool with those of `pool`, for every holder of this one.
+    pub(crate) fn replace(&self, pool: KeyPool) {
+        *self.state.lock().unwrap() = pool.state.into_inner().unwrap();
+    }
+
+    /// Picks the key for a new request costing `cost` credits and charges the request to it. Keys that are rate
+    /// limited or unauthorized are only picked when every other key is too, so the API has the final say.
+    /// Fails if every key is over quota.
+    pub(crate) fn select(&self, cost: u64) -> ClientResult<(usize, ApiKey)> {
+        self.pick(cost, &[], false).ok_or_else(|| {
+            ClientError::from(ClientErrorKind::Custom(
+                "Every API key in the pool has used its credit quota".to_string(),
+            ))
+        })
+    }
+
+    /// Picks a usable key that has not been `tried` yet to retry a request rejected because of its key.
+    pub(crate) fn select_retry(&self, cost: u64, tried: &[usize]) -> Option<(usize, ApiKey)> {
+        self.pick(cost, tried, true)
+    }
+
+    fn pick(&self, cost: u64, tried: &[usize], available_only: bool) -> Option<(usize, ApiKey)> {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        let candidates: Vec<usize> = (0..state.keys.len())
+            .filter(|index| !tried.contains(index) && state.keys[*index].has_quota_for(cost))
+            .collect();
+        let available: Vec<usize> = candidates
+            .iter()
+            .copied()
+            .filter(|index| state.keys[*index].is_available(now))
+            .collect();
+        let candidates = if !available.is_empty() || available_only {
+            available
+        } else {
+            candidates
+        };
+
+        let index = match state.strategy {
+            RotationStrategy::RoundRobin => {
+                let next = state.next;
+                candidates
+                    .iter()
+                    .copied()
+                    .find(|index| *index >= next)
+                    .or_else(|| candidates.first().copied())
+            }
+            RotationStrategy::LeastUsed => candidates
+                .iter()
+                .copied()
+                .min_by_key(|index| state.keys[*index].requests),
+            RotationStrategy::Failover => candidates.first().copied(),
+        }?;
+
+        state.next = (index + 1) % state.keys.len();
+        let key = &mut state.keys[index];
+        key.requests += 1;
+        key.credits += cost;
+        Some((index, key.key.key.clone()))
+    }
+
+    /// Takes back the request and credits charged to the key at `index` for a request that was not sent.
+    pub(crate) fn refund(&self, index: usize, cost: u64) {
+        let key = &mut self.state.lock().unwrap().keys[index];
+        key.requests = key.requests.saturating_sub(1);
+        key.credits = key.credits.saturating_sub(cost);
+    }
+
+    /// Returns the key [`select`](KeyPool::select) would prefer, without rotating or charging anything to it, for
+    /// requests that are not counted such as health checks.
+    #[cfg(feature = "rpc")]
+    pub(crate) fn peek(&self) -> ApiKey {
+        let state = self.state.lock().unwrap();
+        let now = Instant::now();
+        state
+            .keys
+            .iter()
+            .find(|key| key.is_available(now))
+            .unwrap_or(&state.keys[0])
+            .key
+            .key
+            .clone()
+    }
+
+    /// Records how a request sent with the key at `index` went. Requests rejected because of their key are refunded.
+    pub(crate) fn report(&self, index: usize, cost: u64, outcome: KeyOutcome) {
+        let mut state = self.state.lock().unwrap();
+        let cooldown = state.rate_limit_cooldown;
+        let key = &mut state.keys[index];
+        match outcome {
+            KeyOutcome::Accepted => {
+                key.unauthorized = false;
+                return;
+            }
+            KeyOutcome::Unauthorized => key.unauthorized = true,
+            KeyOutcome::RateLimited(retry_after) => {
+                key.rate_limited += 1;
+                key.cooldown_until = Some(Instant::now() + retry_after.unwrap_or(cooldown));
+            }
+        }
+        key.credits = key.credits.saturating_sub(cost);
+        tracing::warn!(key = index, ?outcome, "Helius API key rejected");
+    }
+
+    pub(crate) fn stats(&self) -> Vec<KeyStats> {
+        let state = self.state.lock().unwrap();
+        let now = Instant::now();
+        state
+            .keys
+            .iter()
+            .map(|key| KeyStats {
+                key: key.key.key.clone(),
+                requests: key.requests,
+                credits: key.credits,
+                credit_quota: key.key.credit_quota,
+                rate_limited: key.rate_limited,
+                cooling_down: key.cooldown_until.is_some_and(|until| until > now),
+                unauthorized: key.unauthorized,
+            })
+            .collect()
+    }
+
+    pub(crate) fn reset_credits(&self) {
+        for key in self.state.lock().unwrap().keys.iter_mut() {
+            key.credits = 0;
+        }
+    }
+}
+
+impl HeliusClient {
+    /// Sends every REST and RPC request with a key from `config` instead of the client's key, picked according to
+    /// [`KeyPoolConfig::strategy`]. A request answered with `401` or `429` is retried once with each other usable key,
+    /// and the rejected key is avoided until it cools down or, if unauthorized, until no other key is usable.
+    /// Keys over their credit quota are skipped. The RPC half sends each request to the RPC URL of its key.
+    ///
+    /// # Panics
+    ///
+    /// If `config` has no keys.
+    pub fn with_key_pool(self, config: KeyPoolConfig) -> Self {
+        self.keys.replace(KeyPool::new(config));
+        self
+    }
+
+    /// Returns the state of every key in the pool, in pool order.
+    pub fn key_stats(&self) -> Vec<KeyStats> {
+        self.keys.stats()
+    }
+}
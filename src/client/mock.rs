@@ -0,0 +1,283 @@
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Debug,
+    sync::Mutex,
+};
+
+use crate::error::{ClientError, ClientErrorKind, ClientResult};
+use crate::models::{
+    das::AssetsByOwner,
+    enriched_transaction::{EnrichedTransaction, RequestConfig},
+    nft::{
+        ActiveListingsRequestConfig, ActiveListingsResponse, MintListRequestConfig,
+        MintListResponse, NftEvent, NftInfo, NftMetadata, TokenBalancesResponse,
+    },
+    raw_transaction::{GetRawTransactionsRequestConfig, RawTransaction},
+    structs::TokenMetadata,
+};
+use crate::types::CommitmentLevel;
+
+use super::{
+    api::HeliusApi,
+    batch::BatchResponse,
+    webhooks::{CreateWebhookRequest, Webhook},
+};
+
+/// The methods of [`HeliusApi`], used to seed and inspect a [`MockHeliusClient`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum HeliusMethod {
+    GetNamingServiceNames,
+    GetTokenBalances,
+    GetNfts,
+    GetAllNfts,
+    GetNftsMetadata,
+    GetNftEventsForAddress,
+    GetNftEvents,
+    GetActiveNftListings,
+    GetMintList,
+    GetTokensMetadata,
+    GetAssetsByOwner,
+    GetTransactionsForAddress,
+    GetTransactionsByHash,
+    GetEnrichedTransactions,
+    GetEnrichedTransactionsByHash,
+    CreateWebhook,
+    GetWebhooks,
+    GetWebhook,
+    EditWebhook,
+    DeleteWebhook,
+}
+
+/// A call made to a [`MockHeliusClient`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockCall {
+    pub method: HeliusMethod,
+    /// The `Debug` representation of each argument, in order.
+    pub arguments: Vec<String>,
+}
+
+#[derive(Clone)]
+enum MockResponse {
+    Value(Value),
+    Error(String),
+}
+
+/// An in-memory [`HeliusApi`] that returns canned responses and records every call.
+///
+/// Responses are queued per [`HeliusMethod`] and returned in the order they were pushed; the last response queued for a method
+/// is repeated once the others are used up. Batch methods are seeded with the plain list of items, which is returned as a
+/// complete [`BatchResponse`]. Calling a method with nothing queued returns an error.
+#[derive(Default)]
+pub struct MockHeliusClient {
+    responses: Mutex<HashMap<HeliusMethod, VecDeque<MockResponse>>>,
+    calls: Mutex<Vec<MockCall>>,
+}
+
+impl MockHeliusClient {
+    pub fn new() -> Self {
+        MockHeliusClient::default()
+    }
+
+    /// Queues `response` as the result of a call to `method`.
+    pub fn push_response<T: Serialize>(&self, method: HeliusMethod, response: &T) {
+        let value = serde_json::to_value(response).expect("mock response must serialize to JSON");
+        self.push(method, MockResponse::Value(value));
+    }
+
+    /// Queues an error with `message` as the result of a call to `method`.
+    pub fn push_error<S: Into<String>>(&self, method: HeliusMethod, message: S) {
+        self.push(method, MockResponse::Error(message.into()));
+    }
+
+    /// Returns every call made so far, in order.
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    /// Returns the calls made so far to `method`, in order.
+    pub fn calls_to(&self, method: HeliusMethod) -> Vec<MockCall> {
+        self.calls
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|call| call.method == method)
+            .cloned()
+            .collect()
+    }
+
+    fn push(&self, method: HeliusMethod, response: MockResponse) {
+        self.responses
+            .lock()
+            .unwrap()
+            .entry(method)
+            .or_default()
+            .push_back(response);
+    }
+
+    fn respond<T: DeserializeOwned>(
+        &self,
+        method: HeliusMethod,
+        arguments: &[&dyn Debug],
+    ) -> ClientResult<T> {
+        self.calls.lock().unwrap().push(MockCall {
+            method,
+            arguments: arguments.iter().map(|arg| format!("{:?}", arg)).collect(),
+        });
+
+        let mut responses = self.responses.lock().unwrap();
+        let queue = responses.entry(method).or_default();
+        let response = if queue.len() > 1 {
+            queue.pop_front()
+        } else {
+            queue.front().cloned()
+        };
+        match response {
+            Some(MockResponse::Value(value)) => serde_json::from_value(value)
+                .map_err(|e| ClientError::from(ClientErrorKind::SerdeJson(e))),
+            Some(MockResponse::Error(message)) => {
+                Err(ClientError::from(ClientErrorKind::Custom(message)))
+            }
+            None => Err(ClientError::from(ClientErrorKind::Custom(format!(
+                "No mock response queued for {:?}",
+                method
+            )))),
+        }
+    }
+
+    fn respond_batch<T: DeserializeOwned>(
+        &self,
+        method: HeliusMethod,
+        arguments: &[&dyn Debug],
+    ) -> ClientResult<BatchResponse<T>> {
+        Ok(BatchResponse {
+            results: self.respond(method, arguments)?,
+            failed_chunks: Vec::new(),
+        })
+    }
+}
+
+#[async_trait]
+impl HeliusApi for MockHeliusClient {
+    async fn get_naming_service_names(&self, address: String) -> ClientResult<Vec<String>> {
+        self.respond(HeliusMethod::GetNamingServiceNames, &[&address])
+    }
+
+    async fn get_token_balances(&self, address: String) -> ClientResult<TokenBalancesResponse> {
+        self.respond(HeliusMethod::GetTokenBalances, &[&address])
+    }
+
+    async fn get_nfts(
+        &self,
+        address: String,
+        page_number: Option<usize>,
+    ) -> ClientResult<Vec<NftInfo>> {
+        self.respond(HeliusMethod::GetNfts, &[&address, &page_number])
+    }
+
+    async fn get_all_nfts(&self, address: String) -> ClientResult<Vec<NftInfo>> {
+        self.respond(HeliusMethod::GetAllNfts, &[&address])
+    }
+
+    async fn get_nfts_metadata(
+        &self,
+        token_mints: Vec<String>,
+    ) -> ClientResult<BatchResponse<NftMetadata>> {
+        self.respond_batch(HeliusMethod::GetNftsMetadata, &[&token_mints])
+    }
+
+    async fn get_nft_events_for_address(
+        &self,
+        config: RequestConfig,
+    ) -> ClientResult<Vec<NftEvent>> {
+        self.respond(HeliusMethod::GetNftEventsForAddress, &[&config])
+    }
+
+    async fn get_nft_events(&self, config: RequestConfig) -> ClientResult<Vec<NftEvent>> {
+        self.respond(HeliusMethod::GetNftEvents, &[&config])
+    }
+
+    async fn get_active_nft_listings(
+        &self,
+        config: ActiveListingsRequestConfig,
+    ) -> ClientResult<ActiveListingsResponse> {
+        self.respond(HeliusMethod::GetActiveNftListings, &[&config])
+    }
+
+    async fn get_mint_list(&self, config: MintListRequestConfig) -> ClientResult<MintListResponse> {
+        self.respond(HeliusMethod::GetMintList, &[&config])
+    }
+
+    async fn get_tokens_metadata(
+        &self,
+        token_mints: Vec<String>,
+    ) -> ClientResult<BatchResponse<TokenMetadata>> {
+        self.respond_batch(HeliusMethod::GetTokensMetadata, &[&token_mints])
+    }
+
+    async fn get_assets_by_owner(&self, owner: String, page: u32) -> ClientResult<AssetsByOwner> {
+        self.respond(HeliusMethod::GetAssetsByOwner, &[&owner, &page])
+    }
+
+    async fn get_transactions_for_address(
+        &self,
+        config: GetRawTransactionsRequestConfig,
+    ) -> ClientResult<Vec<RawTransaction>> {
+        self.respond(HeliusMethod::GetTransactionsForAddress, &[&config])
+    }
+
+    async fn get_transactions_by_hash(
+        &self,
+        transaction_hashes: Vec<String>,
+        commitment: Option<CommitmentLevel>,
+    ) -> ClientResult<BatchResponse<RawTransaction>> {
+        self.respond_batch(
+            HeliusMethod::GetTransactionsByHash,
+            &[&transaction_hashes, &commitment],
+        )
+    }
+
+    async fn get_enriched_transactions(
+        &self,
+        config: RequestConfig,
+    ) -> ClientResult<Vec<EnrichedTransaction>> {
+        self.respond(HeliusMethod::GetEnrichedTransactions, &[&config])
+    }
+
+    async fn get_enriched_transactions_by_hash(
+        &self,
+        transaction_hashes: Vec<String>,
+        commitment: Option<CommitmentLevel>,
+    ) -> ClientResult<BatchResponse<EnrichedTransaction>> {
+        self.respond_batch(
+            HeliusMethod::GetEnrichedTransactionsByHash,
+            &[&transaction_hashes, &commitment],
+        )
+    }
+
+    async fn create_webhook(&self, webhook_request: CreateWebhookRequest) -> ClientResult<Webhook> {
+        self.respond(HeliusMethod::CreateWebhook, &[&webhook_request])
+    }
+
+    async fn get_webhooks(&self) -> ClientResult<Vec<Webhook>> {
+        self.respond(HeliusMethod::GetWebhooks, &[])
+    }
+
+    async fn get_webhook(&self, webhook_id: String) -> ClientResult<Webhook> {
+        self.respond(HeliusMethod::GetWebhook, &[&webhook_id])
+    }
+
+    async fn edit_webhook(
+        &self,
+        webhook_id: String,
+        new_webhook: CreateWebhookRequest,
+    ) -> ClientResult<Webhook> {
+        self.respond(HeliusMethod::EditWebhook, &[&webhook_id, &new_webhook])
+    }
+
+    async fn delete_webhook(&self, webhook_id: String) -> ClientResult<()> {
+        self.respond(HeliusMethod::DeleteWebhook, &[&webhook_id])
+    }
+}
@@ -1,28 +1,189 @@
-use reqwest::{Error as ReqwestError, Response};
+use reqwest::{header::RETRY_AFTER, Request, RequestBuilder, Response};
 use serde::Deserialize;
-use solana_client::client_error::{ClientError, ClientErrorKind, Result as ClientResult};
+use std::time::{Duration, Instant};
+use tracing::{Instrument, Span};
 
+use crate::error::{ClientError, ClientErrorKind, ClientResult};
+
+use self::{
+    init::HeliusClient,
+    keys::KeyOutcome,
+    secret::{redact_error, redact_reqwest_error, redact_url, ApiKey},
+    telemetry::{record_request, request_span, Api, RequestRecord},
+    vcr::VcrMode,
+};
+
+pub mod api;
+pub mod batch;
+pub mod cache;
+pub mod coalesce;
+pub mod credits;
+pub mod das;
+#[cfg(feature = "rpc")]
+pub mod failover;
 pub mod init;
+pub mod keys;
+pub mod mock;
 pub mod names;
+pub mod portfolio;
+pub mod secret;
+#[cfg(feature = "rpc")]
+pub(crate) mod sender;
+pub mod telemetry;
 pub mod tokens;
 pub mod transactions;
+pub mod vcr;
+pub mod watch;
 pub mod webhooks;
 
 pub async fn parse_response<T: for<'a> Deserialize<'a>>(
-    response: Result<Response, ReqwestError>,
+    response: ClientResult<Response>,
 ) -> ClientResult<T> {
     match response {
         Ok(res) => {
             let payload = res.json().await;
             match payload {
                 Ok(j) => Ok(j),
-                Err(e) => Err(ClientError::from(ClientErrorKind::Reqwest(e))),
+                Err(e) => Err(redact_reqwest_error(e)),
             }
         }
-        Err(e) => Err(ClientError::from(ClientErrorKind::Reqwest(e))),
+        Err(e) => Err(e),
+    }
+}
+
+impl HeliusClient {
+    /// Sends a REST request with an API key from the client's pool attached, through the client's [`Vcr`](vcr::Vcr)
+    /// when one is attached. A request rejected because of its key is retried with the next usable key, see
+    /// [`with_key_pool`](HeliusClient::with_key_pool). The key is redacted from any error returned.
+    /// `endpoint` names the request in traces, metrics and credit accounting.
+    pub(crate) async fn send(
+        &self,
+        endpoint: &'static str,
+        request: RequestBuilder,
+    ) -> ClientResult<Response> {
+        let request = request.build().map_err(redact_reqwest_error)?;
+        let replay = matches!(&self.vcr, Some(vcr) if vcr.mode() == VcrMode::Replay);
+        let cost = if replay { 0 } else { self.credits.cost(endpoint) };
+        let (mut index, mut api_key) = self.keys.select(cost)?;
+
+        let mut url = request.url().clone();
+        redact_url(&mut url);
+        let span = request_span(Api::Rest, endpoint, request.method().as_str(), url.as_str());
+        let request_bytes = request
+            .body()
+            .and_then(|body| body.as_bytes())
+            .map_or(0, |bytes| bytes.len());
+
+        async {
+            let started = Instant::now();
+            let mut tried = Vec::new();
+            let response = loop {
+                // Nothing is charged for a request until its first attempt is sent.
+                let attempt = self.prepare_attempt(&request, &api_key).and_then(|attempt| {
+                    if tried.is_empty() && !replay {
+                        self.credits.charge(endpoint)?;
+                    }
+                    Ok(attempt)
+                });
+                let attempt = match attempt {
+                    Ok(attempt) => attempt,
+                    Err(error) => {
+                        self.keys.refund(index, cost);
+                        return Err(error);
+                    }
+                };
+                let response = self.execute_attempt(attempt).await;
+
+                let outcome = match &response {
+                    Ok((response, _)) => {
+                        KeyOutcome::of_status(response.status().as_u16(), retry_after(response))
+                    }
+                    Err(_) => KeyOutcome::Accepted,
+                };
+                self.keys.report(index, cost, outcome);
+                if outcome.is_rejection() {
+                    tried.push(index);
+                    if let Some((next_index, next_key)) = self.keys.select_retry(cost, &tried) {
+                        index = next_index;
+                        api_key = next_key;
+                        Span::current().record("retries", tried.len() as u32);
+                        continue;
+                    }
+                }
+                break response;
+            };
+
+            record_request(
+                &Span::current(),
+                RequestRecord {
+                    api: Api::Rest,
+                    endpoint,
+                    status: response
+                        .as_ref()
+                        .ok()
+                        .map(|(response, _)| response.status().as_u16()),
+                    elapsed: started.elapsed(),
+                    request_bytes,
+                    response_bytes: response.as_ref().map_or(0, |(_, size)| *size),
+                    error: response.as_ref().err(),
+                },
+            );
+            response.map(|(response, _)| response)
+        }
+        .instrument(span)
+        .await
+    }
+
+    /// Copies `request` for a single attempt and attaches `api_key` to it.
+    fn prepare_attempt(&self, request: &Request, api_key: &ApiKey) -> ClientResult<Request> {
+        let mut attempt = request.try_clone().ok_or_else(|| {
+            ClientError::from(ClientErrorKind::Custom(
+                "Request body cannot be resent".to_string(),
+            ))
+        })?;
+        self.attach_api_key(&mut attempt, api_key)?;
+        Ok(attempt)
+    }
+
+    /// Executes a single attempt of a REST request and buffers its response.
+    async fn execute_attempt(&self, request: Request) -> ClientResult<(Response, usize)> {
+        let response = match &self.vcr {
+            Some(vcr) => vcr
+                .execute(&self.http_client, request)
+                .await
+                .map_err(redact_error),
+            None => self
+                .http_client
+                .execute(request)
+                .await
+                .map_err(redact_reqwest_error),
+        }?;
+        buffer_response(response).await
     }
 }
 
+/// The delay requested by the `Retry-After` header of `response`, if it is given in seconds.
+fn retry_after(response: &Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok())
+        .map(Duration::from_secs)
+}
+
+/// Reads the body of `response` so its size can be recorded. Returns an equivalent response along with the size.
+async fn buffer_response(response: Response) -> ClientResult<(Response, usize)> {
+    let status = response.status();
+    let headers = response.headers().clone();
+    let body = response.bytes().await.map_err(redact_reqwest_error)?;
+    let size = body.len();
+    let mut buffered = http::Response::new(body);
+    *buffered.status_mut() = status;
+    *buffered.headers_mut() = headers;
+    Ok((Response::from(buffered), size))
+}
+
 pub fn api_commitment_error<T>() -> ClientResult<T> {
     Err(ClientError::from(ClientErrorKind::Custom(
         "Only Confirmed and Finalized commitments are supported by this API".to_string(),
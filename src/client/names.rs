@@ -1,9 +1,11 @@
+use crate::error::ClientResult;
+
 use super::{
-    init::{HeliusClient, API_URL_V0},
+    cache::CacheFamily,
+    init::HeliusClient,
     parse_response,
 };
 use serde::Deserialize;
-use solana_client::client_error::Result as ClientResult;
 
 #[derive(Deserialize, Debug, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -13,22 +15,31 @@ struct DomainNamesResponse {
 
 impl HeliusClient {
     /// Returns the Solana Naming Service name for a given address. GET request to `https://api.helius.xyz/v0/addresses/{address}/names`.
+    /// Served from the client's cache when one is configured.
     /// * `address` - The address that you want names for.
     pub async fn get_naming_service_names(&self, address: String) -> ClientResult<Vec<String>> {
-        let request_url = format!(
-            "{}/addresses/{}/names?api-key={}",
-            API_URL_V0, address, self.api_key
-        );
+        if let Some(cache) = &self.cache {
+            if let Some(names) = cache.get(CacheFamily::NamingService, &address) {
+                return Ok(names);
+            }
+        }
+
+        let request_url = format!("{}/addresses/{}/names", self.api_url_v0, address);
 
         let response = self
-            .http_client
-            .get(request_url)
-            .header("accept", "application/json")
-            .header("Content-Type", "application/json")
-            .send()
+            .send(
+                "get_naming_service_names",
+                self.http_client
+                    .get(request_url)
+                    .header("accept", "application/json")
+                    .header("Content-Type", "application/json"),
+            )
             .await;
 
         let response: DomainNamesResponse = parse_response(response).await?;
+        if let Some(cache) = &self.cache {
+            cache.insert(CacheFamily::NamingService, &address, &response.domain_names);
+        }
         Ok(response.domain_names)
     }
 }
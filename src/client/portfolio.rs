@@ -0,0 +1,271 @@
+use futures::try_join;
+use std::collections::{BTreeMap, HashMap};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::accounting::PriceSource;
+use crate::error::{ClientError, ClientErrorKind, ClientResult};
+use crate::models::{nft::NftInfo, structs::TokenMetadata};
+use crate::types::NATIVE_DECIMALS;
+
+use super::{
+    api::HeliusApi,
+    init::{HeliusClient, MAX_ASSETS_PER_PAGE},
+};
+
+/// The currencies of DAS prices that are taken as USD.
+const USD_CURRENCIES: &[&str] = &["USD", "USDC"];
+
+/// The holdings of a wallet: SOL, fungible tokens and NFTs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Portfolio {
+    pub owner: String,
+    pub sol: TokenHolding,
+    /// Ordered by USD value, highest first, then by mint. Tokens without a value come last.
+    pub tokens: Vec<TokenHolding>,
+    /// Ordered by collection name.
+    pub collections: Vec<NftCollection>,
+}
+
+/// The balance of SOL or of a fungible token.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TokenHolding {
+    /// The mint, or `None` for SOL.
+    pub mint: Option<String>,
+    pub symbol: Option<String>,
+    pub name: Option<String>,
+    /// The raw amount, summed over the token accounts of the mint.
+    pub amount: u64,
+    pub decimals: u8,
+    /// The amount in whole tokens.
+    pub ui_amount: f64,
+    /// The price of a whole token, if the DAS API or the price source knows it.
+    pub price_usd: Option<f64>,
+    pub value_usd: Option<f64>,
+}
+
+/// The NFTs of a wallet in one collection.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NftCollection {
+    pub address: String,
+    pub name: String,
+    pub nfts: Vec<NftInfo>,
+}
+
+impl Portfolio {
+    /// The USD value of SOL and of every token with a price.
+    pub fn total_value_usd(&self) -> f64 {
+        self.holdings()
+            .filter_map(|holding| holding.value_usd)
+            .sum()
+    }
+
+    /// The number of holdings, SOL included, without a USD value.
+    pub fn unpriced(&self) -> usize {
+        self.holdings()
+            .filter(|holding| holding.value_usd.is_none())
+            .count()
+    }
+
+    pub fn nft_count(&self) -> usize {
+        self.collections
+            .iter()
+            .map(|collection| collection.nfts.len())
+            .sum()
+    }
+
+    /// SOL, then every token.
+    pub fn holdings(&self) -> impl Iterator<Item = &TokenHolding> {
+        std::iter::once(&self.sol).chain(&self.tokens)
+    }
+}
+
+impl HeliusClient {
+    /// Returns the holdings of `owner`, valued with the prices of the DAS API. See [`portfolio`].
+    pub async fn portfolio(&self, owner: String) -> ClientResult<Portfolio> {
+        portfolio(self, owner, None).await
+    }
+
+    /// Returns the holdings of `owner`, valued with the current prices of `prices` instead. See [`portfolio`].
+    pub async fn portfolio_with_prices(
+        &self,
+        owner: String,
+        prices: &dyn PriceSource,
+    ) -> ClientResult<Portfolio> {
+        portfolio(self, owner, Some(prices)).await
+    }
+}
+
+/// Returns the holdings of `owner` through `api`, combining `get_token_balances`, `get_all_nfts` and
+/// `get_tokens_metadata`.
+///
+/// Token accounts of the same mint are summed, and the mints held as NFTs are left out of the tokens. Symbols and names
+/// come from the token metadata, on-chain first; they are `None` for mints whose metadata could not be fetched.
+///
+/// SOL and tokens are valued at their price now: without `prices`, at the USD prices that `get_assets_by_owner` returns
+/// for the fungible tokens and the native balance of `owner`. Fails if the amounts of a mint overflow a `u64`.
+pub async fn portfolio<A: HeliusApi + ?Sized>(
+    api: &A,
+    owner: String,
+    prices: Option<&dyn PriceSource>,
+) -> ClientResult<Portfolio> {
+    let (balances, nfts, das_prices) = try_join!(
+        api.get_token_balances(owner.clone()),
+        api.get_all_nfts(owner.clone()),
+        async {
+            match prices {
+                Some(_) => Ok(HashMap::new()),
+                None => das_prices(api, &owner).await,
+            }
+        }
+    )?;
+
+    let mut amounts: BTreeMap<String, (u64, u8)> = BTreeMap::new();
+    for token in balances.tokens {
+        if token.amount == 0 || nfts.iter().any(|nft| nft.token_address == token.mint) {
+            continue;
+        }
+        let (amount, decimals) = amounts.entry(token.mint.clone()).or_default();
+        *amount = amount.checked_add(token.amount).ok_or_else(|| {
+            ClientError::from(ClientErrorKind::Custom(format!(
+                "amounts of {} held by {} overflow",
+                token.mint, owner
+            )))
+        })?;
+        *decimals = token.decimals;
+    }
+
+    let metadata: HashMap<String, TokenMetadata> = if amounts.is_empty() {
+        HashMap::new()
+    } else {
+        api.get_tokens_metadata(amounts.keys().cloned().collect())
+            .await?
+            .results
+            .into_iter()
+            .map(|metadata| (metadata.mint.clone(), metadata))
+            .collect()
+    };
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs() as i64)
+        .unwrap_or_default();
+    let mut sol = holding(None, balances.native_balance, NATIVE_DECIMALS);
+    sol.symbol = Some("SOL".to_string());
+    sol.name = Some("Solana".to_string());
+    let mut tokens = Vec::new();
+    for (mint, (amount, decimals)) in amounts {
+        let mut token = holding(Some(mint.clone()), amount, decimals);
+        if let Some(metadata) = metadata.get(&mint) {
+            token.symbol = symbol(metadata);
+            token.name = name(metadata);
+        }
+        tokens.push(token);
+    }
+    for holding in std::iter::once(&mut sol).chain(&mut tokens) {
+        holding.price_usd = match prices {
+            Some(prices) => prices.price(holding.mint.as_deref(), now).await?,
+            None => das_prices.get(&holding.mint).copied(),
+        };
+        holding.value_usd = holding.price_usd.map(|price| price * holding.ui_amount);
+    }
+    tokens.sort_by(|a, b| {
+        let value = |holding: &TokenHolding| holding.value_usd.unwrap_or(f64::NEG_INFINITY);
+        value(b)
+            .total_cmp(&value(a))
+            .then_with(|| a.mint.cmp(&b.mint))
+    });
+
+    let mut collections: BTreeMap<(String, String), Vec<NftInfo>> = BTreeMap::new();
+    for nft in nfts {
+        collections
+            .entry((nft.collection_name.clone(), nft.collection_address.clone()))
+            .or_default()
+            .push(nft);
+    }
+
+    Ok(Portfolio {
+        owner,
+        sol,
+        tokens,
+        collections: collections
+            .into_iter()
+            .map(|((name, address), nfts)| NftCollection {
+                address,
+                name,
+                nfts,
+            })
+            .collect(),
+    })
+}
+
+/// The USD prices of SOL (`None`) and of the fungible tokens of `owner` known to the DAS API, over every page of its
+/// assets.
+async fn das_prices<A: HeliusApi + ?Sized>(
+    api: &A,
+    owner: &str,
+) -> ClientResult<HashMap<Option<String>, f64>> {
+    let mut prices = HashMap::new();
+    for page in 1.. {
+        let assets = api.get_assets_by_owner(owner.to_string(), page).await?;
+        if let Some(price) = assets.native_balance.and_then(|balance| balance.price_per_sol) {
+            prices.insert(None, price);
+        }
+        let last = assets.items.len() < MAX_ASSETS_PER_PAGE as usize;
+        for asset in assets.items {
+            if let Some(price) = asset.token_info.and_then(|info| info.price_info) {
+                if USD_CURRENCIES.contains(&price.currency.as_str()) {
+                    prices.insert(Some(asset.id), price.price_per_token);
+                }
+            }
+        }
+        if last {
+            break;
+        }
+    }
+    Ok(prices)
+}
+
+fn holding(mint: Option<String>, amount: u64, decimals: u8) -> TokenHolding {
+    TokenHolding {
+        mint,
+        symbol: None,
+        name: None,
+        amount,
+        decimals,
+        ui_amount: amount as f64 / 10f64.powi(decimals as i32),
+        price_usd: None,
+        value_usd: None,
+    }
+}
+
+/// On-chain metadata strings are padded with NUL characters.
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim_end_matches('\0').trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+fn symbol(metadata: &TokenMetadata) -> Option<String> {
+    metadata
+        .on_chain_data
+        .as_ref()
+        .and_then(|data| non_empty(&data.data.symbol))
+        .or_else(|| {
+            metadata
+                .off_chain_data
+                .as_ref()
+                .and_then(|data| non_empty(&data.symbol))
+        })
+}
+
+fn name(metadata: &TokenMetadata) -> Option<String> {
+    metadata
+        .on_chain_data
+        .as_ref()
+        .and_then(|data| non_empty(&data.data.name))
+        .or_else(|| {
+            metadata
+                .off_chain_data
+                .as_ref()
+                .and_then(|data| non_empty(&data.name))
+        })
+}
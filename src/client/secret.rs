@@ -0,0 +1,136 @@
+use reqwest::{header::HeaderValue, Request, Url};
+use std::fmt;
+
+use crate::error::{ClientError, ClientErrorKind, ClientResult};
+
+use super::init::HeliusClient;
+
+/// Shown in place of an API key in `Debug`/`Display` output, URLs and errors.
+pub const REDACTED: &str = "REDACTED";
+/// The header carrying the API key when [`ApiKeyPlacement::Header`] is used.
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// A Helius API key. Its `Debug` and `Display` output is redacted, so it can be logged along with the structs holding it.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct ApiKey(String);
+
+impl ApiKey {
+    pub fn new<S: Into<String>>(key: S) -> Self {
+        ApiKey(key.into())
+    }
+
+    /// Returns the key itself. Take care not to log the result.
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for ApiKey {
+    fn from(key: String) -> Self {
+        ApiKey(key)
+    }
+}
+
+impl From<&str> for ApiKey {
+    fn from(key: &str) -> Self {
+        ApiKey(key.to_string())
+    }
+}
+
+impl fmt::Debug for ApiKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ApiKey({})", REDACTED)
+    }
+}
+
+impl fmt::Display for ApiKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+/// Where REST requests carry the API key.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum ApiKeyPlacement {
+    /// The `api-key` query parameter, accepted by every endpoint.
+    #[default]
+    QueryParameter,
+    /// The [`API_KEY_HEADER`] header, which keeps the key out of URLs logged by proxies and servers.
+    /// Only use it against endpoints or proxies that accept the header.
+    Header,
+}
+
+impl HeliusClient {
+    /// Sends the API key of REST requests as described by `placement`. RPC requests always carry it as a query parameter.
+    pub fn with_api_key_placement(mut self, placement: ApiKeyPlacement) -> Self {
+        self.api_key_placement = placement;
+        self
+    }
+
+    pub(crate) fn attach_api_key(&self, request: &mut Request, api_key: &ApiKey) -> ClientResult<()> {
+        match self.api_key_placement {
+            ApiKeyPlacement::QueryParameter => {
+                request
+                    .url_mut()
+                    .query_pairs_mut()
+                    .append_pair("api-key", api_key.expose());
+            }
+            ApiKeyPlacement::Header => {
+                let mut value = HeaderValue::from_str(api_key.expose()).map_err(|_| {
+                    ClientError::from(ClientErrorKind::Custom(
+                        "API key is not a valid header value".to_string(),
+                    ))
+                })?;
+                value.set_sensitive(true);
+                request.headers_mut().insert(API_KEY_HEADER, value);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Replaces the value of every `api-key` query parameter of `url` with [`REDACTED`].
+pub(crate) fn redact_url(url: &mut Url) {
+    if !url.query_pairs().any(|(key, _)| key == "api-key") {
+        return;
+    }
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(key, value)| {
+            let value = if key == "api-key" {
+                REDACTED.to_string()
+            } else {
+                value.into_owned()
+            };
+            (key.into_owned(), value)
+        })
+        .collect();
+    url.query_pairs_mut().clear().extend_pairs(pairs);
+}
+
+/// Returns `url` with its API key redacted. URLs that cannot be parsed are redacted entirely.
+#[cfg(feature = "rpc")]
+pub(crate) fn redact_url_str(url: &str) -> String {
+    match Url::parse(url) {
+        Ok(mut url) => {
+            redact_url(&mut url);
+            url.to_string()
+        }
+        Err(_) => REDACTED.to_string(),
+    }
+}
+
+/// Redacts the API key from the request URL carried by `error`.
+pub(crate) fn redact_error(mut error: ClientError) -> ClientError {
+    if let ClientErrorKind::Reqwest(e) = &mut error.kind {
+        if let Some(url) = e.url_mut() {
+            redact_url(url);
+        }
+    }
+    error
+}
+
+/// Converts `error` into a [`ClientError`] with the API key redacted from its URL.
+pub(crate) fn redact_reqwest_error(error: reqwest::Error) -> ClientError {
+    redact_error(ClientError::from(ClientErrorKind::Reqwest(error)))
+}
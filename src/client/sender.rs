@@ -0,0 +1,423 @@
+use async_trait::async_trait;
+use futures::{
+    future::{join_all, select, Either},
+    join, pin_mut,
+};
+use reqwest::header::CONTENT_TYPE;
+use serde_json::{json, Value};
+use solana_client::{
+    client_error::Result as RpcResult,
+    nonblocking::rpc_client::RpcClient,
+    rpc_custom_error::JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY,
+    rpc_request::RpcRequest,
+    rpc_sender::{RpcSender, RpcTransportStats},
+};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+use tracing::{Instrument, Span};
+
+use super::{
+    credits::CreditTracker,
+    failover::{HealthCheck, HealthCheckDue, RpcFailover},
+    keys::{KeyOutcome, KeyPool},
+    secret::{redact_error, ApiKey, REDACTED},
+    telemetry::{record_request, request_span, sizes_enabled, Api, RequestRecord},
+};
+use crate::error::{ClientError, ClientErrorKind, ClientResult, RpcResponseError};
+
+/// The timeout of RPC requests when the client is created without one, the same as `solana-client`'s.
+const DEFAULT_RPC_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Sends JSON-RPC requests to a single URL. `solana-client` does not export its own HTTP sender, so this one stands
+/// in for it. Error responses become [`ClientErrorKind::RpcResponse`] errors and responses with an error status
+/// [`ClientErrorKind::Reqwest`] errors carrying the status.
+pub(crate) struct HttpSender {
+    http_client: reqwest::Client,
+    url: String,
+    request_id: AtomicU64,
+    stats: Mutex<RpcTransportStats>,
+}
+
+impl HttpSender {
+    pub(crate) fn new(url: String, timeout: Option<Duration>) -> Self {
+        let timeout = timeout.unwrap_or(DEFAULT_RPC_TIMEOUT);
+        HttpSender {
+            http_client: reqwest::Client::builder()
+                .timeout(timeout)
+                .pool_idle_timeout(timeout)
+                .build()
+                .expect("the RPC HTTP client builds"),
+            url,
+            request_id: AtomicU64::new(0),
+            stats: Mutex::new(RpcTransportStats::default()),
+        }
+    }
+
+    pub(crate) async fn send(&self, request: RpcRequest, params: &Value) -> ClientResult<Value> {
+        let started = Instant::now();
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": self.request_id.fetch_add(1, Ordering::Relaxed),
+            "method": request.to_string(),
+            "params": params,
+        });
+        let result = self.post(body).await;
+
+        let mut stats = self.stats.lock().unwrap();
+        stats.request_count += 1;
+        stats.elapsed_time += started.elapsed();
+        result
+    }
+
+    async fn post(&self, body: Value) -> ClientResult<Value> {
+        let response = self
+            .http_client
+            .post(&self.url)
+            .header(CONTENT_TYPE, "application/json")
+            .body(body.to_string())
+            .send()
+            .await?
+            .error_for_status()?;
+        let mut json: Value = response.json().await?;
+        if json["error"].is_object() {
+            let kind = match serde_json::from_value::<RpcResponseError>(json["error"].take()) {
+                Ok(error) => ClientErrorKind::RpcResponse(error),
+                Err(e) => ClientErrorKind::Custom(format!(
+                    "Failed to deserialize RPC error response: {}",
+                    e
+                )),
+            };
+            return Err(ClientError::from(kind));
+        }
+        Ok(json["result"].take())
+    }
+
+    pub(crate) fn stats(&self) -> RpcTransportStats {
+        self.stats.lock().unwrap().clone()
+    }
+}
+
+/// The [`RpcSender`] behind [`HeliusClient::rpc_client`](super::init::HeliusClient::rpc_client).
+/// Sends requests over HTTP like the default sender, but keeps the API key out of its URL and errors,
+/// traces every request, see [`telemetry`](super::telemetry), and charges those sent to Helius to the client's credit
+/// tracker.
+/// Each request is sent with a key from the client's [`KeyPool`], through an [`HttpSender`] bound to that key's URL,
+/// or to a fallback endpoint when [`RpcFailover`] routes it there. Mock clients answer the requests for the Helius
+/// endpoint with a mock `RpcClient` instead of HTTP.
+pub(crate) struct HeliusSender {
+    transport: Arc<Transport>,
+}
+
+/// The state of a [`HeliusSender`], shared with the health checks it runs in the background.
+struct Transport {
+    /// The RPC URL without the key, ending in `api-key=`.
+    url_prefix: String,
+    timeout: Option<Duration>,
+    redacted_url: String,
+    credits: Arc<CreditTracker>,
+    keys: Arc<KeyPool>,
+    failover: Arc<RpcFailover>,
+    senders: Mutex<HashMap<ApiKey, Arc<HttpSender>>>,
+    /// Answers for the Helius endpoint in place of the senders, for mock clients.
+    mock: Option<RpcClient>,
+}
+
+impl HeliusSender {
+    pub(crate) fn new(
+        url_prefix: String,
+        timeout: Option<Duration>,
+        credits: Arc<CreditTracker>,
+        keys: Arc<KeyPool>,
+        failover: Arc<RpcFailover>,
+        mock: Option<RpcClient>,
+    ) -> Self {
+        HeliusSender {
+            transport: Arc::new(Transport {
+                redacted_url: format!("{}{}", url_prefix, REDACTED),
+                url_prefix,
+                timeout,
+                credits,
+                keys,
+                failover,
+                senders: Mutex::new(HashMap::new()),
+                mock,
+            }),
+        }
+    }
+}
+
+impl Transport {
+    /// Returns the sender for `api_key`, creating it on first use.
+    fn sender_for(&self, api_key: &ApiKey) -> Arc<HttpSender> {
+        let mut senders = self.senders.lock().unwrap();
+        senders
+            .entry(api_key.clone())
+            .or_insert_with(|| {
+                let url = format!("{}{}", self.url_prefix, api_key.expose());
+                Arc::new(HttpSender::new(url, self.timeout))
+            })
+            .clone()
+    }
+
+    /// Sends `request` to the Helius endpoint with `api_key`.
+    async fn send_to_helius(
+        &self,
+        api_key: &ApiKey,
+        request: RpcRequest,
+        params: &Value,
+    ) -> ClientResult<Value> {
+        match &self.mock {
+            Some(mock) => mock.send(request, params.clone()).await.map_err(ClientError::from),
+            None => self.sender_for(api_key).send(request, params).await,
+        }
+    }
+
+    /// Sends `request` to the Helius endpoint, retrying it with the next usable key if its key is rejected. The request
+    /// is charged to the credit tracker once, when a key is found for it.
+    async fn send_with_keys(
+        &self,
+        request: RpcRequest,
+        params: &Value,
+        span: &Span,
+    ) -> ClientResult<Value> {
+        let endpoint = request.to_string();
+        let cost = self.credits.cost(&endpoint);
+        let (mut index, mut api_key) = self.keys.select(cost)?;
+        if let Err(error) = self.credits.charge(&endpoint) {
+            self.keys.refund(index, cost);
+            return Err(error);
+        }
+        let mut tried = Vec::new();
+        loop {
+            let result = self
+                .send_to_helius(&api_key, request, params)
+                .instrument(span.clone())
+                .await
+                .map_err(redact_error);
+
+            let outcome = match &result {
+                Err(ClientError {
+                    kind: ClientErrorKind::Reqwest(error),
+                }) => error.status().map_or(KeyOutcome::Accepted, |status| {
+                    KeyOutcome::of_status(status.as_u16(), None)
+                }),
+                _ => KeyOutcome::Accepted,
+            };
+            self.keys.report(index, cost, outcome);
+            if outcome.is_rejection() {
+                tried.push(index);
+                if let Some((next_index, next_key)) = self.keys.select_retry(cost, &tried) {
+                    index = next_index;
+                    api_key = next_key;
+                    span.record("retries", tried.len() as u32);
+                    continue;
+                }
+            }
+            return result;
+        }
+    }
+
+    /// Sends `request` to the endpoint at `index`, the Helius endpoint being 0, and reports the outcome to the router.
+    async fn send_to(
+        &self,
+        index: usize,
+        request: RpcRequest,
+        params: &Value,
+        span: &Span,
+    ) -> ClientResult<Value> {
+        let result = match self.failover.sender(index) {
+            Some(sender) => sender
+                .send(request, params)
+                .instrument(span.clone())
+                .await
+                .map_err(redact_error),
+            None => self.send_with_keys(request, params, span).await,
+        };
+        self.failover
+            .report(index, !matches!(&result, Err(error) if is_endpoint_error(error)));
+        result
+    }
+
+    /// Sends `request` along the route chosen by the router, moving to the next endpoint when one fails.
+    async fn send_with_failover(
+        this: &Arc<Self>,
+        request: RpcRequest,
+        params: &Value,
+        span: &Span,
+    ) -> ClientResult<Value> {
+        match this.failover.health_check_due() {
+            Some(HealthCheckDue::Now) => this.check_health().await,
+            Some(HealthCheckDue::Background) => {
+                let transport = this.clone();
+                tokio::spawn(async move { transport.check_health().await });
+            }
+            None => {}
+        }
+        let route = match this.failover.route(request) {
+            Some(route) => route,
+            None => return this.send_with_keys(request, params, span).await,
+        };
+
+        let mut last_error = None;
+        let mut position = 0;
+        while position < route.endpoints.len() {
+            let index = route.endpoints[position];
+            let hedge = route
+                .hedge_delay
+                .zip(route.endpoints.get(position + 1).copied())
+                .filter(|_| position == 0);
+            let (result, tried) = match hedge {
+                Some((delay, hedge_index)) => {
+                    this.send_hedged(index, hedge_index, delay, request, params, span)
+                        .await
+                }
+                None => (this.send_to(index, request, params, span).await, 1),
+            };
+            position += tried;
+            match result {
+                Err(error) if is_endpoint_error(&error) => {
+                    tracing::warn!(parent: span, endpoint = index, error = %error, "RPC endpoint failed, trying the next one");
+                    last_error = Some(error);
+                }
+                result => return result,
+            }
+        }
+        Err(last_error.expect("a route has at least one endpoint"))
+    }
+
+    /// Sends `request` to the endpoint at `index` and, if it has not answered after `delay`, to the one at
+    /// `hedge_index` as well. Returns the first successful result and the number of endpoints tried.
+    async fn send_hedged(
+        &self,
+        index: usize,
+        hedge_index: usize,
+        delay: Duration,
+        request: RpcRequest,
+        params: &Value,
+        span: &Span,
+    ) -> (ClientResult<Value>, usize) {
+        let first = self.send_to(index, request, params, span);
+        let timer = tokio::time::sleep(delay);
+        pin_mut!(first, timer);
+        let first = match select(first, timer).await {
+            Either::Left((result, _)) => return (result, 1),
+            Either::Right((_, first)) => first,
+        };
+
+        tracing::debug!(parent: span, endpoint = hedge_index, "Hedging slow RPC request");
+        let second = self.send_to(hedge_index, request, params, span);
+        pin_mut!(second);
+        let result = match select(first, second).await {
+            Either::Left((Ok(value), _)) | Either::Right((Ok(value), _)) => Ok(value),
+            Either::Left((Err(_), second)) => second.await,
+            Either::Right((Err(_), first)) => first.await,
+        };
+        (result, 2)
+    }
+
+    /// Checks every endpoint with `getHealth` and `getSlot` and hands the results to the router.
+    async fn check_health(&self) {
+        let checks = join_all(
+            (0..self.failover.endpoint_count()).map(|index| self.check_endpoint(index)),
+        )
+        .await;
+        self.failover.finish_health_check(checks);
+    }
+
+    /// Checks the endpoint at `index`. The Helius endpoint is checked with the key the pool prefers, but the checks
+    /// are neither charged to the key nor reported to the pool or the router.
+    async fn check_endpoint(&self, index: usize) -> HealthCheck {
+        let span = tracing::debug_span!("helius.health_check", endpoint = index);
+        let started = Instant::now();
+        let sender = self.failover.sender(index);
+        let api_key = self.keys.peek();
+        let check = |request| {
+            let sender = sender.clone();
+            let api_key = &api_key;
+            async move {
+                match sender {
+                    Some(sender) => sender.send(request, &Value::Null).await,
+                    None => self.send_to_helius(api_key, request, &Value::Null).await,
+                }
+            }
+        };
+        let (health, slot) = join!(
+            check(RpcRequest::GetHealth).instrument(span.clone()),
+            check(RpcRequest::GetSlot).instrument(span)
+        );
+        HealthCheck {
+            healthy: health.is_ok() && slot.is_ok(),
+            slot: slot.ok().and_then(|slot| slot.as_u64()),
+            latency: started.elapsed(),
+        }
+    }
+}
+
+/// Whether `error` says more about the endpoint than the request, so the request may succeed elsewhere.
+fn is_endpoint_error(error: &ClientError) -> bool {
+    match &error.kind {
+        ClientErrorKind::Io(_) | ClientErrorKind::Reqwest(_) => true,
+        ClientErrorKind::RpcResponse(error) => error.code == JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY,
+        _ => false,
+    }
+}
+
+#[async_trait]
+impl RpcSender for HeliusSender {
+    async fn send(&self, request: RpcRequest, params: Value) -> RpcResult<Value> {
+        let transport = &self.transport;
+        let endpoint = request.to_string();
+        let span = request_span(Api::Rpc, &endpoint, "POST", &transport.redacted_url);
+        let measure_sizes = sizes_enabled(&span);
+        let request_bytes = if measure_sizes {
+            params.to_string().len()
+        } else {
+            0
+        };
+
+        let started = Instant::now();
+        let result = if transport.failover.endpoint_count() > 0 {
+            Transport::send_with_failover(transport, request, &params, &span).await
+        } else {
+            transport.send_with_keys(request, &params, &span).await
+        };
+
+        record_request(
+            &span,
+            RequestRecord {
+                api: Api::Rpc,
+                endpoint: &endpoint,
+                status: None,
+                elapsed: started.elapsed(),
+                request_bytes,
+                response_bytes: match &result {
+                    Ok(value) if measure_sizes => value.to_string().len(),
+                    _ => 0,
+                },
+                error: result.as_ref().err(),
+            },
+        );
+        result.map_err(Into::into)
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        let mut stats = RpcTransportStats::default();
+        for sender in self.transport.senders.lock().unwrap().values() {
+            let sender_stats = sender.stats();
+            stats.request_count += sender_stats.request_count;
+            stats.elapsed_time += sender_stats.elapsed_time;
+            stats.rate_limited_time += sender_stats.rate_limited_time;
+        }
+        stats
+    }
+
+    fn url(&self) -> String {
+        self.transport.redacted_url.clone()
+    }
+}
@@ -0,0 +1,127 @@
+//! Tracing spans and metrics for the requests sent by [`HeliusClient`](super::init::HeliusClient).
+//!
+//! Every REST and RPC request runs in a `helius.request` span with the fields `api` (`rest` or `rpc`), `endpoint`
+//! (the client method for REST requests, the RPC method otherwise), `method`, `url` (with the API key redacted),
+//! `status`, `latency_ms`, `request_bytes`, `response_bytes` and `retries`. Chunked calls run in a `helius.batch`
+//! span with `batch_size` and `chunks`.
+//!
+//! With the `metrics` feature the same requests are reported through the [`metrics`](https://docs.rs/metrics) facade,
+//! labelled by `api` and `endpoint`, so any exporter such as `metrics-exporter-prometheus` can serve them:
+//! * `helius_requests_total` - counter, also labelled by `status`
+//! * `helius_request_errors_total` - counter, also labelled by error `kind`
+//! * `helius_request_duration_seconds` - histogram
+//! * `helius_request_size_bytes` and `helius_response_size_bytes` - histograms
+
+use std::time::Duration;
+use tracing::{field, Span};
+
+use crate::error::ClientError;
+
+/// The API a request was sent to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Api {
+    Rest,
+    #[cfg(feature = "rpc")]
+    Rpc,
+}
+
+impl Api {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Api::Rest => "rest",
+            #[cfg(feature = "rpc")]
+            Api::Rpc => "rpc",
+        }
+    }
+}
+
+/// The outcome of a single request, see [`record_request`].
+pub(crate) struct RequestRecord<'a> {
+    #[cfg_attr(not(feature = "metrics"), allow(dead_code))]
+    pub api: Api,
+    #[cfg_attr(not(feature = "metrics"), allow(dead_code))]
+    pub endpoint: &'a str,
+    /// The HTTP status, if a response was received. RPC requests report none.
+    pub status: Option<u16>,
+    pub elapsed: Duration,
+    pub request_bytes: usize,
+    pub response_bytes: usize,
+    pub error: Option<&'a ClientError>,
+}
+
+pub(crate) fn request_span(api: Api, endpoint: &str, method: &str, url: &str) -> Span {
+    tracing::info_span!(
+        "helius.request",
+        api = api.as_str(),
+        endpoint,
+        method,
+        url,
+        status = field::Empty,
+        latency_ms = field::Empty,
+        request_bytes = field::Empty,
+        response_bytes = field::Empty,
+        retries = 0u32,
+    )
+}
+
+pub(crate) fn batch_span(batch_size: usize, chunks: usize) -> Span {
+    tracing::debug_span!("helius.batch", batch_size, chunks)
+}
+
+/// Whether payload sizes are needed, so callers can skip measuring them when nothing records them.
+#[cfg(feature = "rpc")]
+pub(crate) fn sizes_enabled(span: &Span) -> bool {
+    cfg!(feature = "metrics") || !span.is_disabled()
+}
+
+/// Records `record` on `span` and, with the `metrics` feature, as metrics.
+pub(crate) fn record_request(span: &Span, record: RequestRecord) {
+    if let Some(status) = record.status {
+        span.record("status", status);
+    }
+    span.record("latency_ms", record.elapsed.as_millis() as u64);
+    span.record("request_bytes", record.request_bytes as u64);
+    span.record("response_bytes", record.response_bytes as u64);
+
+    let error_kind = error_kind(&record);
+    match (record.error, &error_kind) {
+        (Some(error), _) => {
+            tracing::warn!(parent: span, error = %error, "Helius request failed")
+        }
+        (None, Some(_)) => {
+            tracing::warn!(parent: span, "Helius request returned an error status")
+        }
+        (None, None) => tracing::debug!(parent: span, "Helius request completed"),
+    }
+
+    #[cfg(feature = "metrics")]
+    {
+        let api = record.api.as_str();
+        let endpoint = record.endpoint.to_string();
+        let status = match (record.status, &error_kind) {
+            (Some(status), _) => status.to_string(),
+            (None, Some(_)) => "error".to_string(),
+            (None, None) => "ok".to_string(),
+        };
+        metrics::counter!("helius_requests_total", 1, "api" => api, "endpoint" => endpoint.clone(), "status" => status);
+        if let Some(kind) = error_kind {
+            metrics::counter!("helius_request_errors_total", 1, "api" => api, "endpoint" => endpoint.clone(), "kind" => kind);
+        }
+        metrics::histogram!("helius_request_duration_seconds", record.elapsed.as_secs_f64(), "api" => api, "endpoint" => endpoint.clone());
+        metrics::histogram!("helius_request_size_bytes", record.request_bytes as f64, "api" => api, "endpoint" => endpoint.clone());
+        metrics::histogram!("helius_response_size_bytes", record.response_bytes as f64, "api" => api, "endpoint" => endpoint);
+    }
+}
+
+/// The error kind of a failed request: the [`ClientErrorKind`](crate::error::ClientErrorKind) variant,
+/// or `http` for a response with an error status.
+fn error_kind(record: &RequestRecord) -> Option<String> {
+    match (record.error, record.status) {
+        (Some(error), _) => {
+            let kind = format!("{:?}", error.kind);
+            Some(kind.split('(').next().unwrap_or_default().to_string())
+        }
+        (None, Some(status)) if status >= 400 => Some("http".to_string()),
+        _ => None,
+    }
+}
@@ -1,86 +1,142 @@
+use crate::error::ClientResult;
 use crate::models::{
     enriched_transaction::RequestConfig,
     nft::{
         ActiveListingsRequestConfig, ActiveListingsResponse, MintListRequestConfig,
-        MintListResponse, NftEvent, NftMetadata, NftResponse, TokenBalancesResponse,
+        MintListResponse, NftEvent, NftInfo, NftMetadata, NftResponse, TokenBalancesResponse,
     },
     structs::TokenMetadata,
 };
 
 use super::{
-    init::{HeliusClient, API_URL_V0},
+    batch::BatchResponse,
+    cache::CacheFamily,
+    init::{HeliusClient, MAX_MINTS_PER_REQUEST},
     parse_response,
 };
-use solana_client::client_error::{Result as ClientResult};
+use futures::{stream, StreamExt, TryStreamExt};
 
 use std::collections::HashMap;
 
-use super::init::API_URL_V1;
-
 impl HeliusClient {
     /// Returns the native balance and token balances for a given address. GET request to `https://api.helius.xyz/v0/addresses/{address}/balances`.
+    /// Concurrent calls for the same address share one request when coalescing is enabled.
     /// * `address` - The address that you want token balances for.
     pub async fn get_token_balances(&self, address: String) -> ClientResult<TokenBalancesResponse> {
-        let request_url = format!(
-            "{}/addresses/{}/balances?api-key={}",
-            API_URL_V0, address, self.api_key
-        );
+        match &self.coalescer {
+            Some(coalescer) => {
+                coalescer
+                    .token_balances
+                    .run(address.clone(), || {
+                        self.get_token_balances_uncoalesced(address)
+                    })
+                    .await
+            }
+            None => self.get_token_balances_uncoalesced(address).await,
+        }
+    }
+
+    async fn get_token_balances_uncoalesced(
+        &self,
+        address: String,
+    ) -> ClientResult<TokenBalancesResponse> {
+        let request_url = format!("{}/addresses/{}/balances", self.api_url_v0, address);
 
         let response = self
-            .http_client
-            .get(request_url)
-            .header("accept", "application/json")
-            .header("Content-Type", "application/json")
-            .send()
+            .send(
+                "get_token_balances",
+                self.http_client
+                    .get(request_url)
+                    .header("accept", "application/json")
+                    .header("Content-Type", "application/json"),
+            )
             .await;
 
         parse_response(response).await
     }
 
-    /// Returns the NFTs held for a given address. GET request to `https://api.helius.xyz/v0/addresses/{address}/nfts`.
+    /// Returns a single page of the NFTs held for a given address. GET request to `https://api.helius.xyz/v0/addresses/{address}/nfts`.
+    /// Use [`get_all_nfts`](HeliusClient::get_all_nfts) for every page.
     /// * `address` - The address that you want nfts for.
+    /// * `page_number` - The page to fetch, starting at 1. Default is the first page.
     pub async fn get_nfts(
         &self,
         address: String,
         page_number: Option<usize>,
-    ) -> ClientResult<NftResponse> {
-        let mut request_url = format!(
-            "{}/addresses/{}/nfts?api-key={}",
-            API_URL_V0, address, self.api_key
-        );
+    ) -> ClientResult<Vec<NftInfo>> {
+        Ok(self.get_nfts_page(address, page_number).await?.nfts)
+    }
 
-        if page_number.is_some() {
-            request_url = format!("{}&pageNumber={}", request_url, page_number.unwrap());
-        }
+    /// Returns a single page of the NFTs held for a given address along with the number of pages.
+    async fn get_nfts_page(
+        &self,
+        address: String,
+        page_number: Option<usize>,
+    ) -> ClientResult<NftResponse> {
+        let request_url = format!("{}/addresses/{}/nfts", self.api_url_v0, address);
 
-        let response = self
+        let mut request = self
             .http_client
             .get(request_url)
             .header("accept", "application/json")
-            .header("Content-Type", "application/json")
-            .send()
-            .await;
+            .header("Content-Type", "application/json");
+        if let Some(page_number) = page_number {
+            request = request.query(&[("pageNumber", page_number)]);
+        }
+        let response = self.send("get_nfts", request).await;
 
         parse_response(response).await
     }
 
+    /// Returns every NFT held for a given address. Fetches the first page to learn `number_of_pages`, then requests the remaining pages concurrently (at most [`max_concurrent_requests`](HeliusClient::max_concurrent_requests) at a time) and merges them in page order.
+    /// * `address` - The address that you want nfts for.
+    pub async fn get_all_nfts(&self, address: String) -> ClientResult<Vec<NftInfo>> {
+        let first_page = self.get_nfts_page(address.clone(), Some(1)).await?;
+        let mut nfts = first_page.nfts;
+
+        let remaining_pages: Vec<NftResponse> = stream::iter(2..=first_page.number_of_pages)
+            .map(|page_number| self.get_nfts_page(address.clone(), Some(page_number)))
+            .buffered(self.max_concurrent_requests.max(1))
+            .try_collect()
+            .await?;
+
+        for page in remaining_pages {
+            nfts.extend(page.nfts);
+        }
+        Ok(nfts)
+    }
+
     /// Returns NFT metadata for the given token mint addresses. POST request to `https://api.helius.xyz/v1/nfts`.
+    /// Mints are sent in chunks of [`MAX_MINTS_PER_REQUEST`], see [`BatchResponse`].
     /// * `token_mints` - The nft mint addresses that you want metadata for.
     pub async fn get_nfts_metadata(
         &self,
         token_mints: Vec<String>,
+    ) -> ClientResult<BatchResponse<NftMetadata>> {
+        Ok(self
+            .execute_batched(token_mints, MAX_MINTS_PER_REQUEST, |chunk| {
+                self.get_nfts_metadata_chunk(chunk)
+            })
+            .await)
+    }
+
+    async fn get_nfts_metadata_chunk(
+        &self,
+        token_mints: Vec<String>,
     ) -> ClientResult<Vec<NftMetadata>> {
-        let request_url = format!("{}/nfts?api-key={}", API_URL_V1, self.api_key);
+        let request_url = format!("{}/nfts", self.api_url_v1);
         let mut body = HashMap::new();
         body.insert("mints", token_mints);
 
         let response = self
-            .http_client
-            .post(request_url)
-            .header("accept", "application/json")
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
+            .send(
+                "get_nfts_metadata",
+                self.http_client
+                    .post(request_url)
+                    .header("accept", "application/json")
+                    .header("Content-Type", "application/json")
+                    .json(&body),
+            )
             .await;
 
         parse_response(response).await
@@ -89,18 +145,20 @@ impl HeliusClient {
     /// Returns all NFT related events associated with the given address. POST request to `https://api.helius.xyz/v1/nft-events`.
     /// * `config` - The [`RequestConfig`](crate::models::enriched_transaction::RequestConfig).
     pub async fn get_nft_events_for_address(&self, config: RequestConfig) -> ClientResult<Vec<NftEvent>> {
-        let query = config.generate_query_parameters(self.api_key.clone())?;
+        let query = config.generate_query_parameters()?;
         let request_url = format!(
             "{}/addresses/{}/nft-events?",
-            API_URL_V0,
+            self.api_url_v0,
             config.address.to_string(),
         );
 
         let response = self
-            .http_client
-            .get(request_url)
-            .query(&query)
-            .send()
+            .send(
+                "get_nft_events_for_address",
+                self.http_client
+                    .get(request_url)
+                    .query(&query),
+            )
             .await;
 
         parse_response(response).await
@@ -109,14 +167,16 @@ impl HeliusClient {
     /// Returns all NFT related events associated with the given address. GET request to `https://api.helius.xyz/v1/addresses/{address}/nft-events`.
     /// * `config` - The [`RequestConfig`](crate::models::enriched_transaction::RequestConfig).
     pub async fn get_nft_events(&self, config: RequestConfig) -> ClientResult<Vec<NftEvent>> {
-        let query = config.generate_query_parameters(self.api_key.clone())?;
+        let query = config.generate_query_parameters()?;
         let request_url = format!(
             "{}/addresses/{}/nft-events?",
-            API_URL_V0,
+            self.api_url_v0,
             config.address.to_string(),
         );
 
-        let response = self.http_client.get(request_url).query(&query).send().await;
+        let response = self
+            .send("get_nft_events", self.http_client.get(request_url).query(&query))
+            .await;
 
         parse_response(response).await
     }
@@ -128,15 +188,17 @@ impl HeliusClient {
         config: ActiveListingsRequestConfig,
     ) -> ClientResult<ActiveListingsResponse> {
         let body = config.generate_request_body()?;
-        let request_url = format!("{}/active-listings?api-key={}", API_URL_V1, self.api_key);
+        let request_url = format!("{}/active-listings", self.api_url_v1);
 
         let response = self
-            .http_client
-            .post(request_url)
-            .header("accept", "application/json")
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
+            .send(
+                "get_active_nft_listings",
+                self.http_client
+                    .post(request_url)
+                    .header("accept", "application/json")
+                    .header("Content-Type", "application/json")
+                    .json(&body),
+            )
             .await;
 
         parse_response(response).await
@@ -148,40 +210,90 @@ impl HeliusClient {
         &self,
         config: MintListRequestConfig,
     ) -> ClientResult<MintListResponse> {
-        let request_url = format!("{}/mintlist?api-key={}", API_URL_V1, self.api_key);
+        let request_url = format!("{}/mintlist", self.api_url_v1);
 
         let body = config.generate_request_body()?;
         let response = self
-            .http_client
-            .post(request_url)
-            .header("accept", "application/json")
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
+            .send(
+                "get_mint_list",
+                self.http_client
+                    .post(request_url)
+                    .header("accept", "application/json")
+                    .header("Content-Type", "application/json")
+                    .json(&body),
+            )
             .await;
 
         parse_response(response).await
     }
 
     /// Returns token metadata (whether NFT or Fungible) for the given token mint addresses. POST request to `https://api.helius.xyz/v0/tokens/metadata`.
+    /// Mints are sent in chunks of [`MAX_MINTS_PER_REQUEST`], see [`BatchResponse`]. Served from the client's cache when one is configured,
+    /// and merged with concurrent calls into one request when coalescing is enabled.
     /// * `token_mints` - The token mint addresses that you want metadata for.
     pub async fn get_tokens_metadata(
         &self,
         token_mints: Vec<String>,
+    ) -> ClientResult<BatchResponse<TokenMetadata>> {
+        Ok(self
+            .execute_cached_batch(
+                Some(CacheFamily::TokenMetadata),
+                token_mints,
+                token_metadata_mint,
+                |mints| self.fetch_tokens_metadata(mints),
+            )
+            .await)
+    }
+
+    async fn fetch_tokens_metadata(
+        &self,
+        token_mints: Vec<String>,
+    ) -> BatchResponse<TokenMetadata> {
+        let fetch = |mints| {
+            self.execute_batched(mints, MAX_MINTS_PER_REQUEST, |chunk| {
+                self.get_tokens_metadata_chunk(chunk)
+            })
+        };
+
+        match &self.coalescer {
+            Some(coalescer) => {
+                coalescer
+                    .tokens_metadata
+                    .run(
+                        coalescer.config.batch_window,
+                        token_mints,
+                        token_metadata_mint,
+                        fetch,
+                    )
+                    .await
+            }
+            None => fetch(token_mints).await,
+        }
+    }
+
+    async fn get_tokens_metadata_chunk(
+        &self,
+        token_mints: Vec<String>,
     ) -> ClientResult<Vec<TokenMetadata>> {
-        let request_url = format!("{}/tokens/metadata?api-key={}", API_URL_V0, self.api_key);
+        let request_url = format!("{}/tokens/metadata", self.api_url_v0);
         let mut body = HashMap::new();
         body.insert("mintAccounts", token_mints);
 
         let response = self
-            .http_client
-            .post(request_url)
-            .header("accept", "application/json")
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
+            .send(
+                "get_tokens_metadata",
+                self.http_client
+                    .post(request_url)
+                    .header("accept", "application/json")
+                    .header("Content-Type", "application/json")
+                    .json(&body),
+            )
             .await;
 
         parse_response(response).await
     }
 }
+
+fn token_metadata_mint(metadata: &TokenMetadata) -> Option<String> {
+    Some(metadata.mint.clone())
+}
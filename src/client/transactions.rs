@@ -1,14 +1,16 @@
 use super::{
-    init::{HeliusClient, API_URL_V0},
+    batch::BatchResponse,
+    cache::CacheFamily,
+    init::{HeliusClient, MAX_TRANSACTIONS_PER_REQUEST},
     parse_response,
 };
-use solana_client::client_error::Result as ClientResult;
-use solana_sdk::commitment_config::CommitmentLevel;
 
 use std::collections::HashMap;
 
 use crate::{
     client::api_commitment_error,
+    error::ClientResult,
+    types::CommitmentLevel,
     models::{
         enriched_transaction::{EnrichedTransaction, RequestConfig},
         raw_transaction::{GetRawTransactionsRequestConfig, RawTransaction},
@@ -22,39 +24,66 @@ impl HeliusClient {
         &self,
         config: GetRawTransactionsRequestConfig,
     ) -> ClientResult<Vec<RawTransaction>> {
-        let query = config.generate_query_parameters(self.api_key.clone())?;
+        let query = config.generate_query_parameters()?;
         let request_url = format!(
             "{}/addresses/{}/raw-transactions?",
-            API_URL_V0,
+            self.api_url_v0,
             config.address.to_string(),
         );
 
-        let response = self.http_client.get(request_url).query(&query).send().await;
+        let response = self
+            .send(
+                "get_transactions_for_address",
+                self.http_client.get(request_url).query(&query),
+            )
+            .await;
 
         parse_response(response).await
     }
 
     /// Returns raw transaction information for the given transaction hashes. POST request to `https://api.helius.xyz/v0/raw-transactions`.
+    /// Hashes are sent in chunks of [`MAX_TRANSACTIONS_PER_REQUEST`], see [`BatchResponse`]. Finalized results are served from the client's cache when one is configured.
     /// * `transaction_hashes` - The transaction hashes as Strings.
     /// * `commitment` - an Option containing the [`CommitmentLevel`]. Default is finalized.
     pub async fn get_transactions_by_hash(
         &self,
         transaction_hashes: Vec<String>,
         commitment: Option<CommitmentLevel>,
-    ) -> ClientResult<Vec<RawTransaction>> {
-        let request_url = format!("{}/raw-transactions?api-key={}", API_URL_V0, self.api_key,);
-
+    ) -> ClientResult<BatchResponse<RawTransaction>> {
+        let request_url = format!("{}/raw-transactions", self.api_url_v0);
         let request_url = attach_commitment(request_url, commitment)?;
+
+        Ok(self
+            .execute_cached_batch(
+                finalized_cache_family(CacheFamily::RawTransactions, commitment),
+                transaction_hashes,
+                |transaction: &RawTransaction| transaction.transaction.signatures.first().cloned(),
+                |hashes| {
+                    self.execute_batched(hashes, MAX_TRANSACTIONS_PER_REQUEST, |chunk| {
+                        self.get_transactions_by_hash_chunk(&request_url, chunk)
+                    })
+                },
+            )
+            .await)
+    }
+
+    async fn get_transactions_by_hash_chunk(
+        &self,
+        request_url: &str,
+        transaction_hashes: Vec<String>,
+    ) -> ClientResult<Vec<RawTransaction>> {
         let mut body = HashMap::new();
         body.insert("transactions", transaction_hashes);
 
         let response = self
-            .http_client
-            .post(request_url)
-            .header("accept", "application/json")
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
+            .send(
+                "get_transactions_by_hash",
+                self.http_client
+                    .post(request_url)
+                    .header("accept", "application/json")
+                    .header("Content-Type", "application/json")
+                    .json(&body),
+            )
             .await;
 
         parse_response(response).await
@@ -66,60 +95,98 @@ impl HeliusClient {
         &self,
         config: RequestConfig,
     ) -> ClientResult<Vec<EnrichedTransaction>> {
-        let query = config.generate_query_parameters(self.api_key.clone())?;
+        let query = config.generate_query_parameters()?;
         let request_url = format!(
             "{}/addresses/{}/transactions?",
-            API_URL_V0,
+            self.api_url_v0,
             config.address.to_string(),
         );
         let request_url = attach_commitment(request_url, config.commitment)?;
 
-        let response = self.http_client.get(request_url).query(&query).send().await;
+        let response = self
+            .send(
+                "get_enriched_transactions",
+                self.http_client.get(request_url).query(&query),
+            )
+            .await;
 
         parse_response(response).await
     }
 
     /// Returns enriched transaction information for the given transaction hashes. POST request to `https://api.helius.xyz/v0/transactions`.
+    /// Hashes are sent in chunks of [`MAX_TRANSACTIONS_PER_REQUEST`], see [`BatchResponse`]. Finalized results are served from the client's cache when one is configured.
     /// * `transaction_hashes` - The transaction hashes as Strings.
     /// * `commitment` - an Option containing the [`CommitmentLevel`]. Default is finalized.
     pub async fn get_enriched_transactions_by_hash(
         &self,
         transaction_hashes: Vec<String>,
         commitment: Option<CommitmentLevel>,
-    ) -> ClientResult<Vec<EnrichedTransaction>> {
-        let request_url = format!("{}/transactions/?api-key={}", API_URL_V0, self.api_key,);
-
+    ) -> ClientResult<BatchResponse<EnrichedTransaction>> {
+        let request_url = format!("{}/transactions/", self.api_url_v0);
         let request_url = attach_commitment(request_url, commitment)?;
 
+        Ok(self
+            .execute_cached_batch(
+                finalized_cache_family(CacheFamily::EnrichedTransactions, commitment),
+                transaction_hashes,
+                |transaction: &EnrichedTransaction| Some(transaction.signature.clone()),
+                |hashes| {
+                    self.execute_batched(hashes, MAX_TRANSACTIONS_PER_REQUEST, |chunk| {
+                        self.get_enriched_transactions_by_hash_chunk(&request_url, chunk)
+                    })
+                },
+            )
+            .await)
+    }
+
+    async fn get_enriched_transactions_by_hash_chunk(
+        &self,
+        request_url: &str,
+        transaction_hashes: Vec<String>,
+    ) -> ClientResult<Vec<EnrichedTransaction>> {
         let mut body = HashMap::new();
         body.insert("transactions", transaction_hashes);
 
         let response = self
-            .http_client
-            .post(request_url)
-            .header("accept", "application/json")
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
+            .send(
+                "get_enriched_transactions_by_hash",
+                self.http_client
+                    .post(request_url)
+                    .header("accept", "application/json")
+                    .header("Content-Type", "application/json")
+                    .json(&body),
+            )
             .await;
 
         parse_response(response).await
     }
 }
 
+/// Only finalized transactions are immutable, so `Confirmed` requests bypass the cache.
+fn finalized_cache_family(
+    family: CacheFamily,
+    commitment: Option<CommitmentLevel>,
+) -> Option<CacheFamily> {
+    match commitment {
+        Some(CommitmentLevel::Confirmed) => None,
+        _ => Some(family),
+    }
+}
+
 fn attach_commitment(
     mut request_url: String,
     commitment: Option<CommitmentLevel>,
 ) -> ClientResult<String> {
+    request_url.push(if request_url.contains('?') { '&' } else { '?' });
     match commitment {
         Some(CommitmentLevel::Confirmed) => {
-            request_url.push_str("&commitment=confirmed");
+            request_url.push_str("commitment=confirmed");
         }
         Some(CommitmentLevel::Finalized) => {
-            request_url.push_str("&commitment=finalized");
+            request_url.push_str("commitment=finalized");
         }
         None => {
-            request_url.push_str("&commitment=finalized");
+            request_url.push_str("commitment=finalized");
         }
         _ => return api_commitment_error(),
     }
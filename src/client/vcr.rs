@@ -0,0 +1,246 @@
+use reqwest::{Client as RestClient, Request, Response, Url};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use crate::error::{ClientError, ClientErrorKind, ClientResult};
+
+use super::init::HeliusClient;
+
+/// Whether a [`Vcr`] captures real traffic or serves it back.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum VcrMode {
+    /// Requests are sent to the API and every request/response pair is written to the cassette.
+    Record,
+    /// Requests are answered from the cassette and never reach the network.
+    Replay,
+}
+
+/// A request as stored in a cassette. `path` is the URL path and query with the `api-key` parameter removed,
+/// so cassettes hold no secrets and replay against any base URL.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+}
+
+/// A response as stored in a cassette. JSON bodies are stored in `body`, any other non-empty body in `text`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RecordedResponse {
+    pub status: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+/// The contents of a cassette file.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+struct Tape {
+    cassette: Cassette,
+    played: Vec<bool>,
+}
+
+/// Records REST requests made by a [`HeliusClient`] into a cassette file, or replays them from one.
+/// Attach it with [`HeliusClient::with_vcr`].
+///
+/// In replay mode each recorded interaction answers one request with the same method, path, query and body,
+/// in the order they were recorded. A request with no interaction left to play fails instead of reaching the network.
+pub struct Vcr {
+    mode: VcrMode,
+    path: PathBuf,
+    tape: Mutex<Tape>,
+    /// Held while the cassette is written, so concurrent requests reach the file in order.
+    writing: tokio::sync::Mutex<()>,
+}
+
+impl Vcr {
+    /// Records into the cassette at `path`, replacing it if it exists. The file is rewritten after every request.
+    pub fn record<P: AsRef<Path>>(path: P) -> Self {
+        Vcr {
+            mode: VcrMode::Record,
+            path: path.as_ref().to_path_buf(),
+            tape: Mutex::new(Tape {
+                cassette: Cassette::default(),
+                played: Vec::new(),
+            }),
+            writing: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// Replays the cassette at `path`.
+    pub fn replay<P: AsRef<Path>>(path: P) -> ClientResult<Self> {
+        let contents =
+            fs::read(path.as_ref()).map_err(|e| ClientError::from(ClientErrorKind::Io(e)))?;
+        let cassette: Cassette = serde_json::from_slice(&contents)
+            .map_err(|e| ClientError::from(ClientErrorKind::SerdeJson(e)))?;
+        Ok(Vcr {
+            mode: VcrMode::Replay,
+            path: path.as_ref().to_path_buf(),
+            tape: Mutex::new(Tape {
+                played: vec![false; cassette.interactions.len()],
+                cassette,
+            }),
+            writing: tokio::sync::Mutex::new(()),
+        })
+    }
+
+    pub fn mode(&self) -> VcrMode {
+        self.mode
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the interactions recorded so far, or loaded from the cassette in replay mode.
+    pub fn interactions(&self) -> Vec<Interaction> {
+        self.tape.lock().unwrap().cassette.interactions.clone()
+    }
+
+    pub(crate) async fn execute(
+        &self,
+        http_client: &RestClient,
+        request: Request,
+    ) -> ClientResult<Response> {
+        let recorded_request = RecordedRequest {
+            method: request.method().to_string(),
+            path: scrub_path(request.url()),
+            body: request
+                .body()
+                .and_then(|body| body.as_bytes())
+                .and_then(|bytes| serde_json::from_slice(bytes).ok()),
+        };
+
+        match self.mode {
+            VcrMode::Replay => {
+                let response = self.play(&recorded_request)?;
+                Ok(into_response(response))
+            }
+            VcrMode::Record => {
+                let response = http_client
+                    .execute(request)
+                    .await
+                    .map_err(|e| ClientError::from(ClientErrorKind::Reqwest(e)))?;
+                let status = response.status().as_u16();
+                let bytes = response
+                    .bytes()
+                    .await
+                    .map_err(|e| ClientError::from(ClientErrorKind::Reqwest(e)))?;
+                let recorded_response = RecordedResponse {
+                    status,
+                    body: serde_json::from_slice(&bytes).ok(),
+                    text: match serde_json::from_slice::<Value>(&bytes) {
+                        Err(_) if !bytes.is_empty() => {
+                            Some(String::from_utf8_lossy(&bytes).into_owned())
+                        }
+                        _ => None,
+                    },
+                };
+                self.record_interaction(Interaction {
+                    request: recorded_request,
+                    response: recorded_response.clone(),
+                })
+                .await?;
+                Ok(into_response(recorded_response))
+            }
+        }
+    }
+
+    fn play(&self, request: &RecordedRequest) -> ClientResult<RecordedResponse> {
+        let mut tape = self.tape.lock().unwrap();
+        let Tape { cassette, played } = &mut *tape;
+        let position = cassette
+            .interactions
+            .iter()
+            .zip(played.iter())
+            .position(|(interaction, played)| !*played && interaction.request == *request);
+        match position {
+            Some(index) => {
+                played[index] = true;
+                Ok(cassette.interactions[index].response.clone())
+            }
+            None => Err(ClientError::from(ClientErrorKind::Custom(format!(
+                "No recorded interaction left in {} for {} {}",
+                self.path.display(),
+                request.method,
+                request.path
+            )))),
+        }
+    }
+
+    async fn record_interaction(&self, interaction: Interaction) -> ClientResult<()> {
+        {
+            let mut tape = self.tape.lock().unwrap();
+            tape.cassette.interactions.push(interaction);
+            tape.played.push(true);
+        }
+        let _writing = self.writing.lock().await;
+        let contents = serde_json::to_vec_pretty(&self.tape.lock().unwrap().cassette)
+            .map_err(|e| ClientError::from(ClientErrorKind::SerdeJson(e)))?;
+        if let Some(parent) = self.path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| ClientError::from(ClientErrorKind::Io(e)))?;
+        }
+        tokio::fs::write(&self.path, contents)
+            .await
+            .map_err(|e| ClientError::from(ClientErrorKind::Io(e)))
+    }
+}
+
+/// Returns the path and query of `url` without the `api-key` query parameter.
+fn scrub_path(url: &Url) -> String {
+    let mut url = url.clone();
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(key, _)| key != "api-key")
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+    if pairs.is_empty() {
+        url.set_query(None);
+    } else {
+        url.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+    match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    }
+}
+
+fn into_response(recorded: RecordedResponse) -> Response {
+    let body = match (recorded.body, recorded.text) {
+        (Some(body), _) => body.to_string(),
+        (None, Some(text)) => text,
+        (None, None) => String::new(),
+    };
+    http::Response::builder()
+        .status(recorded.status)
+        .body(body)
+        .map(Response::from)
+        .expect("recorded status must be a valid HTTP status code")
+}
+
+impl HeliusClient {
+    /// Records REST requests into, or replays them from, the cassette of `vcr`. RPC requests are not affected.
+    pub fn with_vcr(mut self, vcr: Vcr) -> Self {
+        self.vcr = Some(vcr);
+        self
+    }
+}
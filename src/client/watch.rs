@@ -0,0 +1,175 @@
+use futures::{stream, Stream};
+use lru::LruCache;
+use solana_program::pubkey::Pubkey;
+use std::{collections::VecDeque, num::NonZeroUsize, time::Duration};
+
+use crate::error::ClientResult;
+use crate::models::enriched_transaction::{
+    parse_signature, EnrichedTransaction, RequestConfig, MAX_TRANSACTIONS_PER_PAGE,
+};
+
+use super::{api::HeliusApi, init::HeliusClient};
+
+/// The number of recent signatures a watch remembers to drop transactions it already yielded.
+const SEEN_CAPACITY: usize = 10_000;
+
+impl HeliusClient {
+    /// Polls the enriched transaction history of `addresses` every `interval` and yields the transactions that happen
+    /// after the watch starts, a polling alternative to webhooks. See [`watch_addresses`].
+    pub fn watch_addresses(
+        &self,
+        addresses: Vec<Pubkey>,
+        interval: Duration,
+    ) -> impl Stream<Item = ClientResult<EnrichedTransaction>> + '_ {
+        watch_addresses(self, addresses, interval)
+    }
+}
+
+/// Polls the enriched transaction history of `addresses` through `api` every `interval`, and yields every transaction
+/// that happens after the first poll once.
+///
+/// Each poll fetches the transactions of every address after the last one it saw, using `until`. The transactions of a
+/// poll are yielded oldest first by slot, and a transaction involving several of the addresses is yielded only once.
+/// Polling is driven by the consumer: the next poll starts `interval` after the transactions of the previous one are
+/// consumed, so a slow consumer slows the watch down rather than letting transactions pile up.
+///
+/// A failed request yields its error; the address is polled again from the same transaction in the next poll. The
+/// stream never ends.
+pub fn watch_addresses<A: HeliusApi + ?Sized>(
+    api: &A,
+    addresses: Vec<Pubkey>,
+    interval: Duration,
+) -> impl Stream<Item = ClientResult<EnrichedTransaction>> + '_ {
+    let watch = Watch {
+        api,
+        addresses: addresses
+            .into_iter()
+            .map(|address| WatchedAddress {
+                address,
+                started: false,
+                last_seen: None,
+            })
+            .collect(),
+        interval,
+        polled: false,
+        pending: VecDeque::new(),
+        seen: LruCache::new(NonZeroUsize::new(SEEN_CAPACITY).unwrap()),
+    };
+    stream::unfold(watch, |mut watch| async move {
+        let item = watch.next().await;
+        Some((item, watch))
+    })
+}
+
+struct WatchedAddress {
+    address: Pubkey,
+    /// Whether the newest transaction of the address at the start of the watch is known.
+    started: bool,
+    last_seen: Option<String>,
+}
+
+struct Watch<'a, A: ?Sized> {
+    api: &'a A,
+    addresses: Vec<WatchedAddress>,
+    interval: Duration,
+    polled: bool,
+    /// The transactions and errors of the last poll that were not yielded yet.
+    pending: VecDeque<ClientResult<EnrichedTransaction>>,
+    seen: LruCache<String, ()>,
+}
+
+impl<'a, A: HeliusApi + ?Sized> Watch<'a, A> {
+    async fn next(&mut self) -> ClientResult<EnrichedTransaction> {
+        loop {
+            if let Some(item) = self.pending.pop_front() {
+                return item;
+            }
+            if self.polled {
+                tokio::time::sleep(self.interval).await;
+            }
+            self.poll().await;
+            self.polled = true;
+        }
+    }
+
+    async fn poll(&mut self) {
+        let mut transactions = Vec::new();
+        for index in 0..self.addresses.len() {
+            match self.poll_address(index).await {
+                Ok(new) => transactions.extend(new),
+                Err(e) => self.pending.push_back(Err(e)),
+            }
+        }
+        // Pages are newest first, so this orders every address oldest first before merging them by slot.
+        transactions.reverse();
+        transactions.sort_by_key(|transaction| transaction.slot);
+        for transaction in transactions {
+            if self.seen.put(transaction.signature.clone(), ()).is_none() {
+                self.pending.push_back(Ok(transaction));
+            }
+        }
+    }
+
+    /// Returns the transactions of an address since the last poll, newest first. The first poll only looks up the
+    /// newest transaction of the address.
+    async fn poll_address(&mut self, index: usize) -> ClientResult<Vec<EnrichedTransaction>> {
+        let watched = &self.addresses[index];
+        if !watched.started {
+            let newest = self.fetch_page(&watched.address, None, None, 1).await?;
+            let watched = &mut self.addresses[index];
+            watched.started = true;
+            watched.last_seen = newest
+                .first()
+                .map(|transaction| transaction.signature.clone());
+            if let Some(signature) = &watched.last_seen {
+                self.seen.put(signature.clone(), ());
+            }
+            return Ok(Vec::new());
+        }
+
+        let mut transactions: Vec<EnrichedTransaction> = Vec::new();
+        loop {
+            let before = transactions
+                .last()
+                .map(|transaction| transaction.signature.clone());
+            let page = self
+                .fetch_page(
+                    &watched.address,
+                    before.as_deref(),
+                    watched.last_seen.as_deref(),
+                    MAX_TRANSACTIONS_PER_PAGE,
+                )
+                .await?;
+            let full = page.len() >= MAX_TRANSACTIONS_PER_PAGE;
+            transactions.extend(
+                page.into_iter().filter(|transaction| {
+                    Some(&transaction.signature) != watched.last_seen.as_ref()
+                }),
+            );
+            if !full {
+                break;
+            }
+        }
+        if let Some(newest) = transactions.first() {
+            self.addresses[index].last_seen = Some(newest.signature.clone());
+        }
+        Ok(transactions)
+    }
+
+    async fn fetch_page(
+        &self,
+        address: &Pubkey,
+        before: Option<&str>,
+        until: Option<&str>,
+        limit: usize,
+    ) -> ClientResult<Vec<EnrichedTransaction>> {
+        let config = RequestConfig {
+            address: *address,
+            before: before.map(parse_signature).transpose()?,
+            until: until.map(parse_signature).transpose()?,
+            limit: Some(limit),
+            ..Default::default()
+        };
+        self.api.get_enriched_transactions(config).await
+    }
+}
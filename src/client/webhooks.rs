@@ -1,13 +1,13 @@
+use crate::error::{ClientError, ClientErrorKind, ClientResult};
 use crate::models::enums::TransactionType;
 
 use super::{
-    init::{HeliusClient, API_URL_V0},
+    init::HeliusClient,
     parse_response,
 };
 use serde::{Deserialize, Serialize};
-use solana_client::client_error::{ClientError, ClientErrorKind, Result as ClientResult};
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Webhook {
     #[serde(rename = "webhookID")]
@@ -21,7 +21,7 @@ pub struct Webhook {
     pub auth_header: String,
 }
 
-#[derive(Serialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateWebhookRequest {
     #[serde(rename = "webhookURL")]
@@ -50,15 +50,17 @@ impl HeliusClient {
         &self,
         webhook_request: CreateWebhookRequest,
     ) -> ClientResult<Webhook> {
-        let request_url = format!("{}/webhooks/?api-key={}", API_URL_V0, self.api_key);
+        let request_url = format!("{}/webhooks/", self.api_url_v0);
 
         let response = self
-            .http_client
-            .post(request_url)
-            .header("accept", "application/json")
-            .header("Content-Type", "application/json")
-            .json(&webhook_request)
-            .send()
+            .send(
+                "create_webhook",
+                self.http_client
+                    .post(request_url)
+                    .header("accept", "application/json")
+                    .header("Content-Type", "application/json")
+                    .json(&webhook_request),
+            )
             .await;
 
         parse_response(response).await
@@ -66,14 +68,16 @@ impl HeliusClient {
 
     /// Returns all webhooks for the API key used to create the [`HeliusClient`](super::helius_rust_client::HeliusClient). GET request to `https://api.helius.xyz/v0/webhooks`.
     pub async fn get_webhooks(&self) -> ClientResult<Vec<Webhook>> {
-        let request_url = format!("{}/webhooks?api-key={}", API_URL_V0, self.api_key);
+        let request_url = format!("{}/webhooks", self.api_url_v0);
 
         let response = self
-            .http_client
-            .get(request_url)
-            .header("accept", "application/json")
-            .header("Content-Type", "application/json")
-            .send()
+            .send(
+                "get_webhooks",
+                self.http_client
+                    .get(request_url)
+                    .header("accept", "application/json")
+                    .header("Content-Type", "application/json"),
+            )
             .await;
 
         parse_response(response).await
@@ -82,17 +86,16 @@ impl HeliusClient {
     /// Returns a single webhook. GET request to `https://api.helius.xyz/v0/webhooks/{webhook_id}`.
     /// * `webhook_id` - The webhook that you want to fetch.
     pub async fn get_webhook(&self, webhook_id: String) -> ClientResult<Webhook> {
-        let request_url = format!(
-            "{}/webhooks/{}?api-key={}",
-            API_URL_V0, webhook_id, self.api_key
-        );
+        let request_url = format!("{}/webhooks/{}", self.api_url_v0, webhook_id);
 
         let response = self
-            .http_client
-            .get(request_url)
-            .header("accept", "application/json")
-            .header("Content-Type", "application/json")
-            .send()
+            .send(
+                "get_webhook",
+                self.http_client
+                    .get(request_url)
+                    .header("accept", "application/json")
+                    .header("Content-Type", "application/json"),
+            )
             .await;
 
         parse_response(response).await
@@ -105,18 +108,17 @@ impl HeliusClient {
         webhook_id: String,
         new_webhook: CreateWebhookRequest,
     ) -> ClientResult<Webhook> {
-        let request_url = format!(
-            "{}/webhooks/{}?api-key={}",
-            API_URL_V0, webhook_id, self.api_key
-        );
+        let request_url = format!("{}/webhooks/{}", self.api_url_v0, webhook_id);
 
         let response = self
-            .http_client
-            .put(request_url)
-            .header("accept", "application/json")
-            .header("Content-Type", "application/json")
-            .json(&new_webhook)
-            .send()
+            .send(
+                "edit_webhook",
+                self.http_client
+                    .put(request_url)
+                    .header("accept", "application/json")
+                    .header("Content-Type", "application/json")
+                    .json(&new_webhook),
+            )
             .await;
 
         parse_response(response).await
@@ -125,17 +127,16 @@ impl HeliusClient {
     /// Deletes a webhook. DELETE request to `https://api.helius.xyz/v0/webhooks/{webhook_id}`.
     /// * `webhook_id` - The webhook that you want to delete.
     pub async fn delete_webhook(&self, webhook_id: String) -> ClientResult<()> {
-        let request_url = format!(
-            "{}/webhooks/{}?api-key={}",
-            API_URL_V0, webhook_id, self.api_key
-        );
+        let request_url = format!("{}/webhooks/{}", self.api_url_v0, webhook_id);
 
         let response = self
-            .http_client
-            .delete(request_url)
-            .header("accept", "application/json")
-            .header("Content-Type", "application/json")
-            .send()
+            .send(
+                "delete_webhook",
+                self.http_client
+                    .delete(request_url)
+                    .header("accept", "application/json")
+                    .header("Content-Type", "application/json"),
+            )
             .await;
 
         match response {
@@ -149,7 +150,7 @@ impl HeliusClient {
                     ))))
                 }
             }
-            Err(e) => Err(ClientError::from(ClientErrorKind::Reqwest(e))),
+            Err(e) => Err(e),
         }
     }
 }
@@ -0,0 +1,207 @@
+//! The error type of every client method.
+//!
+//! These types are the same with and without the `rpc` feature. With it, they convert to and from the errors of
+//! `solana-client`, so errors of `HeliusClient::rpc_client` can be returned with `?` from functions returning
+//! [`ClientResult`].
+
+use serde::Deserialize;
+use serde_json::Value;
+use std::{error::Error, fmt, io, sync::Arc};
+
+pub type ClientResult<T> = std::result::Result<T, ClientError>;
+
+/// The error object of a JSON-RPC response.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct RpcResponseError {
+    pub code: i64,
+    pub message: String,
+    /// Further details, whose shape depends on `code`. `Null` if there are none.
+    #[serde(default)]
+    pub data: Value,
+}
+
+impl fmt::Display for RpcResponseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "RPC response error {}: {}", self.code, self.message)
+    }
+}
+
+#[derive(Debug)]
+pub enum ClientErrorKind {
+    Io(io::Error),
+    Reqwest(reqwest::Error),
+    SerdeJson(serde_json::Error),
+    /// An RPC request was answered with an error.
+    RpcResponse(RpcResponseError),
+    Custom(String),
+    /// The error of a request shared by concurrent callers, see `HeliusClient::with_coalescing`.
+    /// [`ClientError::kind`] returns the kind of the shared error.
+    Coalesced(Arc<ClientError>),
+}
+
+impl fmt::Display for ClientErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ClientErrorKind::Io(e) => write!(f, "{}", e),
+            ClientErrorKind::Reqwest(e) => write!(f, "{}", e),
+            ClientErrorKind::SerdeJson(e) => write!(f, "{}", e),
+            ClientErrorKind::RpcResponse(e) => write!(f, "{}", e),
+            ClientErrorKind::Custom(message) => write!(f, "Custom: {}", message),
+            ClientErrorKind::Coalesced(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ClientError {
+    pub kind: ClientErrorKind,
+}
+
+impl ClientError {
+    /// Returns the kind of the error, looking through [`ClientErrorKind::Coalesced`] to the shared error.
+    pub fn kind(&self) -> &ClientErrorKind {
+        match &self.kind {
+            ClientErrorKind::Coalesced(e) => e.kind(),
+            kind => kind,
+        }
+    }
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.kind.fmt(f)
+    }
+}
+
+impl Error for ClientError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.kind {
+            ClientErrorKind::Io(e) => Some(e),
+            ClientErrorKind::Reqwest(e) => Some(e),
+            ClientErrorKind::SerdeJson(e) => Some(e),
+            ClientErrorKind::RpcResponse(_) | ClientErrorKind::Custom(_) => None,
+            ClientErrorKind::Coalesced(e) => e.source(),
+        }
+    }
+}
+
+impl From<ClientErrorKind> for ClientError {
+    fn from(kind: ClientErrorKind) -> Self {
+        ClientError { kind }
+    }
+}
+
+impl From<io::Error> for ClientError {
+    fn from(e: io::Error) -> Self {
+        ClientErrorKind::Io(e).into()
+    }
+}
+
+impl From<reqwest::Error> for ClientError {
+    fn from(e: reqwest::Error) -> Self {
+        ClientErrorKind::Reqwest(e).into()
+    }
+}
+
+impl From<serde_json::Error> for ClientError {
+    fn from(e: serde_json::Error) -> Self {
+        ClientErrorKind::SerdeJson(e).into()
+    }
+}
+
+#[cfg(feature = "rpc")]
+mod rpc {
+    use serde_json::{json, Value};
+    use solana_client::{
+        client_error::{
+            ClientError as SolanaClientError, ClientErrorKind as SolanaClientErrorKind,
+        },
+        rpc_custom_error::{
+            NodeUnhealthyErrorData, JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY,
+            JSON_RPC_SERVER_ERROR_SEND_TRANSACTION_PREFLIGHT_FAILURE,
+        },
+        rpc_request::{RpcError, RpcResponseErrorData},
+    };
+
+    use std::sync::Arc;
+
+    use super::{ClientError, ClientErrorKind, RpcResponseError};
+
+    /// Keeps the I/O, HTTP, JSON, RPC response and custom errors as they are. The other kinds of `solana-client`,
+    /// such as transaction and signing errors, become [`ClientErrorKind::Custom`] errors with the same message.
+    impl From<SolanaClientError> for ClientError {
+        fn from(error: SolanaClientError) -> Self {
+            let kind = match error.kind {
+                SolanaClientErrorKind::Io(e) => ClientErrorKind::Io(e),
+                SolanaClientErrorKind::Reqwest(e) => ClientErrorKind::Reqwest(e),
+                SolanaClientErrorKind::SerdeJson(e) => ClientErrorKind::SerdeJson(e),
+                SolanaClientErrorKind::Custom(message) => ClientErrorKind::Custom(message),
+                SolanaClientErrorKind::RpcError(RpcError::RpcResponseError {
+                    code,
+                    message,
+                    data,
+                }) => ClientErrorKind::RpcResponse(RpcResponseError {
+                    code,
+                    message,
+                    data: match data {
+                        RpcResponseErrorData::Empty => Value::Null,
+                        RpcResponseErrorData::SendTransactionPreflightFailure(result) => {
+                            serde_json::to_value(result).unwrap_or_default()
+                        }
+                        RpcResponseErrorData::NodeUnhealthy { num_slots_behind } => {
+                            json!(NodeUnhealthyErrorData { num_slots_behind })
+                        }
+                    },
+                }),
+                kind => ClientErrorKind::Custom(kind.to_string()),
+            };
+            ClientError { kind }
+        }
+    }
+
+    /// The reverse of the conversion above. The data of preflight failures and node health errors is parsed like the
+    /// HTTP sender of `solana-client` does, and dropped if it does not parse. Coalesced errors convert like the error
+    /// they share, or to custom errors while other callers still hold it.
+    impl From<ClientError> for SolanaClientError {
+        fn from(error: ClientError) -> Self {
+            let kind = match error.kind {
+                ClientErrorKind::Io(e) => SolanaClientErrorKind::Io(e),
+                ClientErrorKind::Reqwest(e) => SolanaClientErrorKind::Reqwest(e),
+                ClientErrorKind::SerdeJson(e) => SolanaClientErrorKind::SerdeJson(e),
+                ClientErrorKind::Custom(message) => SolanaClientErrorKind::Custom(message),
+                ClientErrorKind::Coalesced(e) => {
+                    return Arc::try_unwrap(e)
+                        .map(SolanaClientError::from)
+                        .unwrap_or_else(|e| SolanaClientErrorKind::Custom(e.to_string()).into())
+                }
+                ClientErrorKind::RpcResponse(RpcResponseError {
+                    code,
+                    message,
+                    data,
+                }) => {
+                    let data = match code {
+                        JSON_RPC_SERVER_ERROR_SEND_TRANSACTION_PREFLIGHT_FAILURE => {
+                            serde_json::from_value(data)
+                                .map(RpcResponseErrorData::SendTransactionPreflightFailure)
+                                .unwrap_or(RpcResponseErrorData::Empty)
+                        }
+                        JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY => {
+                            serde_json::from_value::<NodeUnhealthyErrorData>(data)
+                                .map(|data| RpcResponseErrorData::NodeUnhealthy {
+                                    num_slots_behind: data.num_slots_behind,
+                                })
+                                .unwrap_or(RpcResponseErrorData::Empty)
+                        }
+                        _ => RpcResponseErrorData::Empty,
+                    };
+                    SolanaClientErrorKind::RpcError(RpcError::RpcResponseError {
+                        code,
+                        message,
+                        data,
+                    })
+                }
+            };
+            SolanaClientError::from(kind)
+        }
+    }
+}
@@ -0,0 +1,371 @@
+//! Flattens enriched transaction history into one [`Movement`] per native transfer, token transfer and balance change,
+//! and writes movements as CSV, NDJSON or, with the `parquet` feature, Parquet.
+//!
+//! Every format has the columns of [`COLUMNS`], in that order:
+//! * `amount` is the raw integer amount, in lamports for SOL. Balance changes are negative for outflows.
+//! * `mint` is empty for SOL.
+//! * `ui_amount` is `amount` scaled by `decimals`, as an exact decimal string.
+//! * `decimals` of token transfers come from the token balance changes of the same transaction, and are empty when it
+//!   has none for the mint.
+
+use futures::{pin_mut, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use solana_program::{clock::UnixTimestamp, slot_history::Slot};
+use std::{collections::HashMap, io::Write};
+
+use crate::error::{ClientError, ClientErrorKind, ClientResult};
+use crate::models::{
+    enriched_transaction::EnrichedTransaction,
+    enums::{TransactionSource, TransactionType},
+};
+use crate::types::NATIVE_DECIMALS;
+
+#[cfg(feature = "parquet")]
+mod parquet;
+
+#[cfg(feature = "parquet")]
+pub use self::parquet::ParquetWriter;
+
+/// The columns of every export format.
+pub const COLUMNS: &[&str] = &[
+    "signature",
+    "slot",
+    "timestamp",
+    "transaction_type",
+    "source",
+    "fee_payer",
+    "kind",
+    "account",
+    "from_account",
+    "to_account",
+    "mint",
+    "amount",
+    "decimals",
+    "ui_amount",
+];
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MovementKind {
+    /// A SOL transfer between `from_account` and `to_account`.
+    NativeTransfer,
+    /// A token transfer between the owners `from_account` and `to_account`.
+    TokenTransfer,
+    /// The change of the SOL balance of `account`, including fees.
+    NativeBalanceChange,
+    /// The change of the token balance of the owner `account`.
+    TokenBalanceChange,
+}
+
+impl MovementKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MovementKind::NativeTransfer => "native_transfer",
+            MovementKind::TokenTransfer => "token_transfer",
+            MovementKind::NativeBalanceChange => "native_balance_change",
+            MovementKind::TokenBalanceChange => "token_balance_change",
+        }
+    }
+}
+
+/// A single movement of SOL or tokens within an [`EnrichedTransaction`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Movement {
+    pub signature: String,
+    pub slot: Slot,
+    pub timestamp: Option<UnixTimestamp>,
+    pub transaction_type: TransactionType,
+    pub source: TransactionSource,
+    pub fee_payer: String,
+    pub kind: MovementKind,
+    /// The account whose balance changed, for balance changes.
+    pub account: Option<String>,
+    /// The sender, for transfers.
+    pub from_account: Option<String>,
+    /// The recipient, for transfers.
+    pub to_account: Option<String>,
+    /// The token mint, or `None` for SOL.
+    pub mint: Option<String>,
+    pub amount: i128,
+    pub decimals: Option<u8>,
+}
+
+impl Movement {
+    /// Returns the amount scaled by its decimals, e.g. `"-1.000005"` for `-1000005000` lamports.
+    pub fn ui_amount(&self) -> Option<String> {
+        let decimals = self.decimals? as usize;
+        let digits = self.amount.unsigned_abs().to_string();
+        let digits = format!("{:0>width$}", digits, width = decimals + 1);
+        let (whole, fraction) = digits.split_at(digits.len() - decimals);
+        let fraction = fraction.trim_end_matches('0');
+        let sign = if self.amount < 0 { "-" } else { "" };
+        Some(if fraction.is_empty() {
+            format!("{}{}", sign, whole)
+        } else {
+            format!("{}{}.{}", sign, whole, fraction)
+        })
+    }
+}
+
+/// Returns the movements of `transaction`: its native transfers, then its token transfers, then the balance changes
+/// of its accounts. Fails if a token balance change has an amount that is not an integer.
+pub fn movements(transaction: &EnrichedTransaction) -> ClientResult<Vec<Movement>> {
+    let decimals: HashMap<&str, u8> = transaction
+        .account_data
+        .iter()
+        .flat_map(|account| &account.token_balance_changes)
+        .map(|change| (change.mint.as_str(), change.raw_token_amount.decimals))
+        .collect();
+    let movement = |kind, amount, mint: Option<&str>, decimals| Movement {
+        signature: transaction.signature.clone(),
+        slot: transaction.slot,
+        timestamp: transaction.timestamp,
+        transaction_type: transaction.transaction_type,
+        source: transaction.source,
+        fee_payer: transaction.fee_payer.clone(),
+        kind,
+        account: None,
+        from_account: None,
+        to_account: None,
+        mint: mint.map(str::to_string),
+        amount,
+        decimals,
+    };
+
+    let native_transfers = transaction
+        .native_transfers
+        .iter()
+        .map(|transfer| Movement {
+            from_account: Some(transfer.from_user_account.clone()),
+            to_account: Some(transfer.to_user_account.clone()),
+            ..movement(
+                MovementKind::NativeTransfer,
+                transfer.amount.into(),
+                None,
+                Some(NATIVE_DECIMALS),
+            )
+        });
+    let token_transfers = transaction.token_transfers.iter().map(|transfer| Movement {
+        from_account: Some(transfer.from_user_account.clone()),
+        to_account: Some(transfer.to_user_account.clone()),
+        ..movement(
+            MovementKind::TokenTransfer,
+            transfer.token_amount.into(),
+            Some(&transfer.mint),
+            decimals.get(transfer.mint.as_str()).copied(),
+        )
+    });
+    let mut balance_changes = Vec::new();
+    for account in &transaction.account_data {
+        if account.native_balance_change != 0 {
+            balance_changes.push(Movement {
+                account: Some(account.account.clone()),
+                ..movement(
+                    MovementKind::NativeBalanceChange,
+                    account.native_balance_change,
+                    None,
+                    Some(NATIVE_DECIMALS),
+                )
+            });
+        }
+        for change in &account.token_balance_changes {
+            let token_amount = &change.raw_token_amount.token_amount;
+            let amount = token_amount.parse().map_err(|e| {
+                ClientError::from(ClientErrorKind::Custom(format!(
+                    "invalid amount {} of {} in transaction {}: {}",
+                    token_amount, change.mint, transaction.signature, e
+                )))
+            })?;
+            balance_changes.push(Movement {
+                account: Some(change.user_account.clone()),
+                ..movement(
+                    MovementKind::TokenBalanceChange,
+                    amount,
+                    Some(&change.mint),
+                    Some(change.raw_token_amount.decimals),
+                )
+            });
+        }
+    }
+
+    Ok(native_transfers
+        .chain(token_transfers)
+        .chain(balance_changes)
+        .collect())
+}
+
+/// A destination for [`Movement`]s in one of the export formats.
+pub trait MovementWriter {
+    fn write_movement(&mut self, movement: &Movement) -> ClientResult<()>;
+
+    /// Flushes buffered rows and completes the output. Nothing may be written afterwards.
+    fn finish(&mut self) -> ClientResult<()>;
+
+    /// Writes the movements of `transaction` and returns how many there were.
+    fn write_transaction(&mut self, transaction: &EnrichedTransaction) -> ClientResult<usize> {
+        let movements = movements(transaction)?;
+        for movement in &movements {
+            self.write_movement(movement)?;
+        }
+        Ok(movements.len())
+    }
+}
+
+/// Writes the movements of every transaction of `transactions` to `writer`, finishes it and returns the number of
+/// movements written. Stops at the first error.
+pub async fn export<S, W>(transactions: S, writer: &mut W) -> ClientResult<usize>
+where
+    S: Stream<Item = ClientResult<EnrichedTransaction>>,
+    W: MovementWriter + ?Sized,
+{
+    pin_mut!(transactions);
+    let mut count = 0;
+    while let Some(transaction) = transactions.next().await {
+        count += writer.write_transaction(&transaction?)?;
+    }
+    writer.finish()?;
+    Ok(count)
+}
+
+/// The fields of a movement in [`COLUMNS`] order, as written to CSV and NDJSON. `amount` is a string so that JSON
+/// readers do not lose precision.
+#[derive(Serialize)]
+struct Row<'a> {
+    signature: &'a str,
+    slot: Slot,
+    timestamp: Option<UnixTimestamp>,
+    transaction_type: TransactionType,
+    source: TransactionSource,
+    fee_payer: &'a str,
+    kind: MovementKind,
+    account: Option<&'a str>,
+    from_account: Option<&'a str>,
+    to_account: Option<&'a str>,
+    mint: Option<&'a str>,
+    amount: String,
+    decimals: Option<u8>,
+    ui_amount: Option<String>,
+}
+
+impl<'a> From<&'a Movement> for Row<'a> {
+    fn from(movement: &'a Movement) -> Self {
+        Row {
+            signature: &movement.signature,
+            slot: movement.slot,
+            timestamp: movement.timestamp,
+            transaction_type: movement.transaction_type,
+            source: movement.source,
+            fee_payer: &movement.fee_payer,
+            kind: movement.kind,
+            account: movement.account.as_deref(),
+            from_account: movement.from_account.as_deref(),
+            to_account: movement.to_account.as_deref(),
+            mint: movement.mint.as_deref(),
+            amount: movement.amount.to_string(),
+            decimals: movement.decimals,
+            ui_amount: movement.ui_amount(),
+        }
+    }
+}
+
+fn io_error(e: std::io::Error) -> ClientError {
+    ClientError::from(ClientErrorKind::Io(e))
+}
+
+/// Writes movements as CSV with a header row.
+pub struct CsvWriter<W: Write> {
+    writer: W,
+    wrote_header: bool,
+}
+
+impl<W: Write> CsvWriter<W> {
+    pub fn new(writer: W) -> Self {
+        CsvWriter {
+            writer,
+            wrote_header: false,
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn write_record<'a>(&mut self, fields: impl IntoIterator<Item = &'a str>) -> ClientResult<()> {
+        let record: Vec<String> = fields.into_iter().map(csv_field).collect();
+        writeln!(self.writer, "{}", record.join(",")).map_err(io_error)
+    }
+
+    fn write_header(&mut self) -> ClientResult<()> {
+        if !self.wrote_header {
+            self.write_record(COLUMNS.iter().copied())?;
+            self.wrote_header = true;
+        }
+        Ok(())
+    }
+}
+
+/// Quotes `field` if it contains a delimiter, quote or line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+impl<W: Write> MovementWriter for CsvWriter<W> {
+    fn write_movement(&mut self, movement: &Movement) -> ClientResult<()> {
+        self.write_header()?;
+        let optional = |value: Option<String>| value.unwrap_or_default();
+        let fields = [
+            movement.signature.clone(),
+            movement.slot.to_string(),
+            optional(movement.timestamp.map(|timestamp| timestamp.to_string())),
+            movement.transaction_type.to_string(),
+            movement.source.to_string(),
+            movement.fee_payer.clone(),
+            movement.kind.as_str().to_string(),
+            optional(movement.account.clone()),
+            optional(movement.from_account.clone()),
+            optional(movement.to_account.clone()),
+            optional(movement.mint.clone()),
+            movement.amount.to_string(),
+            optional(movement.decimals.map(|decimals| decimals.to_string())),
+            optional(movement.ui_amount()),
+        ];
+        self.write_record(fields.iter().map(String::as_str))
+    }
+
+    /// Writes the header if no movement was written, so empty exports still have the schema.
+    fn finish(&mut self) -> ClientResult<()> {
+        self.write_header()?;
+        self.writer.flush().map_err(io_error)
+    }
+}
+
+/// Writes movements as newline-delimited JSON objects.
+pub struct NdjsonWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> NdjsonWriter<W> {
+    pub fn new(writer: W) -> Self {
+        NdjsonWriter { writer }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> MovementWriter for NdjsonWriter<W> {
+    fn write_movement(&mut self, movement: &Movement) -> ClientResult<()> {
+        serde_json::to_writer(&mut self.writer, &Row::from(movement))
+            .map_err(|e| ClientError::from(ClientErrorKind::SerdeJson(e)))?;
+        self.writer.write_all(b"\n").map_err(io_error)
+    }
+
+    fn finish(&mut self) -> ClientResult<()> {
+        self.writer.flush().map_err(io_error)
+    }
+}
@@ -0,0 +1,136 @@
+use arrow_array::{
+    builder::{Decimal128Builder, Int64Builder, StringBuilder, UInt64Builder, UInt8Builder},
+    ArrayRef, RecordBatch,
+};
+use arrow_schema::{DataType, Field, Schema, SchemaRef};
+use parquet::arrow::ArrowWriter;
+use std::{io::Write, sync::Arc};
+
+use crate::error::{ClientError, ClientErrorKind, ClientResult};
+
+use super::{Movement, MovementWriter, COLUMNS};
+
+/// The number of movements buffered into each record batch.
+const BATCH_SIZE: usize = 8192;
+
+/// Writes movements as a Parquet file. `amount` is a `Decimal128(38, 0)`, the other integers are `UInt64`, `Int64`
+/// and `UInt8`, and everything else is UTF-8.
+pub struct ParquetWriter<W: Write + Send> {
+    writer: Option<ArrowWriter<W>>,
+    schema: SchemaRef,
+    rows: Vec<Movement>,
+}
+
+impl<W: Write + Send> ParquetWriter<W> {
+    pub fn new(writer: W) -> ClientResult<Self> {
+        let schema = Arc::new(schema());
+        let writer = ArrowWriter::try_new(writer, schema.clone(), None).map_err(custom_error)?;
+        Ok(ParquetWriter {
+            writer: Some(writer),
+            schema,
+            rows: Vec::with_capacity(BATCH_SIZE),
+        })
+    }
+
+    fn writer(&mut self) -> ClientResult<&mut ArrowWriter<W>> {
+        self.writer.as_mut().ok_or_else(|| {
+            ClientError::from(ClientErrorKind::Custom(
+                "parquet writer is finished".to_string(),
+            ))
+        })
+    }
+
+    fn flush_rows(&mut self) -> ClientResult<()> {
+        if self.rows.is_empty() {
+            return Ok(());
+        }
+        let batch = record_batch(self.schema.clone(), &self.rows)?;
+        self.rows.clear();
+        self.writer()?.write(&batch).map_err(custom_error)
+    }
+}
+
+impl<W: Write + Send> MovementWriter for ParquetWriter<W> {
+    fn write_movement(&mut self, movement: &Movement) -> ClientResult<()> {
+        self.writer()?;
+        self.rows.push(movement.clone());
+        if self.rows.len() >= BATCH_SIZE {
+            self.flush_rows()?;
+        }
+        Ok(())
+    }
+
+    /// Writes the remaining rows and the file footer.
+    fn finish(&mut self) -> ClientResult<()> {
+        self.flush_rows()?;
+        self.writer()?;
+        if let Some(writer) = self.writer.take() {
+            writer.close().map_err(custom_error)?;
+        }
+        Ok(())
+    }
+}
+
+fn custom_error(e: impl ToString) -> ClientError {
+    ClientError::from(ClientErrorKind::Custom(e.to_string()))
+}
+
+fn schema() -> Schema {
+    let fields = COLUMNS.iter().map(|&name| {
+        let (data_type, nullable) = match name {
+            "slot" => (DataType::UInt64, false),
+            "timestamp" => (DataType::Int64, true),
+            "amount" => (DataType::Decimal128(38, 0), false),
+            "decimals" => (DataType::UInt8, true),
+            "signature" | "transaction_type" | "source" | "fee_payer" | "kind" => {
+                (DataType::Utf8, false)
+            }
+            _ => (DataType::Utf8, true),
+        };
+        Field::new(name, data_type, nullable)
+    });
+    Schema::new(fields.collect::<Vec<_>>())
+}
+
+fn record_batch(schema: SchemaRef, rows: &[Movement]) -> ClientResult<RecordBatch> {
+    let strings = |value: &dyn Fn(&Movement) -> Option<String>| -> ArrayRef {
+        let mut builder = StringBuilder::new();
+        for row in rows {
+            builder.append_option(value(row));
+        }
+        Arc::new(builder.finish())
+    };
+
+    let mut slots = UInt64Builder::with_capacity(rows.len());
+    let mut timestamps = Int64Builder::with_capacity(rows.len());
+    let mut amounts = Decimal128Builder::with_capacity(rows.len());
+    let mut decimals = UInt8Builder::with_capacity(rows.len());
+    for row in rows {
+        slots.append_value(row.slot);
+        timestamps.append_option(row.timestamp);
+        amounts.append_value(row.amount);
+        decimals.append_option(row.decimals);
+    }
+    let amounts = amounts
+        .finish()
+        .with_precision_and_scale(38, 0)
+        .map_err(custom_error)?;
+
+    let columns: Vec<ArrayRef> = vec![
+        strings(&|row| Some(row.signature.clone())),
+        Arc::new(slots.finish()),
+        Arc::new(timestamps.finish()),
+        strings(&|row| Some(row.transaction_type.to_string())),
+        strings(&|row| Some(row.source.to_string())),
+        strings(&|row| Some(row.fee_payer.clone())),
+        strings(&|row| Some(row.kind.as_str().to_string())),
+        strings(&|row| row.account.clone()),
+        strings(&|row| row.from_account.clone()),
+        strings(&|row| row.to_account.clone()),
+        strings(&|row| row.mint.clone()),
+        Arc::new(amounts),
+        Arc::new(decimals.finish()),
+        strings(&|row| row.ui_amount()),
+    ];
+    RecordBatch::try_new(schema, columns).map_err(custom_error)
+}
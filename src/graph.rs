@@ -0,0 +1,406 @@
+//! Builds a directed multigraph of the SOL and token flows between accounts, for counterparty analysis.
+//!
+//! A [`FlowGraph`] has an edge for every native transfer and token transfer of the transactions added to it, from the
+//! sending to the receiving user account, with the mint and raw amount moved. Edges are kept individually, so two
+//! accounts can be connected by many edges; [`FlowGraph::flows`] sums them by direction and mint.
+//!
+//! An [`Explorer`] grows a graph breadth first from seed addresses: it fetches the history of every seed, then of
+//! their counterparties, and so on for a number of hops. Graphs are exported as GraphML, DOT or JSON.
+
+use serde::{Deserialize, Serialize};
+use solana_program::{clock::UnixTimestamp, pubkey::Pubkey, slot_history::Slot};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet, VecDeque},
+    io::Write,
+    str::FromStr,
+    sync::Arc,
+};
+
+use crate::client::api::HeliusApi;
+use crate::error::{ClientError, ClientErrorKind, ClientResult};
+use crate::models::enriched_transaction::{
+    parse_signature, EnrichedTransaction, RequestConfig, MAX_TRANSACTIONS_PER_PAGE,
+};
+use crate::types::{CommitmentLevel, Signature};
+
+/// The number of history pages fetched per account by default. Busy accounts such as exchanges and programs would
+/// otherwise take over an exploration.
+pub const DEFAULT_PAGES_PER_ACCOUNT: usize = 1;
+
+/// The number of accounts whose history an exploration fetches by default.
+pub const DEFAULT_MAX_ACCOUNTS: usize = 100;
+
+/// A transfer of SOL or of a token between two user accounts.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Edge {
+    pub from: String,
+    pub to: String,
+    /// The mint, or `None` for SOL.
+    pub mint: Option<String>,
+    /// The raw amount: lamports for SOL and base units for tokens.
+    pub amount: u64,
+    pub signature: String,
+    pub slot: Slot,
+    pub timestamp: Option<UnixTimestamp>,
+}
+
+/// The sum of the edges from one account to another in one mint.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Flow {
+    pub from: String,
+    pub to: String,
+    pub mint: Option<String>,
+    pub amount: u128,
+    pub transfers: usize,
+}
+
+/// The transfers between accounts of a set of transactions. See the [module documentation](self).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FlowGraph {
+    edges: Vec<Edge>,
+    signatures: HashSet<String>,
+}
+
+#[derive(Serialize)]
+struct JsonGraph<'a> {
+    nodes: Vec<&'a str>,
+    edges: &'a [Edge],
+}
+
+impl FlowGraph {
+    pub fn new() -> Self {
+        FlowGraph::default()
+    }
+
+    /// Adds the [`transfers`] of `transaction` as edges. Returns `false` if the transaction was added before.
+    pub fn add_transaction(&mut self, transaction: &EnrichedTransaction) -> bool {
+        if !self.signatures.insert(transaction.signature.clone()) {
+            return false;
+        }
+        self.edges.extend(transfers(transaction));
+        true
+    }
+
+    /// Adds every transaction of `transactions` and returns the number of new ones.
+    pub fn add_transactions<'a, I>(&mut self, transactions: I) -> usize
+    where
+        I: IntoIterator<Item = &'a EnrichedTransaction>,
+    {
+        transactions
+            .into_iter()
+            .filter(|transaction| self.add_transaction(transaction))
+            .count()
+    }
+
+    /// Every edge, in the order the transactions were added.
+    pub fn edges(&self) -> &[Edge] {
+        &self.edges
+    }
+
+    /// Every account with an edge, in order.
+    pub fn nodes(&self) -> Vec<&str> {
+        self.edges
+            .iter()
+            .flat_map(|edge| [edge.from.as_str(), edge.to.as_str()])
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    pub fn outgoing<'a>(&'a self, account: &'a str) -> impl Iterator<Item = &'a Edge> {
+        self.edges.iter().filter(move |edge| edge.from == account)
+    }
+
+    pub fn incoming<'a>(&'a self, account: &'a str) -> impl Iterator<Item = &'a Edge> {
+        self.edges.iter().filter(move |edge| edge.to == account)
+    }
+
+    /// The accounts that sent to or received from `account`, in order.
+    pub fn counterparties(&self, account: &str) -> Vec<&str> {
+        self.edges
+            .iter()
+            .filter_map(|edge| {
+                if edge.from == account && edge.to != account {
+                    Some(edge.to.as_str())
+                } else if edge.to == account && edge.from != account {
+                    Some(edge.from.as_str())
+                } else {
+                    None
+                }
+            })
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// The edges summed by sender, receiver and mint, in that order.
+    pub fn flows(&self) -> Vec<Flow> {
+        let mut flows: BTreeMap<(&str, &str, Option<&str>), (u128, usize)> = BTreeMap::new();
+        for edge in &self.edges {
+            let flow = flows
+                .entry((&edge.from, &edge.to, edge.mint.as_deref()))
+                .or_default();
+            flow.0 += edge.amount as u128;
+            flow.1 += 1;
+        }
+        flows
+            .into_iter()
+            .map(|((from, to, mint), (amount, transfers))| Flow {
+                from: from.to_string(),
+                to: to.to_string(),
+                mint: mint.map(str::to_string),
+                amount,
+                transfers,
+            })
+            .collect()
+    }
+
+    /// Writes the graph as GraphML, with the mint, amount, signature and slot of each edge as data.
+    pub fn write_graphml<W: Write>(&self, mut writer: W) -> ClientResult<()> {
+        let mut graphml = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+            "  <key id=\"mint\" for=\"edge\" attr.name=\"mint\" attr.type=\"string\"/>\n",
+            "  <key id=\"amount\" for=\"edge\" attr.name=\"amount\" attr.type=\"long\"/>\n",
+            "  <key id=\"signature\" for=\"edge\" attr.name=\"signature\" attr.type=\"string\"/>\n",
+            "  <key id=\"slot\" for=\"edge\" attr.name=\"slot\" attr.type=\"long\"/>\n",
+            "  <graph id=\"flows\" edgedefault=\"directed\">\n",
+        ));
+        for node in self.nodes() {
+            graphml.push_str(&format!("    <node id=\"{}\"/>\n", xml_escape(node)));
+        }
+        for (index, edge) in self.edges.iter().enumerate() {
+            graphml.push_str(&format!(
+                concat!(
+                    "    <edge id=\"e{}\" source=\"{}\" target=\"{}\">\n",
+                    "      <data key=\"mint\">{}</data>\n",
+                    "      <data key=\"amount\">{}</data>\n",
+                    "      <data key=\"signature\">{}</data>\n",
+                    "      <data key=\"slot\">{}</data>\n",
+                    "    </edge>\n",
+                ),
+                index,
+                xml_escape(&edge.from),
+                xml_escape(&edge.to),
+                xml_escape(mint_name(edge)),
+                edge.amount,
+                xml_escape(&edge.signature),
+                edge.slot,
+            ));
+        }
+        graphml.push_str("  </graph>\n</graphml>\n");
+        writer.write_all(graphml.as_bytes()).map_err(io_error)
+    }
+
+    /// Writes the graph in the DOT language of Graphviz, with edges labeled by amount and mint.
+    pub fn write_dot<W: Write>(&self, mut writer: W) -> ClientResult<()> {
+        let mut dot = String::from("digraph flows {\n");
+        for node in self.nodes() {
+            dot.push_str(&format!("  {};\n", dot_id(node)));
+        }
+        for edge in &self.edges {
+            dot.push_str(&format!(
+                "  {} -> {} [label={}, signature={}, slot={}];\n",
+                dot_id(&edge.from),
+                dot_id(&edge.to),
+                dot_id(&format!("{} {}", edge.amount, mint_name(edge))),
+                dot_id(&edge.signature),
+                edge.slot,
+            ));
+        }
+        dot.push_str("}\n");
+        writer.write_all(dot.as_bytes()).map_err(io_error)
+    }
+
+    /// Writes the graph as a JSON object with the `nodes` and the `edges`.
+    pub fn write_json<W: Write>(&self, writer: W) -> ClientResult<()> {
+        let graph = JsonGraph {
+            nodes: self.nodes(),
+            edges: &self.edges,
+        };
+        serde_json::to_writer(writer, &graph)
+            .map_err(|e| ClientError::from(ClientErrorKind::SerdeJson(e)))
+    }
+}
+
+/// A graph grown by an [`Explorer`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Exploration {
+    pub graph: FlowGraph,
+    /// The accounts whose history was fetched, with their distance in hops from the nearest seed.
+    pub hops: BTreeMap<String, usize>,
+    /// Whether accounts within reach were left unexplored because of the account limit.
+    pub truncated: bool,
+}
+
+/// Grows a [`FlowGraph`] breadth first from seed addresses. See the [module documentation](self).
+pub struct Explorer {
+    api: Arc<dyn HeliusApi>,
+    hops: usize,
+    page_size: usize,
+    pages_per_account: usize,
+    max_accounts: usize,
+    commitment: Option<CommitmentLevel>,
+}
+
+impl Explorer {
+    pub fn new(api: Arc<dyn HeliusApi>) -> Self {
+        Explorer {
+            api,
+            hops: 1,
+            page_size: MAX_TRANSACTIONS_PER_PAGE,
+            pages_per_account: DEFAULT_PAGES_PER_ACCOUNT,
+            max_accounts: DEFAULT_MAX_ACCOUNTS,
+            commitment: None,
+        }
+    }
+
+    /// Fetches the history of the accounts up to `hops` transfers away from a seed. With 0 hops, only the histories of
+    /// the seeds are fetched. Defaults to 1.
+    pub fn with_hops(mut self, hops: usize) -> Self {
+        self.hops = hops;
+        self
+    }
+
+    pub fn with_page_size(mut self, page_size: usize) -> Self {
+        self.page_size = page_size.max(1);
+        self
+    }
+
+    /// Fetches at most `pages` pages of the newest transactions of each account. Defaults to
+    /// [`DEFAULT_PAGES_PER_ACCOUNT`].
+    pub fn with_pages_per_account(mut self, pages: usize) -> Self {
+        self.pages_per_account = pages.max(1);
+        self
+    }
+
+    /// Fetches the history of at most `max_accounts` accounts, seeds included. Defaults to [`DEFAULT_MAX_ACCOUNTS`].
+    pub fn with_max_accounts(mut self, max_accounts: usize) -> Self {
+        self.max_accounts = max_accounts;
+        self
+    }
+
+    /// Fetches transactions at `commitment` instead of the API default, finalized.
+    pub fn with_commitment(mut self, commitment: CommitmentLevel) -> Self {
+        self.commitment = Some(commitment);
+        self
+    }
+
+    /// Fetches the history of `seeds`, then of their counterparties hop by hop, and returns the graph of the fetched
+    /// transactions. Counterparties that are not valid addresses are not explored. Stops at the first failed request.
+    pub async fn explore(&self, seeds: Vec<Pubkey>) -> ClientResult<Exploration> {
+        let mut exploration = Exploration::default();
+        let mut queue: VecDeque<(Pubkey, usize)> = VecDeque::new();
+        for seed in seeds {
+            if exploration.hops.contains_key(&seed.to_string()) {
+                continue;
+            }
+            if exploration.hops.len() >= self.max_accounts {
+                exploration.truncated = true;
+                break;
+            }
+            exploration.hops.insert(seed.to_string(), 0);
+            queue.push_back((seed, 0));
+        }
+
+        while let Some((address, hop)) = queue.pop_front() {
+            let key = address.to_string();
+            for transaction in self.fetch_history(&address).await? {
+                exploration.graph.add_transaction(&transaction);
+            }
+            if hop == self.hops {
+                continue;
+            }
+            for counterparty in exploration.graph.counterparties(&key) {
+                if exploration.hops.contains_key(counterparty) {
+                    continue;
+                }
+                let Ok(pubkey) = Pubkey::from_str(counterparty) else {
+                    continue;
+                };
+                if exploration.hops.len() >= self.max_accounts {
+                    exploration.truncated = true;
+                    break;
+                }
+                exploration.hops.insert(counterparty.to_string(), hop + 1);
+                queue.push_back((pubkey, hop + 1));
+            }
+        }
+        Ok(exploration)
+    }
+
+    async fn fetch_history(&self, address: &Pubkey) -> ClientResult<Vec<EnrichedTransaction>> {
+        let mut history = Vec::new();
+        let mut before: Option<Signature> = None;
+        for _ in 0..self.pages_per_account {
+            let config = RequestConfig {
+                address: *address,
+                before,
+                limit: Some(self.page_size),
+                commitment: self.commitment,
+                ..Default::default()
+            };
+            let page = self.api.get_enriched_transactions(config).await?;
+            let Some(last) = page.last() else {
+                break;
+            };
+            before = Some(parse_signature(&last.signature)?);
+            let full = page.len() >= self.page_size;
+            history.extend(page);
+            if !full {
+                break;
+            }
+        }
+        Ok(history)
+    }
+}
+
+/// The native and token transfers of `transaction`, native ones first. Transfers without a sender or receiver, such as
+/// mints and burns, are left out.
+pub fn transfers(transaction: &EnrichedTransaction) -> Vec<Edge> {
+    let edge = |from: &str, to: &str, mint: Option<&str>, amount: u64| Edge {
+        from: from.to_string(),
+        to: to.to_string(),
+        mint: mint.map(str::to_string),
+        amount,
+        signature: transaction.signature.clone(),
+        slot: transaction.slot,
+        timestamp: transaction.timestamp,
+    };
+    let native = transaction
+        .native_transfers
+        .iter()
+        .map(|t| edge(&t.from_user_account, &t.to_user_account, None, t.amount));
+    let tokens = transaction.token_transfers.iter().map(|t| {
+        edge(
+            &t.from_user_account,
+            &t.to_user_account,
+            Some(&t.mint),
+            t.token_amount,
+        )
+    });
+    native
+        .chain(tokens)
+        .filter(|edge| !edge.from.is_empty() && !edge.to.is_empty())
+        .collect()
+}
+
+fn io_error(e: std::io::Error) -> ClientError {
+    ClientError::from(ClientErrorKind::Io(e))
+}
+
+fn mint_name(edge: &Edge) -> &str {
+    edge.mint.as_deref().unwrap_or("SOL")
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn dot_id(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
@@ -0,0 +1,498 @@
+//! Indexes the enriched transaction history of a set of addresses into an [`IndexStore`].
+//!
+//! The [`Indexer`] backfills each address from its newest transaction back to its first one, then tails new activity
+//! by polling with `until`, or by [`Indexer::ingest`]ing the transactions posted to an enhanced webhook. Transactions
+//! are split into the rows of [`SCHEMA`] and upserted by signature, so indexing a transaction twice is harmless.
+//!
+//! Progress is kept in a [`Cursor`] per address, saved after every backfilled page and after every poll, so an indexer
+//! that is restarted picks up where it stopped.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use solana_program::{clock::UnixTimestamp, pubkey::Pubkey, slot_history::Slot};
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use crate::client::api::HeliusApi;
+use crate::error::{ClientError, ClientErrorKind, ClientResult};
+use crate::models::{
+    enriched_transaction::{
+        parse_signature, EnrichedTransaction, RequestConfig, MAX_TRANSACTIONS_PER_PAGE,
+    },
+    enums::{NftEventType, TransactionSource, TransactionType},
+};
+use crate::types::CommitmentLevel;
+
+#[cfg(feature = "postgres")]
+pub mod postgres;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+/// The SQL schema of the indexed rows and cursors, for SQLite and Postgres.
+pub const SCHEMA: &str = include_str!("indexer/schema.sql");
+
+/// The indexing progress of an address. Signatures are those of the newest and oldest indexed transactions.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cursor {
+    /// Polling fetches the transactions after this one.
+    pub newest: Option<String>,
+    /// An interrupted backfill resumes before this one.
+    pub oldest: Option<String>,
+    /// Whether the backfill reached the first transaction of the address.
+    pub backfilled: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransactionRow {
+    pub signature: String,
+    pub slot: Slot,
+    pub timestamp: Option<UnixTimestamp>,
+    pub transaction_type: TransactionType,
+    pub source: TransactionSource,
+    pub fee: u64,
+    pub fee_payer: String,
+    pub description: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct NativeTransferRow {
+    pub position: usize,
+    pub from_account: String,
+    pub to_account: String,
+    pub amount: u64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TokenTransferRow {
+    pub position: usize,
+    pub from_account: String,
+    pub to_account: String,
+    pub from_token_account: String,
+    pub to_token_account: String,
+    pub mint: String,
+    pub amount: u64,
+}
+
+/// A change of the SOL balance of `account`, or of its token balance of `mint`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BalanceChangeRow {
+    pub position: usize,
+    pub account: String,
+    pub token_account: Option<String>,
+    pub mint: Option<String>,
+    pub amount: i128,
+    pub decimals: Option<u8>,
+}
+
+/// An NFT of an NFT event.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NftEventRow {
+    pub position: usize,
+    pub event_type: NftEventType,
+    pub mint: String,
+    pub buyer: String,
+    pub seller: String,
+    pub amount: i128,
+}
+
+/// The rows of a transaction in every table of [`SCHEMA`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct IndexedTransaction {
+    pub transaction: TransactionRow,
+    /// The indexed addresses the transaction was found for.
+    pub addresses: Vec<String>,
+    pub native_transfers: Vec<NativeTransferRow>,
+    pub token_transfers: Vec<TokenTransferRow>,
+    pub balance_changes: Vec<BalanceChangeRow>,
+    pub nft_events: Vec<NftEventRow>,
+}
+
+impl IndexedTransaction {
+    /// Splits `transaction` into its rows. Fails if a token balance change has an amount that is not an integer.
+    pub fn new(transaction: &EnrichedTransaction, addresses: Vec<String>) -> ClientResult<Self> {
+        let native_transfers = transaction
+            .native_transfers
+            .iter()
+            .enumerate()
+            .map(|(position, transfer)| NativeTransferRow {
+                position,
+                from_account: transfer.from_user_account.clone(),
+                to_account: transfer.to_user_account.clone(),
+                amount: transfer.amount,
+            })
+            .collect();
+        let token_transfers = transaction
+            .token_transfers
+            .iter()
+            .enumerate()
+            .map(|(position, transfer)| TokenTransferRow {
+                position,
+                from_account: transfer.from_user_account.clone(),
+                to_account: transfer.to_user_account.clone(),
+                from_token_account: transfer.from_token_account.clone(),
+                to_token_account: transfer.to_token_account.clone(),
+                mint: transfer.mint.clone(),
+                amount: transfer.token_amount,
+            })
+            .collect();
+
+        let mut balance_changes = Vec::new();
+        for account in &transaction.account_data {
+            if account.native_balance_change != 0 {
+                balance_changes.push(BalanceChangeRow {
+                    position: balance_changes.len(),
+                    account: account.account.clone(),
+                    token_account: None,
+                    mint: None,
+                    amount: account.native_balance_change,
+                    decimals: None,
+                });
+            }
+            for change in &account.token_balance_changes {
+                let token_amount = &change.raw_token_amount.token_amount;
+                let amount = token_amount.parse().map_err(|e| {
+                    ClientError::from(ClientErrorKind::Custom(format!(
+                        "invalid amount {} of {} in transaction {}: {}",
+                        token_amount, change.mint, transaction.signature, e
+                    )))
+                })?;
+                balance_changes.push(BalanceChangeRow {
+                    position: balance_changes.len(),
+                    account: change.user_account.clone(),
+                    token_account: Some(change.token_account.clone()),
+                    mint: Some(change.mint.clone()),
+                    amount,
+                    decimals: Some(change.raw_token_amount.decimals),
+                });
+            }
+        }
+
+        let nft_events = transaction
+            .events
+            .nft
+            .iter()
+            .flat_map(|event| event.nfts.iter().map(move |nft| (event, nft)))
+            .enumerate()
+            .map(|(position, (event, nft))| NftEventRow {
+                position,
+                event_type: event.event_type,
+                mint: nft.mint.clone(),
+                buyer: event.buyer.clone(),
+                seller: event.seller.clone(),
+                amount: event.amount,
+            })
+            .collect();
+
+        Ok(IndexedTransaction {
+            transaction: TransactionRow {
+                signature: transaction.signature.clone(),
+                slot: transaction.slot,
+                timestamp: transaction.timestamp,
+                transaction_type: transaction.transaction_type,
+                source: transaction.source,
+                fee: transaction.fee,
+                fee_payer: transaction.fee_payer.clone(),
+                description: transaction.description.clone(),
+                error: transaction
+                    .transaction_error
+                    .as_ref()
+                    .map(|error| error.error.clone()),
+            },
+            addresses,
+            native_transfers,
+            token_transfers,
+            balance_changes,
+            nft_events,
+        })
+    }
+
+    pub fn signature(&self) -> &str {
+        &self.transaction.signature
+    }
+}
+
+/// Persists indexed transactions and cursors. The `sqlite` and `postgres` features add stores with the tables of
+/// [`SCHEMA`]; implement this to index into another database.
+#[async_trait]
+pub trait IndexStore: Send + Sync {
+    /// Stores `transaction`, replacing the rows of an earlier transaction with the same signature. The addresses of
+    /// both are kept.
+    async fn upsert(&self, transaction: &IndexedTransaction) -> ClientResult<()>;
+
+    async fn cursor(&self, address: &str) -> ClientResult<Option<Cursor>>;
+
+    async fn save_cursor(&self, address: &str, cursor: &Cursor) -> ClientResult<()>;
+}
+
+/// An [`IndexStore`] in memory, for tests and short-lived indexers.
+#[derive(Default)]
+pub struct InMemoryIndexStore {
+    transactions: Mutex<HashMap<String, IndexedTransaction>>,
+    cursors: Mutex<HashMap<String, Cursor>>,
+}
+
+impl InMemoryIndexStore {
+    pub fn new() -> Self {
+        InMemoryIndexStore::default()
+    }
+
+    pub fn transaction(&self, signature: &str) -> Option<IndexedTransaction> {
+        self.transactions.lock().unwrap().get(signature).cloned()
+    }
+
+    /// Returns the transactions indexed for `address`, newest first.
+    pub fn transactions_for(&self, address: &str) -> Vec<IndexedTransaction> {
+        let mut transactions: Vec<IndexedTransaction> = self
+            .transactions
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|transaction| transaction.addresses.iter().any(|a| a == address))
+            .cloned()
+            .collect();
+        transactions.sort_by_key(|transaction| std::cmp::Reverse(transaction.transaction.slot));
+        transactions
+    }
+
+    pub fn len(&self) -> usize {
+        self.transactions.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[async_trait]
+impl IndexStore for InMemoryIndexStore {
+    async fn upsert(&self, transaction: &IndexedTransaction) -> ClientResult<()> {
+        let mut transactions = self.transactions.lock().unwrap();
+        let mut transaction = transaction.clone();
+        if let Some(existing) = transactions.get(transaction.signature()) {
+            let addresses: BTreeSet<String> = existing
+                .addresses
+                .iter()
+                .chain(&transaction.addresses)
+                .cloned()
+                .collect();
+            transaction.addresses = addresses.into_iter().collect();
+        }
+        transactions.insert(transaction.signature().to_string(), transaction);
+        Ok(())
+    }
+
+    async fn cursor(&self, address: &str) -> ClientResult<Option<Cursor>> {
+        Ok(self.cursors.lock().unwrap().get(address).cloned())
+    }
+
+    async fn save_cursor(&self, address: &str, cursor: &Cursor) -> ClientResult<()> {
+        self.cursors
+            .lock()
+            .unwrap()
+            .insert(address.to_string(), cursor.clone());
+        Ok(())
+    }
+}
+
+/// Indexes the history of `addresses` into a store. See the [module documentation](self).
+pub struct Indexer {
+    api: Arc<dyn HeliusApi>,
+    store: Arc<dyn IndexStore>,
+    addresses: Vec<Pubkey>,
+    page_size: usize,
+    commitment: Option<CommitmentLevel>,
+}
+
+impl Indexer {
+    pub fn new(
+        api: Arc<dyn HeliusApi>,
+        store: Arc<dyn IndexStore>,
+        addresses: Vec<Pubkey>,
+    ) -> Self {
+        Indexer {
+            api,
+            store,
+            addresses,
+            page_size: MAX_TRANSACTIONS_PER_PAGE,
+            commitment: None,
+        }
+    }
+
+    pub fn with_page_size(mut self, page_size: usize) -> Self {
+        self.page_size = page_size.max(1);
+        self
+    }
+
+    /// Indexes transactions at `commitment` instead of the API default, finalized.
+    pub fn with_commitment(mut self, commitment: CommitmentLevel) -> Self {
+        self.commitment = Some(commitment);
+        self
+    }
+
+    pub fn addresses(&self) -> &[Pubkey] {
+        &self.addresses
+    }
+
+    /// Indexes the history of every address that is not backfilled yet, resuming interrupted backfills. Returns the
+    /// number of transactions indexed.
+    pub async fn backfill(&self) -> ClientResult<usize> {
+        let mut count = 0;
+        for address in &self.addresses {
+            count += self.backfill_address(address).await?;
+        }
+        Ok(count)
+    }
+
+    /// Indexes the transactions of every address since the last backfill or poll. Addresses that were never indexed
+    /// are backfilled. Returns the number of transactions indexed.
+    pub async fn poll(&self) -> ClientResult<usize> {
+        let mut count = 0;
+        for address in &self.addresses {
+            count += self.poll_address(address).await?;
+        }
+        Ok(count)
+    }
+
+    /// Backfills, then polls every `interval` until a request or the store fails.
+    pub async fn tail(&self, interval: Duration) -> ClientResult<()> {
+        self.backfill().await?;
+        loop {
+            tokio::time::sleep(interval).await;
+            self.poll().await?;
+        }
+    }
+
+    /// Indexes transactions received outside of polling, e.g. the payload of an enhanced webhook, for the indexed
+    /// addresses they involve. Cursors are left alone, so polling still fills any gap. Returns the number of
+    /// transactions indexed.
+    pub async fn ingest(&self, transactions: &[EnrichedTransaction]) -> ClientResult<usize> {
+        let mut count = 0;
+        for transaction in transactions {
+            let addresses: Vec<String> = self
+                .addresses
+                .iter()
+                .map(Pubkey::to_string)
+                .filter(|address| involves(transaction, address))
+                .collect();
+            if !addresses.is_empty() {
+                self.store
+                    .upsert(&IndexedTransaction::new(transaction, addresses)?)
+                    .await?;
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    async fn backfill_address(&self, address: &Pubkey) -> ClientResult<usize> {
+        let key = address.to_string();
+        let mut cursor = self.store.cursor(&key).await?.unwrap_or_default();
+        let mut count = 0;
+        while !cursor.backfilled {
+            let page = self
+                .fetch_page(address, cursor.oldest.as_deref(), None)
+                .await?;
+            count += self.store_page(&key, &page).await?;
+            if let (None, Some(first)) = (&cursor.newest, page.first()) {
+                cursor.newest = Some(first.signature.clone());
+            }
+            if let Some(last) = page.last() {
+                cursor.oldest = Some(last.signature.clone());
+            }
+            cursor.backfilled = page.len() < self.page_size;
+            self.store.save_cursor(&key, &cursor).await?;
+        }
+        Ok(count)
+    }
+
+    /// Pages from the newest transaction back to the cursor, and only then moves the cursor, so an interrupted poll is
+    /// repeated rather than leaving a gap. An address whose backfill found no transactions has no cursor signature, so
+    /// its whole history is paged.
+    async fn poll_address(&self, address: &Pubkey) -> ClientResult<usize> {
+        let key = address.to_string();
+        let mut cursor = match self.store.cursor(&key).await? {
+            Some(cursor) if cursor.backfilled => cursor,
+            _ => return self.backfill_address(address).await,
+        };
+        let mut count = 0;
+        let mut newest = None;
+        let mut before = None;
+        loop {
+            let page = self
+                .fetch_page(address, before.as_deref(), cursor.newest.as_deref())
+                .await?;
+            let page: Vec<EnrichedTransaction> = page
+                .into_iter()
+                .filter(|transaction| Some(&transaction.signature) != cursor.newest.as_ref())
+                .collect();
+            count += self.store_page(&key, &page).await?;
+            if newest.is_none() {
+                newest = page
+                    .first()
+                    .map(|transaction| transaction.signature.clone());
+            }
+            match page.last() {
+                Some(last) if page.len() >= self.page_size => before = Some(last.signature.clone()),
+                _ => break,
+            }
+        }
+        if newest.is_some() {
+            cursor.newest = newest;
+            self.store.save_cursor(&key, &cursor).await?;
+        }
+        Ok(count)
+    }
+
+    async fn fetch_page(
+        &self,
+        address: &Pubkey,
+        before: Option<&str>,
+        until: Option<&str>,
+    ) -> ClientResult<Vec<EnrichedTransaction>> {
+        let config = RequestConfig {
+            address: *address,
+            before: before.map(parse_signature).transpose()?,
+            until: until.map(parse_signature).transpose()?,
+            limit: Some(self.page_size),
+            commitment: self.commitment,
+            ..Default::default()
+        };
+        self.api.get_enriched_transactions(config).await
+    }
+
+    async fn store_page(&self, address: &str, page: &[EnrichedTransaction]) -> ClientResult<usize> {
+        for transaction in page {
+            self.store
+                .upsert(&IndexedTransaction::new(
+                    transaction,
+                    vec![address.to_string()],
+                )?)
+                .await?;
+        }
+        Ok(page.len())
+    }
+}
+
+/// Whether `address` sends or receives SOL or tokens in `transaction`, pays its fee or has a balance change.
+fn involves(transaction: &EnrichedTransaction, address: &str) -> bool {
+    transaction.fee_payer == address
+        || transaction
+            .native_transfers
+            .iter()
+            .any(|t| t.from_user_account == address || t.to_user_account == address)
+        || transaction
+            .token_transfers
+            .iter()
+            .any(|t| t.from_user_account == address || t.to_user_account == address)
+        || transaction.account_data.iter().any(|account| {
+            account.account == address
+                || account
+                    .token_balance_changes
+                    .iter()
+                    .any(|change| change.user_account == address)
+        })
+}
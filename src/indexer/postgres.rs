@@ -0,0 +1,194 @@
+//! An [`IndexStore`] in a Postgres database with the tables of [`SCHEMA`]. Enabled with the `postgres` feature.
+
+use async_trait::async_trait;
+use tokio::sync::Mutex;
+use tokio_postgres::{Client, NoTls};
+
+use super::{Cursor, IndexStore, IndexedTransaction, SCHEMA};
+use crate::error::{ClientError, ClientErrorKind, ClientResult};
+
+/// An [`IndexStore`] in a Postgres database. The tables of [`SCHEMA`] are created when the store is connected.
+pub struct PostgresIndexStore {
+    /// Locked for the duration of an upsert, which runs in a database transaction.
+    client: Mutex<Client>,
+}
+
+impl PostgresIndexStore {
+    /// Connects without TLS to the database described by `config`, e.g. `host=localhost user=postgres dbname=helius`
+    /// or `postgresql://postgres@localhost/helius`. Must be called from within a tokio runtime, which drives the
+    /// connection.
+    pub async fn connect(config: &str) -> ClientResult<Self> {
+        let (client, connection) = tokio_postgres::connect(config, NoTls)
+            .await
+            .map_err(postgres_error)?;
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                tracing::error!(error = %e, "Postgres connection of the index store failed");
+            }
+        });
+        PostgresIndexStore::new(client).await
+    }
+
+    /// Uses an already connected `client`, e.g. one connected with TLS.
+    pub async fn new(client: Client) -> ClientResult<Self> {
+        client.batch_execute(SCHEMA).await.map_err(postgres_error)?;
+        Ok(PostgresIndexStore {
+            client: Mutex::new(client),
+        })
+    }
+}
+
+#[async_trait]
+impl IndexStore for PostgresIndexStore {
+    async fn upsert(&self, transaction: &IndexedTransaction) -> ClientResult<()> {
+        let mut client = self.client.lock().await;
+        let tx = client.transaction().await.map_err(postgres_error)?;
+        let row = &transaction.transaction;
+        tx.execute(
+            "INSERT INTO transactions
+                (signature, slot, timestamp, transaction_type, source, fee, fee_payer, description, error)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+             ON CONFLICT (signature) DO UPDATE SET
+                slot = excluded.slot, timestamp = excluded.timestamp,
+                transaction_type = excluded.transaction_type, source = excluded.source, fee = excluded.fee,
+                fee_payer = excluded.fee_payer, description = excluded.description, error = excluded.error",
+            &[
+                &row.signature,
+                &(row.slot as i64),
+                &row.timestamp,
+                &row.transaction_type.to_string(),
+                &row.source.to_string(),
+                &(row.fee as i64),
+                &row.fee_payer,
+                &row.description,
+                &row.error,
+            ],
+        )
+        .await
+        .map_err(postgres_error)?;
+        for table in ["native_transfers", "token_transfers", "balance_changes", "nft_events"] {
+            tx.execute(
+                &format!("DELETE FROM {} WHERE signature = $1", table),
+                &[&row.signature],
+            )
+            .await
+            .map_err(postgres_error)?;
+        }
+
+        for address in &transaction.addresses {
+            tx.execute(
+                "INSERT INTO address_transactions (address, signature) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+                &[address, &row.signature],
+            )
+            .await
+            .map_err(postgres_error)?;
+        }
+        // Amounts are sent as text, since `tokio-postgres` has no Rust type for NUMERIC.
+        for transfer in &transaction.native_transfers {
+            tx.execute(
+                "INSERT INTO native_transfers (signature, position, from_account, to_account, amount)
+                 VALUES ($1, $2, $3, $4, $5::TEXT::NUMERIC)",
+                &[
+                    &row.signature,
+                    &(transfer.position as i32),
+                    &transfer.from_account,
+                    &transfer.to_account,
+                    &transfer.amount.to_string(),
+                ],
+            )
+            .await
+            .map_err(postgres_error)?;
+        }
+        for transfer in &transaction.token_transfers {
+            tx.execute(
+                "INSERT INTO token_transfers (signature, position, from_account, to_account, from_token_account,
+                    to_token_account, mint, amount)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8::TEXT::NUMERIC)",
+                &[
+                    &row.signature,
+                    &(transfer.position as i32),
+                    &transfer.from_account,
+                    &transfer.to_account,
+                    &transfer.from_token_account,
+                    &transfer.to_token_account,
+                    &transfer.mint,
+                    &transfer.amount.to_string(),
+                ],
+            )
+            .await
+            .map_err(postgres_error)?;
+        }
+        for change in &transaction.balance_changes {
+            tx.execute(
+                "INSERT INTO balance_changes (signature, position, account, token_account, mint, amount, decimals)
+                 VALUES ($1, $2, $3, $4, $5, $6::TEXT::NUMERIC, $7)",
+                &[
+                    &row.signature,
+                    &(change.position as i32),
+                    &change.account,
+                    &change.token_account,
+                    &change.mint,
+                    &change.amount.to_string(),
+                    &change.decimals.map(i16::from),
+                ],
+            )
+            .await
+            .map_err(postgres_error)?;
+        }
+        for event in &transaction.nft_events {
+            tx.execute(
+                "INSERT INTO nft_events (signature, position, event_type, mint, buyer, seller, amount)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7::TEXT::NUMERIC)",
+                &[
+                    &row.signature,
+                    &(event.position as i32),
+                    &event.event_type.to_string(),
+                    &event.mint,
+                    &event.buyer,
+                    &event.seller,
+                    &event.amount.to_string(),
+                ],
+            )
+            .await
+            .map_err(postgres_error)?;
+        }
+        tx.commit().await.map_err(postgres_error)
+    }
+
+    async fn cursor(&self, address: &str) -> ClientResult<Option<Cursor>> {
+        let row = self
+            .client
+            .lock()
+            .await
+            .query_opt(
+                "SELECT newest, oldest, backfilled FROM cursors WHERE address = $1",
+                &[&address],
+            )
+            .await
+            .map_err(postgres_error)?;
+        Ok(row.map(|row| Cursor {
+            newest: row.get(0),
+            oldest: row.get(1),
+            backfilled: row.get(2),
+        }))
+    }
+
+    async fn save_cursor(&self, address: &str, cursor: &Cursor) -> ClientResult<()> {
+        self.client
+            .lock()
+            .await
+            .execute(
+                "INSERT INTO cursors (address, newest, oldest, backfilled) VALUES ($1, $2, $3, $4)
+                 ON CONFLICT (address) DO UPDATE SET
+                    newest = excluded.newest, oldest = excluded.oldest, backfilled = excluded.backfilled",
+                &[&address, &cursor.newest, &cursor.oldest, &cursor.backfilled],
+            )
+            .await
+            .map_err(postgres_error)?;
+        Ok(())
+    }
+}
+
+fn postgres_error(error: tokio_postgres::Error) -> ClientError {
+    ClientError::from(ClientErrorKind::Custom(format!("Postgres error: {}", error)))
+}
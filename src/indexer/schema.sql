@@ -0,0 +1,77 @@
+-- The relational schema of the indexer, valid for both SQLite and Postgres. Rows of a transaction are keyed by its
+-- signature: a store upserts `transactions` and replaces the rows of the other tables with the same signature.
+
+CREATE TABLE IF NOT EXISTS transactions (
+    signature TEXT PRIMARY KEY,
+    slot BIGINT NOT NULL,
+    timestamp BIGINT,
+    transaction_type TEXT NOT NULL,
+    source TEXT NOT NULL,
+    fee BIGINT NOT NULL,
+    fee_payer TEXT NOT NULL,
+    description TEXT,
+    error TEXT
+);
+
+CREATE INDEX IF NOT EXISTS transactions_slot ON transactions (slot);
+
+-- The indexed addresses each transaction was found for.
+CREATE TABLE IF NOT EXISTS address_transactions (
+    address TEXT NOT NULL,
+    signature TEXT NOT NULL REFERENCES transactions (signature) ON DELETE CASCADE,
+    PRIMARY KEY (address, signature)
+);
+
+CREATE TABLE IF NOT EXISTS native_transfers (
+    signature TEXT NOT NULL REFERENCES transactions (signature) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    from_account TEXT NOT NULL,
+    to_account TEXT NOT NULL,
+    amount NUMERIC(20, 0) NOT NULL,
+    PRIMARY KEY (signature, position)
+);
+
+CREATE TABLE IF NOT EXISTS token_transfers (
+    signature TEXT NOT NULL REFERENCES transactions (signature) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    from_account TEXT NOT NULL,
+    to_account TEXT NOT NULL,
+    from_token_account TEXT NOT NULL,
+    to_token_account TEXT NOT NULL,
+    mint TEXT NOT NULL,
+    amount NUMERIC(20, 0) NOT NULL,
+    PRIMARY KEY (signature, position)
+);
+
+-- SOL balance changes have no token account, mint or decimals.
+CREATE TABLE IF NOT EXISTS balance_changes (
+    signature TEXT NOT NULL REFERENCES transactions (signature) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    account TEXT NOT NULL,
+    token_account TEXT,
+    mint TEXT,
+    amount NUMERIC(39, 0) NOT NULL,
+    decimals SMALLINT,
+    PRIMARY KEY (signature, position)
+);
+
+CREATE INDEX IF NOT EXISTS balance_changes_account ON balance_changes (account, mint);
+
+-- One row per NFT of an NFT event.
+CREATE TABLE IF NOT EXISTS nft_events (
+    signature TEXT NOT NULL REFERENCES transactions (signature) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    event_type TEXT NOT NULL,
+    mint TEXT NOT NULL,
+    buyer TEXT NOT NULL,
+    seller TEXT NOT NULL,
+    amount NUMERIC(39, 0) NOT NULL,
+    PRIMARY KEY (signature, position)
+);
+
+CREATE TABLE IF NOT EXISTS cursors (
+    address TEXT PRIMARY KEY,
+    newest TEXT,
+    oldest TEXT,
+    backfilled BOOLEAN NOT NULL
+);
@@ -0,0 +1,180 @@
+//! An [`IndexStore`] in a SQLite database with the tables of [`SCHEMA`]. Enabled with the `sqlite` feature.
+//!
+//! Amounts are written to `NUMERIC` columns as decimal text, which SQLite stores as integers. Amounts beyond the 64-bit
+//! integer range, which only token amounts reach, are stored as floating point numbers.
+
+use async_trait::async_trait;
+use rusqlite::{params, OptionalExtension};
+use std::path::Path;
+
+use super::{Cursor, IndexStore, IndexedTransaction, SCHEMA};
+use crate::error::ClientResult;
+use crate::sqlite::SqliteConnection;
+
+/// An [`IndexStore`] in a SQLite database. The tables of [`SCHEMA`] are created when the store is opened.
+#[derive(Clone)]
+pub struct SqliteIndexStore {
+    connection: SqliteConnection,
+}
+
+impl SqliteIndexStore {
+    /// Opens (or creates) the database at `path`.
+    pub fn open<P: AsRef<Path>>(path: P) -> ClientResult<Self> {
+        Ok(SqliteIndexStore {
+            connection: SqliteConnection::open(path, SCHEMA)?,
+        })
+    }
+
+    /// Opens a database in memory, which is lost when the store is dropped.
+    pub fn open_in_memory() -> ClientResult<Self> {
+        Ok(SqliteIndexStore {
+            connection: SqliteConnection::open_in_memory(SCHEMA)?,
+        })
+    }
+}
+
+#[async_trait]
+impl IndexStore for SqliteIndexStore {
+    async fn upsert(&self, transaction: &IndexedTransaction) -> ClientResult<()> {
+        let transaction = transaction.clone();
+        self.connection
+            .call(move |connection| {
+                let tx = connection.transaction()?;
+                let row = &transaction.transaction;
+                tx.execute(
+                    "INSERT INTO transactions
+                        (signature, slot, timestamp, transaction_type, source, fee, fee_payer, description, error)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+                     ON CONFLICT (signature) DO UPDATE SET
+                        slot = excluded.slot, timestamp = excluded.timestamp,
+                        transaction_type = excluded.transaction_type, source = excluded.source, fee = excluded.fee,
+                        fee_payer = excluded.fee_payer, description = excluded.description, error = excluded.error",
+                    params![
+                        row.signature,
+                        row.slot as i64,
+                        row.timestamp,
+                        row.transaction_type.to_string(),
+                        row.source.to_string(),
+                        row.fee as i64,
+                        row.fee_payer,
+                        row.description,
+                        row.error,
+                    ],
+                )?;
+                for table in ["native_transfers", "token_transfers", "balance_changes", "nft_events"] {
+                    tx.execute(
+                        &format!("DELETE FROM {} WHERE signature = ?1", table),
+                        params![row.signature],
+                    )?;
+                }
+
+                for address in &transaction.addresses {
+                    tx.execute(
+                        "INSERT INTO address_transactions (address, signature) VALUES (?1, ?2)
+                         ON CONFLICT DO NOTHING",
+                        params![address, row.signature],
+                    )?;
+                }
+                for transfer in &transaction.native_transfers {
+                    tx.execute(
+                        "INSERT INTO native_transfers (signature, position, from_account, to_account, amount)
+                         VALUES (?1, ?2, ?3, ?4, ?5)",
+                        params![
+                            row.signature,
+                            transfer.position as i64,
+                            transfer.from_account,
+                            transfer.to_account,
+                            transfer.amount.to_string(),
+                        ],
+                    )?;
+                }
+                for transfer in &transaction.token_transfers {
+                    tx.execute(
+                        "INSERT INTO token_transfers (signature, position, from_account, to_account,
+                            from_token_account, to_token_account, mint, amount)
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                        params![
+                            row.signature,
+                            transfer.position as i64,
+                            transfer.from_account,
+                            transfer.to_account,
+                            transfer.from_token_account,
+                            transfer.to_token_account,
+                            transfer.mint,
+                            transfer.amount.to_string(),
+                        ],
+                    )?;
+                }
+                for change in &transaction.balance_changes {
+                    tx.execute(
+                        "INSERT INTO balance_changes (signature, position, account, token_account, mint, amount,
+                            decimals)
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                        params![
+                            row.signature,
+                            change.position as i64,
+                            change.account,
+                            change.token_account,
+                            change.mint,
+                            change.amount.to_string(),
+                            change.decimals,
+                        ],
+                    )?;
+                }
+                for event in &transaction.nft_events {
+                    tx.execute(
+                        "INSERT INTO nft_events (signature, position, event_type, mint, buyer, seller, amount)
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                        params![
+                            row.signature,
+                            event.position as i64,
+                            event.event_type.to_string(),
+                            event.mint,
+                            event.buyer,
+                            event.seller,
+                            event.amount.to_string(),
+                        ],
+                    )?;
+                }
+                tx.commit()
+            })
+            .await
+    }
+
+    async fn cursor(&self, address: &str) -> ClientResult<Option<Cursor>> {
+        let address = address.to_string();
+        self.connection
+            .call(move |connection| {
+                connection
+                    .query_row(
+                        "SELECT newest, oldest, backfilled FROM cursors WHERE address = ?1",
+                        params![address],
+                        |row| {
+                            Ok(Cursor {
+                                newest: row.get(0)?,
+                                oldest: row.get(1)?,
+                                backfilled: row.get(2)?,
+                            })
+                        },
+                    )
+                    .optional()
+            })
+            .await
+    }
+
+    async fn save_cursor(&self, address: &str, cursor: &Cursor) -> ClientResult<()> {
+        let address = address.to_string();
+        let cursor = cursor.clone();
+        self.connection
+            .call(move |connection| {
+                connection.execute(
+                    "INSERT INTO cursors (address, newest, oldest, backfilled) VALUES (?1, ?2, ?3, ?4)
+                     ON CONFLICT (address) DO UPDATE SET
+                        newest = excluded.newest, oldest = excluded.oldest, backfilled = excluded.backfilled",
+                    params![address, cursor.newest, cursor.oldest, cursor.backfilled],
+                )?;
+                Ok(())
+            })
+            .await
+    }
+}
@@ -0,0 +1,236 @@
+//! Names and categories for well-known addresses, and the classification of transfers by counterparty.
+//!
+//! A [`LabelRegistry`] maps addresses such as program IDs, marketplace escrow accounts and exchange hot wallets to a
+//! [`Label`]. [`LabelRegistry::bundled`] has a small set of well-known addresses; registries are loaded from JSON, an
+//! array of `{"address", "name", "category"}` objects, and [`merge`](LabelRegistry::merge)d so that your own labels
+//! override the bundled ones.
+//!
+//! A [`TransferClassifier`] annotates each transfer of an enriched transaction with a [`TransferClass`], from the
+//! label of its counterparty: the side of the transfer that is not one of your own addresses.
+
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
+
+use crate::error::{ClientError, ClientErrorKind, ClientResult};
+use crate::graph::{transfers, Edge};
+use crate::models::enriched_transaction::EnrichedTransaction;
+
+/// The registry of [`LabelRegistry::bundled`].
+pub const BUNDLED_REGISTRY: &str = include_str!("labels/registry.json");
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LabelCategory {
+    Program,
+    /// An NFT marketplace program or one of its escrow accounts.
+    Marketplace,
+    /// A deposit or hot wallet of a centralized exchange.
+    Exchange,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Label {
+    pub address: String,
+    pub name: String,
+    pub category: LabelCategory,
+}
+
+/// Labels by address. See the [module documentation](self).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LabelRegistry {
+    labels: HashMap<String, Label>,
+}
+
+impl LabelRegistry {
+    pub fn new() -> Self {
+        LabelRegistry::default()
+    }
+
+    /// The labels of [`BUNDLED_REGISTRY`].
+    pub fn bundled() -> Self {
+        LabelRegistry::from_json(BUNDLED_REGISTRY).expect("the bundled label registry is valid")
+    }
+
+    /// Parses a JSON array of labels. An address listed twice keeps its last label.
+    pub fn from_json(json: &str) -> ClientResult<Self> {
+        let labels: Vec<Label> = serde_json::from_str(json)
+            .map_err(|e| ClientError::from(ClientErrorKind::SerdeJson(e)))?;
+        let mut registry = LabelRegistry::new();
+        for label in labels {
+            registry.insert(label);
+        }
+        Ok(registry)
+    }
+
+    /// Reads a JSON array of labels from the file at `path`.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> ClientResult<Self> {
+        let json =
+            std::fs::read_to_string(path).map_err(|e| ClientError::from(ClientErrorKind::Io(e)))?;
+        LabelRegistry::from_json(&json)
+    }
+
+    /// Adds `label`, replacing any label of the same address.
+    pub fn insert(&mut self, label: Label) {
+        self.labels.insert(label.address.clone(), label);
+    }
+
+    /// Adds the labels of `other`, which replace those of the same addresses.
+    pub fn merge(&mut self, other: LabelRegistry) {
+        self.labels.extend(other.labels);
+    }
+
+    pub fn get(&self, address: &str) -> Option<&Label> {
+        self.labels.get(address)
+    }
+
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    /// Every label, ordered by address.
+    pub fn labels(&self) -> Vec<&Label> {
+        let mut labels: Vec<&Label> = self.labels.values().collect();
+        labels.sort_by(|a, b| a.address.cmp(&b.address));
+        labels
+    }
+}
+
+/// What the counterparty of a transfer is.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransferClass {
+    /// Between two of your own addresses.
+    Internal,
+    Exchange,
+    Marketplace,
+    /// A program, or an account labeled as one.
+    Program,
+    Unknown,
+}
+
+impl From<LabelCategory> for TransferClass {
+    fn from(category: LabelCategory) -> Self {
+        match category {
+            LabelCategory::Program => TransferClass::Program,
+            LabelCategory::Marketplace => TransferClass::Marketplace,
+            LabelCategory::Exchange => TransferClass::Exchange,
+        }
+    }
+}
+
+/// A transfer and what its counterparty is.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClassifiedTransfer {
+    pub transfer: Edge,
+    pub class: TransferClass,
+    /// The side of the transfer that is not one of your own addresses, or `None` for internal transfers.
+    pub counterparty: Option<String>,
+    /// The label of the counterparty.
+    pub label: Option<Label>,
+}
+
+/// Classifies transfers by the label of their counterparty. See the [module documentation](self).
+#[derive(Clone, Debug)]
+pub struct TransferClassifier {
+    registry: LabelRegistry,
+    own: HashSet<String>,
+}
+
+impl TransferClassifier {
+    pub fn new(registry: LabelRegistry) -> Self {
+        TransferClassifier {
+            registry,
+            own: HashSet::new(),
+        }
+    }
+
+    /// Treats transfers between `addresses` as internal, and the other side of their transfers as the counterparty.
+    pub fn with_own_addresses<I, S>(mut self, addresses: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.own.extend(addresses.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn registry(&self) -> &LabelRegistry {
+        &self.registry
+    }
+
+    /// Classifies every native and token transfer of `transaction`. An unlabeled counterparty that is a program
+    /// invoked by the transaction is classified as a program.
+    pub fn classify(&self, transaction: &EnrichedTransaction) -> Vec<ClassifiedTransfer> {
+        let programs: HashSet<&str> = transaction
+            .instructions
+            .iter()
+            .flat_map(|instruction| {
+                std::iter::once(instruction.program_id.as_str()).chain(
+                    instruction
+                        .inner_instructions
+                        .iter()
+                        .map(|inner| inner.program_id.as_str()),
+                )
+            })
+            .collect();
+        transfers(transaction)
+            .into_iter()
+            .map(|transfer| {
+                let mut classified = self.classify_transfer(transfer);
+                if classified.class == TransferClass::Unknown
+                    && classified
+                        .counterparty
+                        .as_deref()
+                        .is_some_and(|counterparty| programs.contains(counterparty))
+                {
+                    classified.class = TransferClass::Program;
+                }
+                classified
+            })
+            .collect()
+    }
+
+    /// Classifies `transfer` from the labels of the registry alone. When neither side is one of your own addresses,
+    /// the counterparty is the labeled side, the receiver first.
+    pub fn classify_transfer(&self, transfer: Edge) -> ClassifiedTransfer {
+        let (from_own, to_own) = (
+            self.own.contains(&transfer.from),
+            self.own.contains(&transfer.to),
+        );
+        let counterparty = match (from_own, to_own) {
+            (true, true) => {
+                return ClassifiedTransfer {
+                    transfer,
+                    class: TransferClass::Internal,
+                    counterparty: None,
+                    label: None,
+                }
+            }
+            (true, false) => &transfer.to,
+            (false, true) => &transfer.from,
+            (false, false)
+                if self.registry.get(&transfer.to).is_none()
+                    && self.registry.get(&transfer.from).is_some() =>
+            {
+                &transfer.from
+            }
+            (false, false) => &transfer.to,
+        };
+        let label = self.registry.get(counterparty).cloned();
+        ClassifiedTransfer {
+            class: label
+                .as_ref()
+                .map_or(TransferClass::Unknown, |label| label.category.into()),
+            counterparty: Some(counterparty.clone()),
+            label,
+            transfer,
+        }
+    }
+}
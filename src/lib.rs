@@ -1,2 +1,16 @@
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod accounting;
+pub mod backfill;
+pub mod balances;
 pub mod client;
+pub mod error;
+pub mod export;
+pub mod graph;
+pub mod indexer;
+pub mod labels;
 pub mod models;
+pub mod reconcile;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+pub mod types;
@@ -1,3 +1,4 @@
+pub mod das;
 pub mod enriched_transaction;
 pub mod enums;
 pub mod nft;
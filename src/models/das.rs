@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+
+/// A page of the assets of an owner, as returned by the DAS `getAssetsByOwner` method with fungible tokens and the
+/// native balance shown. Only the fields needed to value a wallet are kept.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetsByOwner {
+    pub total: u32,
+    pub limit: u32,
+    pub page: u32,
+    pub items: Vec<Asset>,
+    pub native_balance: Option<NativeBalance>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct Asset {
+    /// The mint of the asset.
+    pub id: String,
+    /// E.g. `FungibleToken`, `FungibleAsset` or `V1_NFT`.
+    pub interface: String,
+    /// Only set for fungible assets.
+    pub token_info: Option<TokenInfo>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct TokenInfo {
+    pub symbol: Option<String>,
+    pub balance: Option<u64>,
+    pub decimals: Option<u8>,
+    /// Only set for tokens Helius has a price for.
+    pub price_info: Option<PriceInfo>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct PriceInfo {
+    /// The price of one whole token in `currency`.
+    pub price_per_token: f64,
+    /// The value of the balance of the owner in `currency`.
+    pub total_price: Option<f64>,
+    /// E.g. `USDC`.
+    pub currency: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct NativeBalance {
+    pub lamports: u64,
+    /// The price of one SOL in USD.
+    pub price_per_sol: Option<f64>,
+    pub total_price: Option<f64>,
+}
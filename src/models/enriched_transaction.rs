@@ -1,15 +1,27 @@
-use serde::{Deserialize};
-use solana_client::client_error::{ClientError, ClientErrorKind, Result as ClientResult};
+use serde::{Deserialize, Serialize};
 use solana_program::{clock::UnixTimestamp, pubkey::Pubkey, slot_history::Slot};
-use solana_sdk::{
-    commitment_config::CommitmentLevel, signature::Signature,
-};
+
+use crate::error::{ClientError, ClientErrorKind, ClientResult};
+use crate::types::{CommitmentLevel, Signature};
 
 use super::{
     enums::{TransactionSource, TransactionType},
     nft::{CompressedNftEvent, NftEvent},
 };
 
+/// The page size of transaction history requests, the maximum [`RequestConfig::limit`] the API accepts.
+pub const MAX_TRANSACTIONS_PER_PAGE: usize = 100;
+
+/// Parses a signature returned by the API, e.g. to page back from it with [`RequestConfig::before`].
+pub(crate) fn parse_signature(signature: &str) -> ClientResult<Signature> {
+    signature.parse().map_err(|e| {
+        ClientError::from(ClientErrorKind::Custom(format!(
+            "invalid signature {}: {}",
+            signature, e
+        )))
+    })
+}
+
 #[derive(Debug, Default)]
 pub struct RequestConfig {
     pub address: Pubkey,
@@ -21,14 +33,8 @@ pub struct RequestConfig {
     pub commitment: Option<CommitmentLevel>,
 }
 impl RequestConfig {
-    pub fn generate_query_parameters(
-        &self,
-        api_key: String,
-    ) -> ClientResult<Vec<(String, String)>> {
-        let mut query_params = vec![
-            ("address".to_string(), self.address.to_string()),
-            ("api-key".to_string(), api_key),
-        ];
+    pub fn generate_query_parameters(&self) -> ClientResult<Vec<(String, String)>> {
+        let mut query_params = vec![("address".to_string(), self.address.to_string())];
         if self.before.is_some() {
             query_params.push(("before".to_string(), self.before.unwrap().to_string()));
         }
@@ -67,7 +73,7 @@ impl RequestConfig {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct EnrichedTransaction {
     pub description: Option<String>,
@@ -87,7 +93,7 @@ pub struct EnrichedTransaction {
     pub events: EnrichedEvents,
 }
 
-#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct EnrichedEvents {
     pub nft: Option<NftEvent>,
@@ -95,7 +101,7 @@ pub struct EnrichedEvents {
     pub compressed: Option<CompressedNftEvent>,
 }
 
-#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct NativeTransfer {
     pub from_user_account: String,
@@ -103,7 +109,7 @@ pub struct NativeTransfer {
     pub amount: u64,
 }
 
-#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TokenTransfer {
     pub from_user_account: String,
@@ -114,7 +120,7 @@ pub struct TokenTransfer {
     pub mint: String,
 }
 
-#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct EnrichedAccountData {
     pub account: String,
@@ -122,7 +128,7 @@ pub struct EnrichedAccountData {
     pub token_balance_changes: Vec<EnrichedTokenBalanceChange>,
 }
 
-#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct EnrichedInstruction {
     pub accounts: Vec<String>,
@@ -131,7 +137,7 @@ pub struct EnrichedInstruction {
     pub inner_instructions: Vec<EnrichedInnerInstruction>,
 }
 
-#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct EnrichedInnerInstruction {
     pub accounts: Vec<String>,
@@ -139,7 +145,7 @@ pub struct EnrichedInnerInstruction {
     pub program_id: String,
 }
 
-#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct EnrichedTokenBalanceChange {
     pub user_account: String,
@@ -148,20 +154,20 @@ pub struct EnrichedTokenBalanceChange {
     pub raw_token_amount: RawTokenAmount,
 }
 
-#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RawTokenAmount {
     pub token_amount: String,
     pub decimals: u8,
 }
 
-#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct EnrichedError {
     pub error: String,
 }
 
-#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SwapEvent {
     pub error: String,
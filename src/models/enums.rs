@@ -109,7 +109,7 @@ impl fmt::Display for TransactionType {
     }
 }
 
-#[derive(Debug, Deserialize, Copy, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Copy, Clone, PartialEq)]
 #[allow(non_camel_case_types)]
 pub enum TransactionSource {
     FORM_FUNCTION,
@@ -204,7 +204,7 @@ impl fmt::Display for TransactionSource {
     }
 }
 
-#[derive(Debug, Deserialize, Copy, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Copy, Clone, PartialEq)]
 #[allow(non_camel_case_types)]
 pub enum NftEventType {
     NFT_BID,
@@ -233,7 +233,13 @@ pub enum NftEventType {
     SFT_MINT,
 }
 
-#[derive(Debug, Deserialize, Copy, Clone, PartialEq)]
+impl fmt::Display for NftEventType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Copy, Clone, PartialEq)]
 pub enum TokenStandard {
     ProgrammableNonFungible,
     NonFungible,
@@ -243,7 +249,7 @@ pub enum TokenStandard {
     UnknownStandard,
 }
 
-#[derive(Debug, Deserialize, Copy, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Copy, Clone, PartialEq)]
 #[allow(non_camel_case_types)]
 pub enum CompressedNftEventType {
     COMPRESSED_NFT_MINT,
@@ -255,7 +261,7 @@ pub enum CompressedNftEventType {
     COMPRESSED_NFT_DELEGATE,
 }
 
-#[derive(Debug, Deserialize, Copy, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Copy, Clone, PartialEq)]
 #[allow(non_camel_case_types)]
 pub enum SaleType {
     AUCTION,
@@ -1,14 +1,15 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 
-use solana_client::client_error::{ClientError, ClientErrorKind, Result as ClientResult};
+
+use crate::error::{ClientError, ClientErrorKind, ClientResult};
 
 use super::{
     enriched_transaction::{NativeTransfer, TokenTransfer},
     enums::{CompressedNftEventType, NftEventType, SaleType, TokenStandard, TransactionSource},
 };
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ActiveListing {
     pub transaction_signature: String,
@@ -17,7 +18,7 @@ pub struct ActiveListing {
     pub seller: String,
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct NftMetadata {
     pub mint: String,
@@ -28,7 +29,7 @@ pub struct NftMetadata {
     pub active_listings: Vec<ActiveListing>,
 }
 
-#[derive(Deserialize, Debug, PartialEq, Clone)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct CompressedNftEvent {
     event_type: CompressedNftEventType,
@@ -39,7 +40,7 @@ pub struct CompressedNftEvent {
     inner_instruction_index: u64,
 }
 
-#[derive(Deserialize, Debug, PartialEq, Clone)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct NftEvent {
     pub amount: i128, // Sometimes has negatives, although it should be lamports?
@@ -56,7 +57,7 @@ pub struct NftEvent {
     pub nfts: Vec<NftToken>,
 }
 
-#[derive(Deserialize, Debug, PartialEq, Clone)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct NftEventV2 {
     #[serde(rename = "type")]
@@ -78,42 +79,42 @@ pub struct NftEventV2 {
     pub pagination_token: Option<String>,
 }
 
-#[derive(Deserialize, Debug, PartialEq, Clone)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct NftToken {
     pub mint: String,
     pub token_standard: TokenStandard,
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct MintListResult {
     pub mint: String,
     pub name: String,
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct TokenBalancesResponse {
     pub native_balance: u64,
     pub tokens: Vec<TokenBalance>,
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct MintListResponse {
     pub result: Vec<MintListResult>,
     pub pagination_token: String,
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct NftResponse {
     pub number_of_pages: usize,
-    pub nfts: NftInfo,
+    pub nfts: Vec<NftInfo>,
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename = "nft")]
 #[serde(rename_all = "camelCase")]
 pub struct NftInfo {
@@ -125,14 +126,14 @@ pub struct NftInfo {
     pub traits: Vec<Trait>,
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Trait {
     pub trait_type: String,
     pub value: String,
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct TokenBalance {
     pub token_account: String,
@@ -179,14 +180,14 @@ impl MintListRequestConfig {
     }
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ActiveListingsResponse {
     pub result: Vec<ListingResult>,
     pub pagination_token: String,
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ListingResult {
     pub mint: String,
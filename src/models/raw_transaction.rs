@@ -1,18 +1,20 @@
-use serde::{Deserialize};
+use serde::{Deserialize, Serialize};
 use serde_json::{Value};
-use solana_client::client_error::{Result as ClientResult};
 use solana_program::{clock::UnixTimestamp, pubkey::Pubkey, slot_history::Slot};
-use solana_sdk::{
-    commitment_config::CommitmentLevel, signature::Signature, transaction::TransactionError,
-};
+#[cfg(feature = "rpc")]
+use solana_sdk::transaction::TransactionError;
+#[cfg(feature = "rpc")]
 use solana_transaction_status::{
     option_serializer::OptionSerializer, Rewards, UiInnerInstructions, UiLoadedAddresses,
     UiTransactionReturnData, UiTransactionTokenBalance,
 };
 
+use crate::error::ClientResult;
+use crate::types::{CommitmentLevel, Signature};
+
 use super::enriched_transaction::RequestConfig;
 
-#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RawTransaction {
     pub slot: Slot,
@@ -23,14 +25,14 @@ pub struct RawTransaction {
     pub index_within_block: Option<u64>,
 }
 
-#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct InnerTransaction {
     pub signatures: Vec<String>,
     pub message: InnerTransactionMessage,
 }
 
-#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct InnerTransactionMessage {
     pub account_keys: Vec<String>,
@@ -39,7 +41,7 @@ pub struct InnerTransactionMessage {
     pub instructions: Vec<InnerInstruction>,
 }
 
-#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct InnerInstruction {
     pub program_id_index: usize,
@@ -56,10 +58,7 @@ pub struct GetRawTransactionsRequestConfig {
     pub commitment: Option<CommitmentLevel>,
 }
 impl GetRawTransactionsRequestConfig {
-    pub fn generate_query_parameters(
-        &self,
-        api_key: String,
-    ) -> ClientResult<Vec<(String, String)>> {
+    pub fn generate_query_parameters(&self) -> ClientResult<Vec<(String, String)>> {
         let config = RequestConfig {
             address: self.address,
             before: self.before,
@@ -69,12 +68,13 @@ impl GetRawTransactionsRequestConfig {
             transaction_type: None,
             commitment: self.commitment,
         };
-        config.generate_query_parameters(api_key)
+        config.generate_query_parameters()
     }
 }
 
 /// A duplicate representation of TransactionStatusMeta with `err` field. Copied from solana-transactions-status crate, but without the status field.
-#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[cfg(feature = "rpc")]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UiTransactionStatusMeta {
     pub err: Option<TransactionError>,
@@ -122,3 +122,22 @@ pub struct UiTransactionStatusMeta {
     )]
     pub compute_units_consumed: OptionSerializer<u64>,
 }
+
+/// The status metadata of a transaction without the `rpc` feature. `err` and the fields not listed here are kept as
+/// JSON; enable `rpc` for the typed representation of `solana-transaction-status`.
+#[cfg(not(feature = "rpc"))]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UiTransactionStatusMeta {
+    pub err: Option<Value>,
+    pub fee: u64,
+    pub pre_balances: Vec<u64>,
+    pub post_balances: Vec<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_messages: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compute_units_consumed: Option<u64>,
+    /// Inner instructions, token balances, rewards, loaded addresses and return data, by field name.
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, Value>,
+}
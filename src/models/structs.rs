@@ -1,6 +1,6 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct TokenMetadata {
     pub mint: String,
@@ -8,7 +8,7 @@ pub struct TokenMetadata {
     pub off_chain_data: Option<OffChainMetadata>,
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct OnChainMetadata {
     pub key: String,
@@ -24,7 +24,7 @@ pub struct OnChainMetadata {
     pub uses: Uses,
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename = "data")]
 #[serde(rename_all = "camelCase")]
 pub struct OnChainData {
@@ -35,7 +35,7 @@ pub struct OnChainData {
     pub creators: Option<Vec<Creator>>,
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Creator {
     pub address: String,
@@ -43,20 +43,20 @@ pub struct Creator {
     pub verified: Option<bool>,
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Collection {
     pub key: String,
     pub verified: bool,
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct CollectionDetails {
     pub size: i32,
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Uses {
     pub use_method: String,
@@ -64,7 +64,7 @@ pub struct Uses {
     pub total: i32,
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct OffChainMetadata {
     pub name: String,
@@ -75,14 +75,14 @@ pub struct OffChainMetadata {
     pub properties: Properties,
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Attribute {
     pub trait_type: String,
     pub value: String,
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Properties {
     pub category: String,
@@ -90,7 +90,7 @@ pub struct Properties {
     pub creators: Vec<Creator>,
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct File {
     pub uri: String,
@@ -0,0 +1,158 @@
+//! Reconciles transactions seen at `Confirmed` commitment with their finalized state.
+//!
+//! A confirmed transaction can still be dropped if its block does not end up on the finalized fork. Hand the
+//! transactions of confirmed requests to a [`Reconciler`] and call [`Reconciler::reconcile`] periodically: each call
+//! looks the pending transactions up at `Finalized` commitment and reports the ones that got finalized, and the ones
+//! that were not finalized within [`drop_after`](Reconciler::with_drop_after) of being first seen as dropped.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::client::{api::HeliusApi, batch::BatchResponse};
+use crate::error::ClientResult;
+use crate::models::enriched_transaction::EnrichedTransaction;
+use crate::types::CommitmentLevel;
+
+/// How long a transaction may stay unfinalized before it is reported as dropped. A blockhash expires after 151 blocks,
+/// about a minute, so a transaction that is not finalized by then will not be.
+pub const DEFAULT_DROP_AFTER: Duration = Duration::from_secs(120);
+
+/// The outcome of a tracked transaction.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ReconcileEvent {
+    /// The transaction is finalized, with its details at `Finalized` commitment.
+    Finalized(EnrichedTransaction),
+    /// The transaction was not finalized in time, with its details as first seen.
+    Dropped(EnrichedTransaction),
+}
+
+impl ReconcileEvent {
+    pub fn transaction(&self) -> &EnrichedTransaction {
+        match self {
+            ReconcileEvent::Finalized(transaction) | ReconcileEvent::Dropped(transaction) => {
+                transaction
+            }
+        }
+    }
+
+    pub fn signature(&self) -> &str {
+        &self.transaction().signature
+    }
+}
+
+struct Pending {
+    transaction: EnrichedTransaction,
+    first_seen: Instant,
+}
+
+/// Tracks confirmed transactions until they are finalized or dropped. See the [module documentation](self).
+pub struct Reconciler {
+    api: Arc<dyn HeliusApi>,
+    drop_after: Duration,
+    pending: Mutex<HashMap<String, Pending>>,
+}
+
+impl Reconciler {
+    pub fn new(api: Arc<dyn HeliusApi>) -> Self {
+        Reconciler {
+            api,
+            drop_after: DEFAULT_DROP_AFTER,
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Reports transactions that are not finalized `drop_after` after they were first tracked as dropped. Defaults to
+    /// [`DEFAULT_DROP_AFTER`].
+    pub fn with_drop_after(mut self, drop_after: Duration) -> Self {
+        self.drop_after = drop_after;
+        self
+    }
+
+    /// Tracks `transaction`, seen at `Confirmed` commitment. Tracking a transaction again keeps the time it was first
+    /// seen.
+    pub fn track(&self, transaction: EnrichedTransaction) {
+        self.pending
+            .lock()
+            .unwrap()
+            .entry(transaction.signature.clone())
+            .or_insert_with(|| Pending {
+                transaction,
+                first_seen: Instant::now(),
+            });
+    }
+
+    pub fn track_all<I: IntoIterator<Item = EnrichedTransaction>>(&self, transactions: I) {
+        for transaction in transactions {
+            self.track(transaction);
+        }
+    }
+
+    /// Stops tracking the transaction with `signature`, e.g. because it was seen at `Finalized` commitment elsewhere.
+    pub fn untrack(&self, signature: &str) -> bool {
+        self.pending.lock().unwrap().remove(signature).is_some()
+    }
+
+    /// The number of tracked transactions that are neither finalized nor dropped yet.
+    pub fn pending(&self) -> usize {
+        self.pending.lock().unwrap().len()
+    }
+
+    pub fn is_tracked(&self, signature: &str) -> bool {
+        self.pending.lock().unwrap().contains_key(signature)
+    }
+
+    /// Looks every tracked transaction up at `Finalized` commitment and returns an event for each one that got finalized
+    /// or dropped, in slot order. Those stop being tracked. Transactions whose lookup failed stay tracked and are
+    /// neither finalized nor dropped until they are looked up successfully.
+    pub async fn reconcile(&self) -> ClientResult<BatchResponse<ReconcileEvent>> {
+        let signatures: Vec<String> = self.pending.lock().unwrap().keys().cloned().collect();
+        if signatures.is_empty() {
+            return Ok(BatchResponse::default());
+        }
+        let lookup = self
+            .api
+            .get_enriched_transactions_by_hash(signatures.clone(), Some(CommitmentLevel::Finalized))
+            .await?;
+
+        let mut finalized: HashMap<String, EnrichedTransaction> = lookup
+            .results
+            .into_iter()
+            .map(|transaction| (transaction.signature.clone(), transaction))
+            .collect();
+        let failed: Vec<&String> = lookup
+            .failed_chunks
+            .iter()
+            .flat_map(|chunk| &chunk.inputs)
+            .collect();
+
+        let mut pending = self.pending.lock().unwrap();
+        let mut events = Vec::new();
+        for signature in &signatures {
+            if failed.contains(&signature) {
+                continue;
+            }
+            let event = match finalized.remove(signature) {
+                Some(transaction) => ReconcileEvent::Finalized(transaction),
+                None => match pending.get(signature) {
+                    Some(tracked) if tracked.first_seen.elapsed() >= self.drop_after => {
+                        ReconcileEvent::Dropped(tracked.transaction.clone())
+                    }
+                    _ => continue,
+                },
+            };
+            pending.remove(signature);
+            events.push(event);
+        }
+        events.sort_by(|a, b| {
+            (a.transaction().slot, a.signature()).cmp(&(b.transaction().slot, b.signature()))
+        });
+
+        Ok(BatchResponse {
+            results: events,
+            failed_chunks: lookup.failed_chunks,
+        })
+    }
+}
@@ -0,0 +1,53 @@
+//! A SQLite connection shared by the SQLite stores. Statements run on the blocking thread pool of tokio, so the stores
+//! do not hold up the async tasks that call them.
+
+use rusqlite::Connection;
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use crate::error::{ClientError, ClientErrorKind, ClientResult};
+
+#[derive(Clone)]
+pub(crate) struct SqliteConnection {
+    connection: Arc<Mutex<Connection>>,
+}
+
+impl SqliteConnection {
+    /// Opens (or creates) the database at `path` and runs `schema` on it.
+    pub(crate) fn open<P: AsRef<Path>>(path: P, schema: &str) -> ClientResult<Self> {
+        let connection = Connection::open(path).map_err(sqlite_error)?;
+        SqliteConnection::with_schema(connection, schema)
+    }
+
+    /// Opens a database in memory and runs `schema` on it.
+    pub(crate) fn open_in_memory(schema: &str) -> ClientResult<Self> {
+        let connection = Connection::open_in_memory().map_err(sqlite_error)?;
+        SqliteConnection::with_schema(connection, schema)
+    }
+
+    fn with_schema(connection: Connection, schema: &str) -> ClientResult<Self> {
+        connection.execute_batch(schema).map_err(sqlite_error)?;
+        Ok(SqliteConnection {
+            connection: Arc::new(Mutex::new(connection)),
+        })
+    }
+
+    /// Runs `f` with the connection on a blocking thread.
+    pub(crate) async fn call<T, F>(&self, f: F) -> ClientResult<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Connection) -> rusqlite::Result<T> + Send + 'static,
+    {
+        let connection = self.connection.clone();
+        tokio::task::spawn_blocking(move || f(&mut connection.lock().unwrap()))
+            .await
+            .map_err(|e| ClientError::from(ClientErrorKind::Custom(e.to_string())))?
+            .map_err(sqlite_error)
+    }
+}
+
+fn sqlite_error(error: rusqlite::Error) -> ClientError {
+    ClientError::from(ClientErrorKind::Custom(format!("SQLite error: {}", error)))
+}
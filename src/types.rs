@@ -0,0 +1,175 @@
+//! The Solana types used by the REST methods.
+//!
+//! These are lightweight types, the same with and without the `rpc` feature, without the deprecated variants of
+//! `solana-sdk`. With it, they convert to and from the `solana-sdk` types of the same names, to be passed to and from
+//! `HeliusClient::rpc_client`. [`Pubkey`] always comes from `solana-program`.
+
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+pub use solana_program::pubkey::Pubkey;
+
+/// The decimals of SOL amounts, which are in lamports.
+pub const NATIVE_DECIMALS: u8 = 9;
+
+/// The Solana clusters. Only [`ClusterType::MainnetBeta`] and [`ClusterType::Devnet`] are served by Helius.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ClusterType {
+    Testnet,
+    MainnetBeta,
+    Devnet,
+    Development,
+}
+
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Serialize, Deserialize,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum CommitmentLevel {
+    Processed,
+    Confirmed,
+    #[default]
+    Finalized,
+}
+
+const SIGNATURE_BYTES: usize = 64;
+
+/// A transaction signature, shown and parsed as base58.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Signature([u8; SIGNATURE_BYTES]);
+
+impl Signature {
+    /// # Panics
+    ///
+    /// If `bytes` is not 64 bytes long.
+    pub fn new(bytes: &[u8]) -> Self {
+        Signature(bytes.try_into().expect("a signature is 64 bytes long"))
+    }
+}
+
+impl Default for Signature {
+    fn default() -> Self {
+        Signature([0; SIGNATURE_BYTES])
+    }
+}
+
+impl From<[u8; SIGNATURE_BYTES]> for Signature {
+    fn from(bytes: [u8; SIGNATURE_BYTES]) -> Self {
+        Signature(bytes)
+    }
+}
+
+impl AsRef<[u8]> for Signature {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl fmt::Display for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&bs58::encode(self.0).into_string())
+    }
+}
+
+impl fmt::Debug for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ParseSignatureError {
+    WrongSize,
+    Invalid,
+}
+
+impl fmt::Display for ParseSignatureError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseSignatureError::WrongSize => {
+                f.write_str("string decoded to wrong size for signature")
+            }
+            ParseSignatureError::Invalid => f.write_str("failed to decode string to signature"),
+        }
+    }
+}
+
+impl std::error::Error for ParseSignatureError {}
+
+impl FromStr for Signature {
+    type Err = ParseSignatureError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = bs58::decode(s)
+            .into_vec()
+            .map_err(|_| ParseSignatureError::Invalid)?;
+        let bytes: [u8; SIGNATURE_BYTES] = bytes
+            .try_into()
+            .map_err(|_| ParseSignatureError::WrongSize)?;
+        Ok(Signature(bytes))
+    }
+}
+
+#[cfg(feature = "rpc")]
+mod rpc {
+    use solana_sdk::{commitment_config, genesis_config, signature};
+
+    use super::{ClusterType, CommitmentLevel, Signature};
+
+    impl From<ClusterType> for genesis_config::ClusterType {
+        fn from(cluster_type: ClusterType) -> Self {
+            match cluster_type {
+                ClusterType::Testnet => genesis_config::ClusterType::Testnet,
+                ClusterType::MainnetBeta => genesis_config::ClusterType::MainnetBeta,
+                ClusterType::Devnet => genesis_config::ClusterType::Devnet,
+                ClusterType::Development => genesis_config::ClusterType::Development,
+            }
+        }
+    }
+
+    impl From<genesis_config::ClusterType> for ClusterType {
+        fn from(cluster_type: genesis_config::ClusterType) -> Self {
+            match cluster_type {
+                genesis_config::ClusterType::Testnet => ClusterType::Testnet,
+                genesis_config::ClusterType::MainnetBeta => ClusterType::MainnetBeta,
+                genesis_config::ClusterType::Devnet => ClusterType::Devnet,
+                genesis_config::ClusterType::Development => ClusterType::Development,
+            }
+        }
+    }
+
+    impl From<CommitmentLevel> for commitment_config::CommitmentLevel {
+        fn from(commitment: CommitmentLevel) -> Self {
+            match commitment {
+                CommitmentLevel::Processed => commitment_config::CommitmentLevel::Processed,
+                CommitmentLevel::Confirmed => commitment_config::CommitmentLevel::Confirmed,
+                CommitmentLevel::Finalized => commitment_config::CommitmentLevel::Finalized,
+            }
+        }
+    }
+
+    /// The deprecated levels become the levels `solana-sdk` says to use instead.
+    impl From<commitment_config::CommitmentLevel> for CommitmentLevel {
+        #[allow(deprecated)]
+        fn from(commitment: commitment_config::CommitmentLevel) -> Self {
+            use commitment_config::CommitmentLevel::*;
+            match commitment {
+                Processed | Recent => CommitmentLevel::Processed,
+                Confirmed | Single | SingleGossip => CommitmentLevel::Confirmed,
+                Finalized | Max | Root => CommitmentLevel::Finalized,
+            }
+        }
+    }
+
+    impl From<Signature> for signature::Signature {
+        fn from(signature: Signature) -> Self {
+            signature::Signature::new(signature.as_ref())
+        }
+    }
+
+    impl From<signature::Signature> for Signature {
+        fn from(signature: signature::Signature) -> Self {
+            Signature::new(signature.as_ref())
+        }
+    }
+}
@@ -0,0 +1,210 @@
+#[cfg(test)]
+mod accounting {
+    use futures::stream;
+    use helius_rust_client::{
+        accounting::{Activity, Ledger, LotMethod, PriceTable, Unpriced},
+        models::enriched_transaction::EnrichedTransaction,
+    };
+    use serde_json::{json, Value};
+    use std::collections::HashMap;
+
+    const ADDRESS: &str = "86xCnPeV69n6t3DnyGvkKobf9FdN2H9oiVDdaMpo2MMY";
+    const OTHER_ADDRESS: &str = "4zvwRjXUKGfvwnParsHAS3HuSVzV5cA4McphgmoCtajS";
+    const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+    const NFT: &str = "nft-mint";
+    const SOL: i64 = 1000000000;
+
+    /// The enriched transaction fixture of the mock server in slot `slot` at `slot * 100`, changing the SOL balance of
+    /// `ADDRESS` by `lamports` and its token balances by `tokens`, with the fee paid by `OTHER_ADDRESS`.
+    fn transaction(slot: u64, lamports: i64, tokens: &[(&str, i64, u8)]) -> EnrichedTransaction {
+        let mut transaction: Value =
+            serde_json::from_str::<Vec<Value>>(include_str!("../helius-mock-server/fixtures/enriched_transactions.json"))
+                .unwrap()
+                .remove(0);
+        transaction["signature"] = format!("signature-{}", slot).into();
+        transaction["slot"] = slot.into();
+        transaction["timestamp"] = (slot * 100).into();
+        transaction["feePayer"] = OTHER_ADDRESS.into();
+        let changes: Vec<Value> = tokens
+            .iter()
+            .map(|(mint, amount, decimals)| {
+                json!({
+                    "userAccount": ADDRESS,
+                    "tokenAccount": format!("{}-token-account", mint),
+                    "mint": mint,
+                    "rawTokenAmount": { "tokenAmount": amount.to_string(), "decimals": decimals }
+                })
+            })
+            .collect();
+        transaction["accountData"] = json!([
+            { "account": ADDRESS, "nativeBalanceChange": lamports, "tokenBalanceChanges": [] },
+            { "account": OTHER_ADDRESS, "nativeBalanceChange": -lamports, "tokenBalanceChanges": changes }
+        ]);
+        serde_json::from_value(transaction).unwrap()
+    }
+
+    /// An NFT sale of `NFT` in slot `slot` from `seller` to `buyer` for `lamports`.
+    fn nft_sale(slot: u64, seller: &str, buyer: &str, lamports: i64) -> EnrichedTransaction {
+        let (sol, nft) = if seller == ADDRESS { (lamports, -1) } else { (-lamports, 1) };
+        let mut transaction = serde_json::to_value(transaction(slot, sol, &[(NFT, nft, 0)])).unwrap();
+        transaction["type"] = "NFT_SALE".into();
+        transaction["events"]["nft"] = json!({
+            "amount": lamports,
+            "fee": 5000,
+            "feePayer": buyer,
+            "signature": format!("signature-{}", slot),
+            "slot": slot,
+            "timestamp": slot * 100,
+            "type": "NFT_SALE",
+            "buyer": buyer,
+            "seller": seller,
+            "staker": "",
+            "nfts": [{ "mint": NFT, "tokenStandard": "NonFungible" }]
+        });
+        serde_json::from_value(transaction).unwrap()
+    }
+
+    fn prices() -> PriceTable {
+        PriceTable::new()
+            .with_price(None, 1000, 100.0)
+            .with_price(None, 2000, 150.0)
+            .with_price(None, 2500, 50.0)
+            .with_price(None, 3000, 200.0)
+            .with_price(Some(USDC), 0, 1.0)
+    }
+
+    /// Three SOL lots, at $100, $150 and $50, then a swap of 12 SOL for 2,400 USDC.
+    fn history() -> Vec<EnrichedTransaction> {
+        vec![
+            transaction(10, 10 * SOL, &[]),
+            transaction(20, 6 * SOL, &[]),
+            transaction(25, 2 * SOL, &[]),
+            transaction(30, -12 * SOL, &[(USDC, 2400000000, 6)]),
+        ]
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-6, "{} != {}", actual, expected);
+    }
+
+    #[tokio::test]
+    async fn assigns_lots_by_method() {
+        let specific = HashMap::from([(
+            "signature-30".to_string(),
+            vec!["signature-25".to_string(), "signature-10".to_string()],
+        )]);
+        let methods = [
+            (LotMethod::Fifo, 1300.0, vec!["signature-10", "signature-20"]),
+            (LotMethod::Lifo, 1400.0, vec!["signature-25", "signature-20", "signature-10"]),
+            (LotMethod::Hifo, 1500.0, vec!["signature-20", "signature-10"]),
+            (LotMethod::SpecificId(specific), 1100.0, vec!["signature-25", "signature-10"]),
+        ];
+
+        for (method, cost_basis, lots) in methods {
+            let mut ledger = Ledger::new(ADDRESS, method);
+            for transaction in history() {
+                ledger.apply(&transaction, &prices()).await.unwrap();
+            }
+
+            let realized = ledger.realized();
+            assert_eq!(realized.len(), 1);
+            assert_eq!(realized[0].activity, Activity::Swap);
+            assert_eq!(realized[0].quantity, 12 * SOL as u128);
+            assert_close(realized[0].proceeds, 2400.0);
+            assert_close(realized[0].cost_basis, cost_basis);
+            assert_close(realized[0].gain, 2400.0 - cost_basis);
+            let signatures: Vec<&str> = realized[0].lots.iter().map(|lot| lot.signature.as_str()).collect();
+            assert_eq!(signatures, lots);
+            assert_eq!(realized[0].unmatched_quantity, 0);
+        }
+    }
+
+    #[tokio::test]
+    async fn values_open_lots() {
+        let mut ledger = Ledger::new(ADDRESS, LotMethod::Fifo);
+        // Out of order, like the history endpoints return it.
+        let history = history().into_iter().rev().map(Ok);
+
+        assert_eq!(ledger.replay(stream::iter(history), &prices()).await.unwrap(), 4);
+
+        let positions = ledger.unrealized(&prices(), 3000).await.unwrap();
+        assert_eq!(positions.len(), 2);
+        // 4 SOL at $150 and 2 SOL at $50, worth $200 each.
+        assert_eq!(positions[0].mint, None);
+        assert_eq!(positions[0].quantity, 6 * SOL as u128);
+        assert_close(positions[0].cost_basis, 700.0);
+        assert_close(positions[0].market_value.unwrap(), 1200.0);
+        assert_close(positions[0].gain.unwrap(), 500.0);
+        assert_eq!(positions[1].mint.as_deref(), Some(USDC));
+        assert_close(positions[1].cost_basis, 2400.0);
+        assert_close(positions[1].gain.unwrap(), 0.0);
+    }
+
+    #[tokio::test]
+    async fn realizes_nft_sales() {
+        let mut ledger = Ledger::new(ADDRESS, LotMethod::Fifo);
+        let history = [
+            transaction(10, 10 * SOL, &[]),
+            nft_sale(20, OTHER_ADDRESS, ADDRESS, 2 * SOL),
+            nft_sale(30, ADDRESS, OTHER_ADDRESS, 3 * SOL),
+        ];
+        for transaction in &history {
+            ledger.apply(transaction, &prices()).await.unwrap();
+        }
+
+        // The NFT has no price, so it is valued at the SOL paid for it, $300, and sold for the SOL received, $600.
+        let realized = ledger.realized();
+        assert_eq!(realized.len(), 2);
+        assert_eq!(realized[0].activity, Activity::NftPurchase);
+        assert_close(realized[0].gain, 100.0);
+        assert_eq!(realized[1].activity, Activity::NftSale);
+        assert_eq!(realized[1].mint.as_deref(), Some(NFT));
+        assert_close(realized[1].proceeds, 600.0);
+        assert_close(realized[1].cost_basis, 300.0);
+        assert_close(ledger.realized_gain(), 400.0);
+        assert!(ledger.lots(Some(NFT)).is_empty());
+        assert_eq!(
+            ledger.unpriced()[0],
+            Unpriced {
+                signature: "signature-20".to_string(),
+                mint: Some(NFT.to_string())
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn disposes_of_fees_without_proceeds() {
+        let mut ledger = Ledger::new(ADDRESS, LotMethod::Fifo);
+        let mut transfer = serde_json::to_value(transaction(20, -SOL - 5000, &[])).unwrap();
+        transfer["feePayer"] = ADDRESS.into();
+        let transfer: EnrichedTransaction = serde_json::from_value(transfer).unwrap();
+
+        ledger.apply(&transaction(10, 10 * SOL, &[]), &prices()).await.unwrap();
+        ledger.apply(&transfer, &prices()).await.unwrap();
+        ledger.apply(&transfer, &prices()).await.unwrap();
+
+        let realized = ledger.realized();
+        assert_eq!(realized.len(), 2);
+        assert_eq!(realized[0].activity, Activity::TransferOut);
+        assert_eq!(realized[0].quantity, SOL as u128);
+        assert_close(realized[0].gain, 50.0);
+        assert_eq!(realized[1].activity, Activity::Fee);
+        assert_eq!(realized[1].quantity, 5000);
+        assert_close(realized[1].proceeds, 0.0);
+        assert_eq!(ledger.lots(None)[0].quantity, 9 * SOL as u128 - 5000);
+    }
+
+    #[tokio::test]
+    async fn rejects_malformed_token_amounts() {
+        let mut ledger = Ledger::new(ADDRESS, LotMethod::Fifo);
+        let mut swap = history().remove(3);
+        swap.account_data[1].token_balance_changes[0].raw_token_amount.token_amount = "2400.5".to_string();
+
+        assert!(ledger.apply(&swap, &prices()).await.is_err());
+
+        // Nothing of the transaction was booked, so it can be applied once fixed.
+        assert!(ledger.lots(Some(USDC)).is_empty());
+        ledger.apply(&history()[3], &prices()).await.unwrap();
+        assert_eq!(ledger.lots(Some(USDC))[0].quantity, 2400000000);
+    }
+}
@@ -0,0 +1,277 @@
+#[cfg(test)]
+mod backfill {
+    use async_trait::async_trait;
+    use helius_rust_client::{
+        backfill::{
+            Ack, Backfill, BackfillControl, BackfillCursor, BackfillHandler, CursorStore, FileCursorStore,
+            InMemoryCursorStore,
+        },
+        client::mock::{HeliusMethod, MockHeliusClient},
+        error::{ClientError, ClientErrorKind, ClientResult},
+        models::raw_transaction::RawTransaction,
+        types::Signature,
+    };
+    use serde_json::Value;
+    use solana_program::pubkey::Pubkey;
+    use std::{
+        str::FromStr,
+        sync::{Arc, Mutex},
+    };
+
+    const ADDRESS: &str = "86xCnPeV69n6t3DnyGvkKobf9FdN2H9oiVDdaMpo2MMY";
+
+    fn signature(n: u8) -> String {
+        Signature::from([n; 64]).to_string()
+    }
+
+    /// The raw transaction fixture of the mock server with the signature `signature(n)`.
+    fn transaction(n: u8) -> RawTransaction {
+        let fixtures: Vec<Value> =
+            serde_json::from_str(include_str!("../helius-mock-server/fixtures/raw_transactions.json")).unwrap();
+        let mut transaction = fixtures[0].clone();
+        transaction["transaction"]["signatures"][0] = Value::String(signature(n));
+        serde_json::from_value(transaction).unwrap()
+    }
+
+    /// Records the signatures it handles and fails on `fail_on`, or stops the job after `stop_after` of them.
+    struct Recorder {
+        ack: Ack,
+        handled: Mutex<Vec<String>>,
+        fail_on: Option<String>,
+        stop_after: Option<(usize, BackfillControl)>,
+        /// The store whose checkpoint is recorded in `checkpoints` whenever a transaction is delivered.
+        store: Option<Arc<InMemoryCursorStore>>,
+        checkpoints: Mutex<Vec<Option<String>>>,
+    }
+
+    impl Recorder {
+        fn new(ack: Ack) -> Self {
+            Recorder {
+                ack,
+                handled: Mutex::new(Vec::new()),
+                fail_on: None,
+                stop_after: None,
+                store: None,
+                checkpoints: Mutex::new(Vec::new()),
+            }
+        }
+
+        fn handled(&self) -> Vec<String> {
+            self.handled.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait]
+    impl BackfillHandler for Recorder {
+        async fn handle(&self, _address: &Pubkey, transaction: &RawTransaction) -> ClientResult<Ack> {
+            let signature = transaction.transaction.signatures[0].clone();
+            if let Some(store) = &self.store {
+                let cursor = store.load("job", ADDRESS).await?;
+                self.checkpoints.lock().unwrap().push(cursor.and_then(|cursor| cursor.before));
+            }
+            if self.fail_on.as_ref() == Some(&signature) {
+                return Err(ClientError::from(ClientErrorKind::Custom("handler failed".to_string())));
+            }
+            let mut handled = self.handled.lock().unwrap();
+            handled.push(signature);
+            if let Some((count, control)) = &self.stop_after {
+                if handled.len() == *count {
+                    control.stop();
+                }
+            }
+            Ok(self.ack)
+        }
+    }
+
+    fn backfill(mock: &Arc<MockHeliusClient>, store: Arc<dyn CursorStore>) -> Backfill {
+        Backfill::new(mock.clone(), store, "job", vec![Pubkey::from_str(ADDRESS).unwrap()]).with_page_size(2)
+    }
+
+    fn pages(mock: &MockHeliusClient, pages: &[&[u8]]) {
+        for page in pages {
+            let transactions: Vec<RawTransaction> = page.iter().map(|&n| transaction(n)).collect();
+            mock.push_response(HeliusMethod::GetTransactionsForAddress, &transactions);
+        }
+    }
+
+    #[tokio::test]
+    async fn delivers_every_page() {
+        let mock = Arc::new(MockHeliusClient::new());
+        let store = Arc::new(InMemoryCursorStore::new());
+        pages(&mock, &[&[4, 3], &[2, 1], &[]]);
+        let handler = Recorder::new(Ack::Defer);
+
+        let outcome = backfill(&mock, store.clone()).run(&handler).await.unwrap();
+
+        assert_eq!(outcome.delivered, 4);
+        assert!(outcome.completed);
+        assert_eq!(handler.handled(), [4, 3, 2, 1].map(signature));
+        let calls = mock.calls_to(HeliusMethod::GetTransactionsForAddress);
+        assert_eq!(calls.len(), 3);
+        assert!(calls[1].arguments[0].contains(&format!("before: Some({})", signature(3))));
+        assert_eq!(
+            store.load("job", ADDRESS).await.unwrap(),
+            Some(BackfillCursor {
+                before: Some(signature(1)),
+                completed: true
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn resumes_after_handler_failure() {
+        let mock = Arc::new(MockHeliusClient::new());
+        let store = Arc::new(InMemoryCursorStore::new());
+        pages(&mock, &[&[4, 3], &[2, 1]]);
+        let mut handler = Recorder::new(Ack::Defer);
+        handler.fail_on = Some(signature(1));
+
+        assert!(backfill(&mock, store.clone()).run(&handler).await.is_err());
+        assert_eq!(handler.handled(), [4, 3, 2].map(signature));
+        assert_eq!(store.load("job", ADDRESS).await.unwrap().unwrap().before, Some(signature(2)));
+
+        let mock = Arc::new(MockHeliusClient::new());
+        pages(&mock, &[&[1]]);
+        let handler = Recorder::new(Ack::Defer);
+        let outcome = backfill(&mock, store.clone()).run(&handler).await.unwrap();
+
+        assert_eq!(handler.handled(), [signature(1)]);
+        assert!(outcome.completed);
+    }
+
+    #[tokio::test]
+    async fn checkpoints_committed_transactions_before_the_next_one() {
+        for (ack, expected) in [
+            (Ack::Commit, [None, Some(4), Some(3), Some(2)]),
+            (Ack::Defer, [None, None, Some(3), Some(3)]),
+        ] {
+            let mock = Arc::new(MockHeliusClient::new());
+            let store = Arc::new(InMemoryCursorStore::new());
+            pages(&mock, &[&[4, 3], &[2, 1], &[]]);
+            let mut handler = Recorder::new(ack);
+            handler.store = Some(store.clone());
+
+            backfill(&mock, store.clone()).run(&handler).await.unwrap();
+
+            assert_eq!(*handler.checkpoints.lock().unwrap(), expected.map(|n| n.map(signature)), "{:?}", ack);
+        }
+    }
+
+    #[tokio::test]
+    async fn stops_and_resumes() {
+        let mock = Arc::new(MockHeliusClient::new());
+        let store = Arc::new(InMemoryCursorStore::new());
+        pages(&mock, &[&[4, 3], &[2, 1]]);
+        let job = backfill(&mock, store.clone());
+        let mut handler = Recorder::new(Ack::Defer);
+        handler.stop_after = Some((1, job.control()));
+
+        let outcome = job.run(&handler).await.unwrap();
+
+        assert_eq!(outcome.delivered, 1);
+        assert!(!outcome.completed);
+        assert_eq!(store.load("job", ADDRESS).await.unwrap().unwrap().before, Some(signature(4)));
+
+        let mock = Arc::new(MockHeliusClient::new());
+        pages(&mock, &[&[3, 2], &[1]]);
+        let handler = Recorder::new(Ack::Defer);
+        let outcome = backfill(&mock, store.clone()).run(&handler).await.unwrap();
+
+        assert_eq!(handler.handled(), [3, 2, 1].map(signature));
+        assert!(outcome.completed);
+        let calls = mock.calls_to(HeliusMethod::GetTransactionsForAddress);
+        assert!(calls[0].arguments[0].contains(&format!("before: Some({})", signature(4))));
+    }
+
+    #[tokio::test]
+    async fn pauses_until_resumed() {
+        let mock = Arc::new(MockHeliusClient::new());
+        pages(&mock, &[&[2, 1], &[]]);
+        let job = backfill(&mock, Arc::new(InMemoryCursorStore::new()));
+        let control = job.control();
+        control.pause();
+        let handler = Recorder::new(Ack::Defer);
+
+        let run = job.run(&handler);
+        tokio::pin!(run);
+        assert!(tokio::time::timeout(std::time::Duration::from_millis(50), &mut run).await.is_err());
+        assert!(handler.handled().is_empty());
+
+        control.resume();
+        let outcome = run.await.unwrap();
+
+        assert_eq!(outcome.delivered, 2);
+    }
+
+    #[tokio::test]
+    async fn runs_again_after_a_run_is_dropped() {
+        let mock = Arc::new(MockHeliusClient::new());
+        // The dropped run fetches the first page before it pauses.
+        pages(&mock, &[&[2, 1], &[2, 1], &[]]);
+        let job = backfill(&mock, Arc::new(InMemoryCursorStore::new()));
+        let control = job.control();
+        control.pause();
+        let handler = Recorder::new(Ack::Defer);
+
+        assert!(tokio::time::timeout(std::time::Duration::from_millis(50), job.run(&handler)).await.is_err());
+
+        control.resume();
+        let outcome = job.run(&handler).await.unwrap();
+
+        assert_eq!(outcome.delivered, 2);
+        assert_eq!(handler.handled(), [2, 1].map(signature));
+    }
+
+    #[tokio::test]
+    async fn file_store_persists_cursors() {
+        let path = std::env::temp_dir().join(format!("helius-cursors-{}.json", std::process::id()));
+        let cursor = BackfillCursor {
+            before: Some(signature(1)),
+            completed: false,
+        };
+
+        let store = FileCursorStore::open(&path).unwrap();
+        store.save("job", ADDRESS, &cursor).await.unwrap();
+        store.save("other-job", ADDRESS, &BackfillCursor::default()).await.unwrap();
+        let reopened = FileCursorStore::open(&path).unwrap();
+        let loaded = reopened.load("job", ADDRESS).await.unwrap();
+        reopened.clear("other-job", ADDRESS).await.unwrap();
+        let cleared = FileCursorStore::open(&path).unwrap().load("other-job", ADDRESS).await.unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded, Some(cursor));
+        assert_eq!(cleared, None);
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn sqlite_store_resumes_after_reopening() {
+        use helius_rust_client::backfill::SqliteCursorStore;
+
+        let path = std::env::temp_dir().join(format!("helius-cursors-{}.sqlite", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mock = Arc::new(MockHeliusClient::new());
+        pages(&mock, &[&[4, 3], &[2, 1]]);
+        let mut handler = Recorder::new(Ack::Commit);
+        handler.fail_on = Some(signature(2));
+
+        let store = Arc::new(SqliteCursorStore::open(&path).unwrap());
+        assert!(backfill(&mock, store).run(&handler).await.is_err());
+
+        let mock = Arc::new(MockHeliusClient::new());
+        pages(&mock, &[&[2, 1], &[]]);
+        let handler = Recorder::new(Ack::Commit);
+        let store = Arc::new(SqliteCursorStore::open(&path).unwrap());
+        let outcome = backfill(&mock, store.clone()).run(&handler).await.unwrap();
+
+        let calls = mock.calls_to(HeliusMethod::GetTransactionsForAddress);
+        assert!(calls[0].arguments[0].contains(&format!("before: Some({})", signature(3))));
+        assert_eq!(handler.handled(), [2, 1].map(signature));
+        assert!(outcome.completed);
+        store.clear("job", ADDRESS).await.unwrap();
+        let cleared = SqliteCursorStore::open(&path).unwrap().load("job", ADDRESS).await.unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(cleared, None);
+    }
+}
@@ -0,0 +1,152 @@
+#[cfg(test)]
+mod balances {
+    use futures::stream;
+    use helius_mock_server::MockServer;
+    use helius_rust_client::{
+        balances::{BalanceMismatch, BalanceTracker},
+        client::init::HeliusClient,
+        models::enriched_transaction::EnrichedTransaction,
+        types::ClusterType,
+    };
+    use serde_json::{json, Value};
+
+    const ADDRESS: &str = "86xCnPeV69n6t3DnyGvkKobf9FdN2H9oiVDdaMpo2MMY";
+    const OTHER_ADDRESS: &str = "4zvwRjXUKGfvwnParsHAS3HuSVzV5cA4McphgmoCtajS";
+    const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+    const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+
+    /// A transaction in slot `slot` at `timestamp` changing the SOL balance of `ADDRESS` by `lamports` and its USDC
+    /// balance by `usdc`.
+    fn transaction(slot: u64, timestamp: i64, lamports: i64, usdc: i64) -> EnrichedTransaction {
+        let mut transaction: Value =
+            serde_json::from_str::<Vec<Value>>(include_str!("../helius-mock-server/fixtures/enriched_transactions.json"))
+                .unwrap()
+                .remove(0);
+        transaction["signature"] = format!("signature-{}", slot).into();
+        transaction["slot"] = slot.into();
+        transaction["timestamp"] = timestamp.into();
+        transaction["accountData"] = json!([
+            { "account": ADDRESS, "nativeBalanceChange": lamports, "tokenBalanceChanges": [] },
+            { "account": OTHER_ADDRESS, "nativeBalanceChange": -lamports, "tokenBalanceChanges": [] },
+            {
+                "account": "usdc-token-account",
+                "nativeBalanceChange": 0,
+                "tokenBalanceChanges": [
+                    {
+                        "userAccount": ADDRESS,
+                        "tokenAccount": "usdc-token-account",
+                        "mint": USDC,
+                        "rawTokenAmount": { "tokenAmount": usdc.to_string(), "decimals": 6 }
+                    }
+                ]
+            }
+        ]);
+        serde_json::from_value(transaction).unwrap()
+    }
+
+    fn history() -> Vec<EnrichedTransaction> {
+        // Newest first, like the transaction history endpoints.
+        vec![
+            transaction(30, 3000, -500000000, 5000000),
+            transaction(20, 2000, 1000000000, -10000000),
+            transaction(10, 1000, 1000000000, 30000000),
+        ]
+    }
+
+    #[tokio::test]
+    async fn reconstructs_balances_over_time() {
+        let mut tracker = BalanceTracker::new(ADDRESS);
+
+        let applied = tracker
+            .replay(stream::iter(history().into_iter().map(Ok)))
+            .await
+            .unwrap();
+
+        assert_eq!(applied, 3);
+        assert_eq!(tracker.balance(None), 1500000000);
+        assert_eq!(tracker.balance(Some(USDC)), 25000000);
+        assert_eq!(tracker.balance_at_slot(None, 9), 0);
+        assert_eq!(tracker.balance_at_slot(None, 20), 2000000000);
+        assert_eq!(tracker.balance_at_slot(Some(USDC), 25), 20000000);
+        assert_eq!(tracker.balance_at_time(Some(USDC), 2999), 20000000);
+        assert_eq!(tracker.balance_at_time(Some(USDC), 3000), 25000000);
+        assert_eq!(tracker.balance_at_time(None, 999), 0);
+        let balances: Vec<i128> = tracker.history(Some(USDC)).iter().map(|point| point.balance).collect();
+        assert_eq!(balances, [30000000, 20000000, 25000000]);
+        assert_eq!(tracker.mints(), [USDC]);
+    }
+
+    #[test]
+    fn ignores_repeated_transactions() {
+        let mut tracker = BalanceTracker::new(ADDRESS);
+
+        assert!(tracker.apply(&history()[0]).unwrap());
+        assert!(!tracker.apply(&history()[0]).unwrap());
+
+        assert_eq!(tracker.balance(None), -500000000);
+    }
+
+    #[test]
+    fn rejects_malformed_token_amounts() {
+        let mut tracker = BalanceTracker::new(ADDRESS);
+        let mut malformed = history().remove(0);
+        malformed.account_data[2].token_balance_changes[0].raw_token_amount.token_amount =
+            "340282366920938463463374607431768211456".to_string();
+
+        assert!(tracker.apply(&malformed).is_err());
+
+        // Nothing of the transaction was recorded, so it can be applied once fixed.
+        assert_eq!(tracker.balance(None), 0);
+        assert!(tracker.apply(&history()[0]).unwrap());
+        assert_eq!(tracker.balance(Some(USDC)), 5000000);
+    }
+
+    #[tokio::test]
+    async fn validates_against_current_balances() {
+        let server = MockServer::start().await;
+        let client = HeliusClient::new_with_urls(
+            "test-api-key".to_string(),
+            ClusterType::MainnetBeta,
+            server.url(),
+            server.url(),
+        );
+        let mut tracker = BalanceTracker::new(ADDRESS);
+        for transaction in history() {
+            tracker.apply(&transaction).unwrap();
+        }
+
+        let mismatches = tracker.validate_with(&client).await.unwrap();
+
+        // The history covers SOL and USDC, but not the BONK balance.
+        assert_eq!(
+            mismatches,
+            [BalanceMismatch {
+                mint: Some(BONK.to_string()),
+                reconstructed: 0,
+                actual: 100000000
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn anchors_partial_history_to_current_balances() {
+        let server = MockServer::start().await;
+        let client = HeliusClient::new_with_urls(
+            "test-api-key".to_string(),
+            ClusterType::MainnetBeta,
+            server.url(),
+            server.url(),
+        );
+        let balances = client.get_token_balances(ADDRESS.to_string()).await.unwrap();
+        // Only the newest transaction is known.
+        let mut tracker = BalanceTracker::new(ADDRESS);
+        tracker.apply(&history()[0]).unwrap();
+
+        tracker.anchor(&balances);
+
+        assert!(tracker.validate(&balances).is_empty());
+        assert_eq!(tracker.opening_balance(None), 2000000000);
+        assert_eq!(tracker.balance_at_slot(Some(USDC), 29), 20000000);
+        assert_eq!(tracker.balance(Some(BONK)), 100000000);
+    }
+}
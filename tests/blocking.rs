@@ -0,0 +1,79 @@
+#![cfg(all(feature = "blocking", feature = "rpc"))]
+
+#[cfg(test)]
+mod blocking {
+    use helius_mock_server::{Method, MockServer};
+    use helius_rust_client::blocking::HeliusClient;
+    use helius_rust_client::types::ClusterType;
+    use tokio::runtime::Runtime;
+
+    const ADDRESS: &str = "86xCnPeV69n6t3DnyGvkKobf9FdN2H9oiVDdaMpo2MMY";
+    const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+    /// Starts a mock server on a runtime of its own, since the blocking client must not run inside one.
+    fn mock_server(runtime: &Runtime) -> MockServer {
+        runtime.block_on(MockServer::start())
+    }
+
+    fn mock_client(server: &MockServer) -> HeliusClient {
+        HeliusClient::new_with_urls(
+            "test-api-key".to_string(),
+            ClusterType::MainnetBeta,
+            server.url(),
+            server.url(),
+        )
+    }
+
+    #[test]
+    fn get_token_balances() {
+        let runtime = Runtime::new().unwrap();
+        let server = mock_server(&runtime);
+        let client = mock_client(&server);
+
+        let x = client.get_token_balances(ADDRESS.to_string()).unwrap();
+
+        assert_eq!(x.native_balance, 1500000000);
+        assert_eq!(x.tokens[0].mint, USDC_MINT);
+        assert_eq!(server.requests_to(Method::GET, &format!("/v0/addresses/{}/balances", ADDRESS)).len(), 1);
+    }
+
+    #[test]
+    fn get_slot() {
+        let runtime = Runtime::new().unwrap();
+        let server = mock_server(&runtime);
+        let client = mock_client(&server);
+
+        assert_eq!(client.rpc_client.get_slot().unwrap(), 171942732);
+        assert_eq!(server.rpc_requests("getSlot").len(), 1);
+    }
+
+    #[test]
+    fn shares_layers_with_async_client() {
+        let runtime = Runtime::new().unwrap();
+        let server = mock_server(&runtime);
+        let client = mock_client(&server);
+
+        client.get_naming_service_names(ADDRESS.to_string()).unwrap();
+        client.rpc_client.get_slot().unwrap();
+
+        // The RPC client asks for the node version before its first request.
+        assert_eq!(client.inner().key_stats()[0].requests, 3);
+        assert!(!client.rpc_client.url().contains("test-api-key"));
+    }
+
+    #[test]
+    fn fails_within_an_async_runtime() {
+        let runtime = Runtime::new().unwrap();
+        let server = mock_server(&runtime);
+
+        runtime.block_on(async {
+            let client = mock_client(&server);
+
+            let error = client.get_token_balances(ADDRESS.to_string()).unwrap_err();
+
+            assert!(error.to_string().contains("within an async runtime"));
+            // Dropping the client here must not panic either.
+        });
+        assert!(server.requests_to(Method::GET, &format!("/v0/addresses/{}/balances", ADDRESS)).is_empty());
+    }
+}
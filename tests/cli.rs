@@ -0,0 +1,173 @@
+#![cfg(feature = "cli")]
+
+#[cfg(test)]
+mod cli {
+    use helius_mock_server::{Method, MockServer};
+    use serde_json::Value;
+    use std::{
+        fs,
+        path::PathBuf,
+        process::{Command, Output},
+    };
+    use tokio::runtime::Runtime;
+
+    const ADDRESS: &str = "86xCnPeV69n6t3DnyGvkKobf9FdN2H9oiVDdaMpo2MMY";
+    const OTHER_ADDRESS: &str = "4zvwRjXUKGfvwnParsHAS3HuSVzV5cA4McphgmoCtajS";
+    const SIGNATURE: &str = "5h6xBEauJ3PK6SWCZ1PGjBvj8vDdWG3KpwATGy1ARAXFSDwt8GFXM7W5Ncn16wmqokgpiKRLuS83KUxyZyv2sUYv";
+
+    /// Starts a mock server on a runtime of its own, which keeps serving while the CLI runs.
+    fn mock_server(runtime: &Runtime) -> MockServer {
+        runtime.block_on(MockServer::start())
+    }
+
+    /// Runs the CLI against `server`, ignoring any config file of the user.
+    fn helius(server: &MockServer, args: &[&str]) -> Output {
+        Command::new(env!("CARGO_BIN_EXE_helius"))
+            .args(args)
+            .env("HELIUS_API_KEY", "test-api-key")
+            .env("HELIUS_API_URL", server.url())
+            .env_remove("HELIUS_CONFIG")
+            .env("XDG_CONFIG_HOME", temp_file("config-home"))
+            .output()
+            .unwrap()
+    }
+
+    fn temp_file(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("helius-cli-{}-{}", std::process::id(), name))
+    }
+
+    fn stdout(output: &Output) -> String {
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        String::from_utf8(output.stdout.clone()).unwrap()
+    }
+
+    #[test]
+    fn balances_as_json() {
+        let runtime = Runtime::new().unwrap();
+        let server = mock_server(&runtime);
+
+        let output = helius(&server, &["balances", ADDRESS, "--output", "json"]);
+
+        let balances: Value = serde_json::from_str(&stdout(&output)).unwrap();
+        assert_eq!(balances["nativeBalance"], 1500000000);
+        let requests = server.requests_to(Method::GET, &format!("/v0/addresses/{}/balances", ADDRESS));
+        assert!(requests[0].query.as_ref().unwrap().contains("api-key=test-api-key"));
+    }
+
+    #[test]
+    fn names_as_table() {
+        let runtime = Runtime::new().unwrap();
+        let server = mock_server(&runtime);
+
+        let output = stdout(&helius(&server, &["names", ADDRESS]));
+
+        assert_eq!(output, "NAME\nmock.sol\n");
+    }
+
+    #[test]
+    fn transactions_follow_pages() {
+        let runtime = Runtime::new().unwrap();
+        let server = mock_server(&runtime);
+
+        let output = helius(&server, &["transactions", ADDRESS, "--pages", "2", "--output", "ndjson"]);
+
+        assert_eq!(stdout(&output).lines().count(), 2);
+        let requests = server.requests_to(Method::GET, &format!("/v0/addresses/{}/transactions", ADDRESS));
+        assert_eq!(requests.len(), 2);
+        assert!(!requests[0].query.as_ref().unwrap().contains("before="));
+        assert!(requests[1].query.as_ref().unwrap().contains(&format!("before={}", SIGNATURE)));
+    }
+
+    #[test]
+    fn rejects_invalid_address() {
+        let runtime = Runtime::new().unwrap();
+        let server = mock_server(&runtime);
+
+        let output = helius(&server, &["balances", "not-an-address"]);
+
+        assert!(!output.status.success());
+        assert!(server.requests().is_empty());
+    }
+
+    #[test]
+    fn reads_api_key_from_config_file() {
+        let runtime = Runtime::new().unwrap();
+        let server = mock_server(&runtime);
+        let config = temp_file("config.yaml");
+        fs::write(&config, format!("api_key: config-api-key\napi_url: {}\n", server.url())).unwrap();
+
+        let output = Command::new(env!("CARGO_BIN_EXE_helius"))
+            .args(["names", ADDRESS, "--config"])
+            .arg(&config)
+            .env_remove("HELIUS_API_KEY")
+            .env_remove("HELIUS_API_URL")
+            .output()
+            .unwrap();
+        fs::remove_file(&config).unwrap();
+
+        stdout(&output);
+        let requests = server.requests_to(Method::GET, &format!("/v0/addresses/{}/names", ADDRESS));
+        assert!(requests[0].query.as_ref().unwrap().contains("api-key=config-api-key"));
+    }
+
+    #[test]
+    fn fails_without_api_key() {
+        let output = Command::new(env!("CARGO_BIN_EXE_helius"))
+            .args(["names", ADDRESS])
+            .env_remove("HELIUS_API_KEY")
+            .env_remove("HELIUS_CONFIG")
+            .env("XDG_CONFIG_HOME", temp_file("config-home"))
+            .output()
+            .unwrap();
+
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("no API key"));
+    }
+
+    #[test]
+    fn creates_webhook_from_yaml() {
+        let runtime = Runtime::new().unwrap();
+        let server = mock_server(&runtime);
+        let file = temp_file("webhook.yaml");
+        fs::write(
+            &file,
+            format!(
+                "webhookURL: https://example.com/hook\ntransactionTypes: [NFT_SALE]\naccountAddresses: [{}]\nwebhookType: enhanced\nauthHeader: secret\n",
+                ADDRESS
+            ),
+        )
+        .unwrap();
+
+        let output = helius(&server, &["webhooks", "create", "--file", file.to_str().unwrap()]);
+        fs::remove_file(&file).unwrap();
+
+        assert!(stdout(&output).contains("mock-webhook-id"));
+        let requests = server.requests_to(Method::POST, "/v0/webhooks");
+        assert_eq!(requests[0].body["webhookURL"], "https://example.com/hook");
+        assert_eq!(requests[0].body["transactionTypes"][0], "NFT_SALE");
+    }
+
+    #[test]
+    fn adds_addresses_to_webhook() {
+        let runtime = Runtime::new().unwrap();
+        let server = mock_server(&runtime);
+
+        let output = helius(&server, &["webhooks", "add-address", "mock-webhook-id", ADDRESS, OTHER_ADDRESS]);
+
+        stdout(&output);
+        let requests = server.requests_to(Method::PUT, "/v0/webhooks/mock-webhook-id");
+        assert_eq!(requests[0].body["accountAddresses"], serde_json::json!([ADDRESS, OTHER_ADDRESS]));
+        assert_eq!(requests[0].body["webhookURL"], "https://discord.com/api/webhooks/12345");
+    }
+
+    #[test]
+    fn deletes_webhook() {
+        let runtime = Runtime::new().unwrap();
+        let server = mock_server(&runtime);
+
+        let output = helius(&server, &["webhooks", "delete", "mock-webhook-id", "--output", "ndjson"]);
+
+        assert_eq!(stdout(&output), "{\"webhookID\":\"mock-webhook-id\"}\n");
+        assert_eq!(server.requests_to(Method::DELETE, "/v0/webhooks/mock-webhook-id").len(), 1);
+    }
+}
@@ -0,0 +1,209 @@
+#[cfg(test)]
+mod export {
+    use futures::stream;
+    use helius_mock_server::MockServer;
+    use helius_rust_client::{
+        client::init::HeliusClient,
+        export::{self, CsvWriter, MovementKind, MovementWriter, NdjsonWriter, COLUMNS},
+        models::enriched_transaction::{EnrichedTransaction, RequestConfig},
+        types::ClusterType,
+    };
+    use serde_json::{json, Value};
+    use solana_program::pubkey::Pubkey;
+    use std::str::FromStr;
+
+    const ADDRESS: &str = "86xCnPeV69n6t3DnyGvkKobf9FdN2H9oiVDdaMpo2MMY";
+    const OTHER_ADDRESS: &str = "4zvwRjXUKGfvwnParsHAS3HuSVzV5cA4McphgmoCtajS";
+    const SIGNATURE: &str = "5h6xBEauJ3PK6SWCZ1PGjBvj8vDdWG3KpwATGy1ARAXFSDwt8GFXM7W5Ncn16wmqokgpiKRLuS83KUxyZyv2sUYv";
+    const MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+    async fn fixture_transactions() -> Vec<EnrichedTransaction> {
+        let server = MockServer::start().await;
+        let client = HeliusClient::new_with_urls(
+            "test-api-key".to_string(),
+            ClusterType::MainnetBeta,
+            server.url(),
+            server.url(),
+        );
+        let config = RequestConfig {
+            address: Pubkey::from_str(ADDRESS).unwrap(),
+            ..Default::default()
+        };
+        client.get_enriched_transactions(config).await.unwrap()
+    }
+
+    /// A USDC transfer of 2.5 with the token balance changes of both owners.
+    fn token_transaction() -> EnrichedTransaction {
+        serde_json::from_value(json!({
+            "description": "",
+            "type": "TRANSFER",
+            "source": "SOLANA_PROGRAM_LIBRARY",
+            "fee": 5000,
+            "feePayer": ADDRESS,
+            "signature": "token-transfer",
+            "slot": 171942800,
+            "timestamp": null,
+            "nativeTransfers": [],
+            "tokenTransfers": [{
+                "fromUserAccount": ADDRESS,
+                "toUserAccount": OTHER_ADDRESS,
+                "fromTokenAccount": "from-token-account",
+                "toTokenAccount": "to-token-account",
+                "tokenAmount": 2500000,
+                "mint": MINT
+            }],
+            "accountData": [
+                {
+                    "account": ADDRESS,
+                    "nativeBalanceChange": 0,
+                    "tokenBalanceChanges": [{
+                        "userAccount": ADDRESS,
+                        "tokenAccount": "from-token-account",
+                        "mint": MINT,
+                        "rawTokenAmount": { "tokenAmount": "-2500000", "decimals": 6 }
+                    }]
+                },
+                {
+                    "account": "to-token-account",
+                    "nativeBalanceChange": 0,
+                    "tokenBalanceChanges": [{
+                        "userAccount": OTHER_ADDRESS,
+                        "tokenAccount": "to-token-account",
+                        "mint": MINT,
+                        "rawTokenAmount": { "tokenAmount": "2500000", "decimals": 6 }
+                    }]
+                }
+            ],
+            "transactionError": null,
+            "instructions": [],
+            "events": {}
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn flattens_native_movements() {
+        let transactions = fixture_transactions().await;
+
+        let movements = export::movements(&transactions[0]).unwrap();
+
+        let kinds: Vec<_> = movements.iter().map(|movement| movement.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                MovementKind::NativeTransfer,
+                MovementKind::NativeBalanceChange,
+                MovementKind::NativeBalanceChange
+            ]
+        );
+        assert_eq!(movements[0].signature, SIGNATURE);
+        assert_eq!(movements[0].from_account.as_deref(), Some(ADDRESS));
+        assert_eq!(movements[0].to_account.as_deref(), Some(OTHER_ADDRESS));
+        assert_eq!(movements[0].mint, None);
+        assert_eq!(movements[0].ui_amount().as_deref(), Some("1"));
+        assert_eq!(movements[1].account.as_deref(), Some(ADDRESS));
+        assert_eq!(movements[1].amount, -1000005000);
+        assert_eq!(movements[1].ui_amount().as_deref(), Some("-1.000005"));
+    }
+
+    #[test]
+    fn flattens_token_movements() {
+        let movements = export::movements(&token_transaction()).unwrap();
+
+        assert_eq!(movements.len(), 3);
+        assert_eq!(movements[0].kind, MovementKind::TokenTransfer);
+        assert_eq!(movements[0].mint.as_deref(), Some(MINT));
+        assert_eq!(movements[0].decimals, Some(6));
+        assert_eq!(movements[0].ui_amount().as_deref(), Some("2.5"));
+        assert_eq!(movements[1].kind, MovementKind::TokenBalanceChange);
+        assert_eq!(movements[1].account.as_deref(), Some(ADDRESS));
+        assert_eq!(movements[1].ui_amount().as_deref(), Some("-2.5"));
+        assert_eq!(movements[2].account.as_deref(), Some(OTHER_ADDRESS));
+    }
+
+    #[test]
+    fn rejects_malformed_token_amounts() {
+        let mut transaction = token_transaction();
+        transaction.account_data[0].token_balance_changes[0].raw_token_amount.token_amount = "-2.5".to_string();
+        let mut writer = CsvWriter::new(Vec::new());
+
+        let error = export::movements(&transaction).unwrap_err();
+        assert!(error.to_string().contains(MINT));
+        assert!(writer.write_transaction(&transaction).is_err());
+        writer.finish().unwrap();
+
+        let csv = String::from_utf8(writer.into_inner()).unwrap();
+        assert_eq!(csv.lines().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn writes_csv() {
+        let transactions = fixture_transactions().await;
+        let mut writer = CsvWriter::new(Vec::new());
+
+        let count = export::export(stream::iter(transactions.into_iter().map(Ok)), &mut writer)
+            .await
+            .unwrap();
+
+        let csv = String::from_utf8(writer.into_inner()).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(count, 3);
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], COLUMNS.join(","));
+        assert_eq!(
+            lines[1],
+            format!(
+                "{},171942732,1673445241,TRANSFER,SYSTEM_PROGRAM,{},native_transfer,,{},{},,1000000000,9,1",
+                SIGNATURE, ADDRESS, ADDRESS, OTHER_ADDRESS
+            )
+        );
+    }
+
+    #[test]
+    fn writes_csv_header_without_movements() {
+        let mut writer = CsvWriter::new(Vec::new());
+
+        writer.finish().unwrap();
+
+        assert_eq!(String::from_utf8(writer.into_inner()).unwrap(), format!("{}\n", COLUMNS.join(",")));
+    }
+
+    #[test]
+    fn writes_ndjson() {
+        let mut writer = NdjsonWriter::new(Vec::new());
+
+        writer.write_transaction(&token_transaction()).unwrap();
+        writer.finish().unwrap();
+
+        let output = String::from_utf8(writer.into_inner()).unwrap();
+        let rows: Vec<Value> = output.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(rows.len(), 3);
+        assert!(output.starts_with("{\"signature\":\"token-transfer\",\"slot\":171942800,"));
+        assert_eq!(rows[0].as_object().unwrap().len(), COLUMNS.len());
+        assert_eq!(rows[0]["kind"], "token_transfer");
+        assert_eq!(rows[0]["timestamp"], Value::Null);
+        assert_eq!(rows[0]["amount"], "2500000");
+        assert_eq!(rows[1]["amount"], "-2500000");
+        assert_eq!(rows[1]["ui_amount"], "-2.5");
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn writes_parquet() {
+        use helius_rust_client::export::ParquetWriter;
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+
+        let path = std::env::temp_dir().join(format!("helius-export-{}.parquet", std::process::id()));
+        let mut writer = ParquetWriter::new(std::fs::File::create(&path).unwrap()).unwrap();
+
+        writer.write_transaction(&token_transaction()).unwrap();
+        writer.finish().unwrap();
+
+        let reader = SerializedFileReader::new(std::fs::File::open(&path).unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let metadata = reader.metadata().file_metadata();
+        assert_eq!(metadata.num_rows(), 3);
+        let columns: Vec<&str> = metadata.schema_descr().columns().iter().map(|column| column.name()).collect();
+        assert_eq!(columns, COLUMNS);
+    }
+}
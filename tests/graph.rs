@@ -0,0 +1,154 @@
+#[cfg(test)]
+mod graph {
+    use helius_rust_client::{
+        client::mock::{HeliusMethod, MockHeliusClient},
+        graph::{Explorer, FlowGraph},
+        models::enriched_transaction::EnrichedTransaction,
+        types::Signature,
+    };
+    use serde_json::{json, Value};
+    use solana_program::pubkey::Pubkey;
+    use std::sync::Arc;
+
+    const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+    fn account(n: u8) -> String {
+        Pubkey::new_from_array([n; 32]).to_string()
+    }
+
+    /// The enriched transaction fixture of the mock server in slot `n`, moving `lamports` from account `from` to
+    /// account `to` and `usdc` from `to` back to `from`.
+    fn transaction(n: u8, from: u8, to: u8, lamports: u64, usdc: u64) -> EnrichedTransaction {
+        let mut transaction: Value =
+            serde_json::from_str::<Vec<Value>>(include_str!("../helius-mock-server/fixtures/enriched_transactions.json"))
+                .unwrap()
+                .remove(0);
+        transaction["signature"] = Signature::from([n; 64]).to_string().into();
+        transaction["slot"] = n.into();
+        transaction["nativeTransfers"] = json!([
+            { "fromUserAccount": account(from), "toUserAccount": account(to), "amount": lamports }
+        ]);
+        transaction["tokenTransfers"] = json!([
+            {
+                "fromUserAccount": account(to),
+                "toUserAccount": account(from),
+                "fromTokenAccount": "",
+                "toTokenAccount": "",
+                "tokenAmount": usdc,
+                "mint": USDC
+            },
+            {
+                "fromUserAccount": "",
+                "toUserAccount": account(from),
+                "fromTokenAccount": "",
+                "toTokenAccount": "",
+                "tokenAmount": 1,
+                "mint": "minted"
+            }
+        ]);
+        serde_json::from_value(transaction).unwrap()
+    }
+
+    #[test]
+    fn builds_a_multigraph_of_transfers() {
+        let mut graph = FlowGraph::new();
+        let transactions = [transaction(1, 1, 2, 100, 5), transaction(2, 1, 2, 200, 7), transaction(3, 2, 3, 300, 0)];
+
+        assert_eq!(graph.add_transactions(&transactions), 3);
+        assert!(!graph.add_transaction(&transactions[0]));
+
+        // A native and a token transfer per transaction; the mints have no sender.
+        assert_eq!(graph.edges().len(), 6);
+        assert_eq!(graph.nodes().len(), 3);
+        assert_eq!(graph.counterparties(&account(2)), {
+            let mut accounts = [account(1), account(3)];
+            accounts.sort();
+            accounts
+        });
+        assert_eq!(graph.outgoing(&account(1)).count(), 2);
+        let flow = graph
+            .flows()
+            .into_iter()
+            .find(|flow| flow.from == account(1) && flow.mint.is_none())
+            .unwrap();
+        assert_eq!((flow.to, flow.amount, flow.transfers), (account(2), 300, 2));
+    }
+
+    #[test]
+    fn exports_graphml_dot_and_json() {
+        let mut graph = FlowGraph::new();
+        graph.add_transaction(&transaction(1, 1, 2, 100, 5));
+
+        let mut dot = Vec::new();
+        graph.write_dot(&mut dot).unwrap();
+        let dot = String::from_utf8(dot).unwrap();
+        assert!(dot.starts_with("digraph flows {\n"));
+        assert!(dot.contains(&format!("\"{}\" -> \"{}\" [label=\"100 SOL\"", account(1), account(2))));
+        assert!(dot.contains(&format!("[label=\"5 {}\"", USDC)));
+
+        let mut graphml = Vec::new();
+        graph.write_graphml(&mut graphml).unwrap();
+        let graphml = String::from_utf8(graphml).unwrap();
+        assert!(graphml.contains(&format!("<edge id=\"e0\" source=\"{}\" target=\"{}\">", account(1), account(2))));
+        assert!(graphml.contains("<data key=\"mint\">SOL</data>"));
+        assert_eq!(graphml.matches("<node id=").count(), 2);
+
+        let mut json = Vec::new();
+        graph.write_json(&mut json).unwrap();
+        let json: Value = serde_json::from_slice(&json).unwrap();
+        assert_eq!(json["nodes"].as_array().unwrap().len(), 2);
+        assert_eq!(json["edges"][1]["mint"], USDC);
+        assert_eq!(json["edges"][1]["amount"], 5);
+    }
+
+    #[tokio::test]
+    async fn explores_counterparties_hop_by_hop() {
+        let mock = Arc::new(MockHeliusClient::new());
+        // The seed transacted with accounts 2 and 3, one of which transacted with account 4.
+        for page in [vec![transaction(1, 1, 2, 100, 0), transaction(2, 3, 1, 100, 0)], vec![transaction(3, 2, 4, 100, 0)], vec![]] {
+            mock.push_response(HeliusMethod::GetEnrichedTransactions, &page);
+        }
+
+        let exploration = Explorer::new(mock.clone())
+            .with_hops(1)
+            .explore(vec![Pubkey::new_from_array([1; 32])])
+            .await
+            .unwrap();
+
+        let hops: Vec<(String, usize)> = exploration.hops.into_iter().collect();
+        let mut expected = vec![(account(1), 0), (account(2), 1), (account(3), 1)];
+        expected.sort();
+        assert_eq!(hops, expected);
+        assert!(!exploration.truncated);
+        // Account 4 is two hops away: it is in the graph, but its history was not fetched.
+        assert!(exploration.graph.nodes().contains(&account(4).as_str()));
+        let calls = mock.calls_to(HeliusMethod::GetEnrichedTransactions);
+        assert_eq!(calls.len(), 3);
+        assert!(calls[0].arguments[0].contains(&account(1)));
+    }
+
+    #[tokio::test]
+    async fn limits_pages_and_accounts() {
+        let mock = Arc::new(MockHeliusClient::new());
+        for page in [vec![transaction(1, 1, 2, 100, 0)], vec![transaction(2, 1, 3, 100, 0)], vec![]] {
+            mock.push_response(HeliusMethod::GetEnrichedTransactions, &page);
+        }
+
+        let exploration = Explorer::new(mock.clone())
+            .with_hops(3)
+            .with_page_size(1)
+            .with_pages_per_account(2)
+            .with_max_accounts(2)
+            .explore(vec![Pubkey::new_from_array([1; 32])])
+            .await
+            .unwrap();
+
+        assert!(exploration.truncated);
+        assert_eq!(exploration.hops.len(), 2);
+        assert_eq!(exploration.graph.counterparties(&account(1)).len(), 2);
+        let calls = mock.calls_to(HeliusMethod::GetEnrichedTransactions);
+        // Two pages of the seed, then the first page of one of its counterparties, which is empty.
+        assert_eq!(calls.len(), 3);
+        assert!(calls[1].arguments[0].contains(&format!("before: Some({})", Signature::from([1; 64]))));
+    }
+}
@@ -0,0 +1,281 @@
+#[cfg(test)]
+mod indexer {
+    use helius_rust_client::{
+        client::mock::{HeliusMethod, MockHeliusClient},
+        indexer::{Cursor, IndexStore, Indexer, InMemoryIndexStore},
+        models::enriched_transaction::EnrichedTransaction,
+        types::Signature,
+    };
+    use serde_json::json;
+    use solana_program::pubkey::Pubkey;
+    use std::{str::FromStr, sync::Arc};
+
+    const ADDRESS: &str = "86xCnPeV69n6t3DnyGvkKobf9FdN2H9oiVDdaMpo2MMY";
+    const OTHER_ADDRESS: &str = "4zvwRjXUKGfvwnParsHAS3HuSVzV5cA4McphgmoCtajS";
+
+    fn signature(n: u8) -> String {
+        Signature::from([n; 64]).to_string()
+    }
+
+    /// A transfer of `n` SOL from `ADDRESS` in slot `n`, with an NFT sale event.
+    fn transaction(n: u8) -> EnrichedTransaction {
+        serde_json::from_value(json!({
+            "description": null,
+            "type": "TRANSFER",
+            "source": "SYSTEM_PROGRAM",
+            "fee": 5000,
+            "feePayer": ADDRESS,
+            "signature": signature(n),
+            "slot": n,
+            "timestamp": 1673445241,
+            "nativeTransfers": [{
+                "fromUserAccount": ADDRESS,
+                "toUserAccount": OTHER_ADDRESS,
+                "amount": n as u64 * 1000000000
+            }],
+            "tokenTransfers": [],
+            "accountData": [
+                { "account": ADDRESS, "nativeBalanceChange": -(n as i64) * 1000000000 - 5000, "tokenBalanceChanges": [] },
+                { "account": OTHER_ADDRESS, "nativeBalanceChange": n as i64 * 1000000000, "tokenBalanceChanges": [] }
+            ],
+            "transactionError": null,
+            "instructions": [],
+            "events": {
+                "nft": {
+                    "amount": 1000000000, "fee": 5000, "feePayer": ADDRESS, "signature": signature(n), "slot": n,
+                    "timestamp": 1673445241, "type": "NFT_SALE", "buyer": ADDRESS, "seller": OTHER_ADDRESS, "staker": "",
+                    "nfts": [{ "mint": "DsfCsbbPH77p6yeLS1i4ag9UA5gP9xWSvdCx72FJjLsx", "tokenStandard": "NonFungible" }]
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    fn indexer(mock: &Arc<MockHeliusClient>, store: &Arc<InMemoryIndexStore>) -> Indexer {
+        Indexer::new(mock.clone(), store.clone(), vec![Pubkey::from_str(ADDRESS).unwrap()]).with_page_size(2)
+    }
+
+    #[tokio::test]
+    async fn backfills_every_page() {
+        let mock = Arc::new(MockHeliusClient::new());
+        let store = Arc::new(InMemoryIndexStore::new());
+        mock.push_response(HeliusMethod::GetEnrichedTransactions, &vec![transaction(3), transaction(2)]);
+        mock.push_response(HeliusMethod::GetEnrichedTransactions, &vec![transaction(1)]);
+
+        let count = indexer(&mock, &store).backfill().await.unwrap();
+
+        assert_eq!(count, 3);
+        assert_eq!(store.transactions_for(ADDRESS).len(), 3);
+        let calls = mock.calls_to(HeliusMethod::GetEnrichedTransactions);
+        assert_eq!(calls.len(), 2);
+        assert!(calls[0].arguments[0].contains("before: None"));
+        assert!(calls[1].arguments[0].contains(&format!("before: Some({})", signature(2))));
+        let cursor = store.cursor(ADDRESS).await.unwrap().unwrap();
+        assert_eq!(
+            cursor,
+            Cursor {
+                newest: Some(signature(3)),
+                oldest: Some(signature(1)),
+                backfilled: true
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn resumes_interrupted_backfill() {
+        let mock = Arc::new(MockHeliusClient::new());
+        let store = Arc::new(InMemoryIndexStore::new());
+        mock.push_response(HeliusMethod::GetEnrichedTransactions, &vec![transaction(3), transaction(2)]);
+        mock.push_error(HeliusMethod::GetEnrichedTransactions, "rate limited");
+
+        assert!(indexer(&mock, &store).backfill().await.is_err());
+        assert_eq!(store.len(), 2);
+
+        let mock = Arc::new(MockHeliusClient::new());
+        mock.push_response(HeliusMethod::GetEnrichedTransactions, &vec![transaction(1)]);
+        indexer(&mock, &store).backfill().await.unwrap();
+
+        assert_eq!(store.len(), 3);
+        let calls = mock.calls_to(HeliusMethod::GetEnrichedTransactions);
+        assert_eq!(calls.len(), 1);
+        assert!(calls[0].arguments[0].contains(&format!("before: Some({})", signature(2))));
+    }
+
+    #[tokio::test]
+    async fn polls_since_newest_transaction() {
+        let mock = Arc::new(MockHeliusClient::new());
+        let store = Arc::new(InMemoryIndexStore::new());
+        let backfilled = Cursor {
+            newest: Some(signature(1)),
+            oldest: Some(signature(1)),
+            backfilled: true,
+        };
+        store.save_cursor(ADDRESS, &backfilled).await.unwrap();
+        mock.push_response(HeliusMethod::GetEnrichedTransactions, &vec![transaction(3), transaction(2)]);
+        mock.push_response(HeliusMethod::GetEnrichedTransactions, &Vec::<EnrichedTransaction>::new());
+
+        let count = indexer(&mock, &store).poll().await.unwrap();
+
+        assert_eq!(count, 2);
+        assert_eq!(store.len(), 2);
+        let calls = mock.calls_to(HeliusMethod::GetEnrichedTransactions);
+        assert_eq!(calls.len(), 2);
+        assert!(calls[0].arguments[0].contains(&format!("until: Some({})", signature(1))));
+        assert!(calls[1].arguments[0].contains(&format!("before: Some({})", signature(2))));
+        let cursor = store.cursor(ADDRESS).await.unwrap().unwrap();
+        assert_eq!(cursor.newest, Some(signature(3)));
+        assert_eq!(cursor.oldest, Some(signature(1)));
+    }
+
+    #[tokio::test]
+    async fn polls_address_without_history() {
+        let mock = Arc::new(MockHeliusClient::new());
+        let store = Arc::new(InMemoryIndexStore::new());
+        mock.push_response(HeliusMethod::GetEnrichedTransactions, &Vec::<EnrichedTransaction>::new());
+        mock.push_response(HeliusMethod::GetEnrichedTransactions, &vec![transaction(1)]);
+        let indexer = indexer(&mock, &store);
+
+        assert_eq!(indexer.backfill().await.unwrap(), 0);
+        assert_eq!(store.cursor(ADDRESS).await.unwrap().unwrap().newest, None);
+
+        let count = indexer.poll().await.unwrap();
+
+        assert_eq!(count, 1);
+        assert_eq!(store.transactions_for(ADDRESS).len(), 1);
+        let calls = mock.calls_to(HeliusMethod::GetEnrichedTransactions);
+        assert_eq!(calls.len(), 2);
+        assert!(calls[1].arguments[0].contains("until: None"));
+        let cursor = store.cursor(ADDRESS).await.unwrap().unwrap();
+        assert_eq!(cursor.newest, Some(signature(1)));
+        assert!(cursor.backfilled);
+    }
+
+    #[tokio::test]
+    async fn upserts_by_signature() {
+        let mock = Arc::new(MockHeliusClient::new());
+        let store = Arc::new(InMemoryIndexStore::new());
+        let indexer = indexer(&mock, &store);
+
+        assert_eq!(indexer.ingest(&[transaction(1), transaction(1)]).await.unwrap(), 2);
+        indexer.ingest(&[transaction(1)]).await.unwrap();
+
+        assert_eq!(store.len(), 1);
+        let indexed = store.transaction(&signature(1)).unwrap();
+        assert_eq!(indexed.addresses, vec![ADDRESS.to_string()]);
+        assert_eq!(indexed.native_transfers.len(), 1);
+        assert_eq!(indexed.balance_changes[0].amount, -1000005000);
+        assert_eq!(indexed.nft_events[0].mint, "DsfCsbbPH77p6yeLS1i4ag9UA5gP9xWSvdCx72FJjLsx");
+        assert!(mock.calls().is_empty());
+        assert_eq!(store.cursor(ADDRESS).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn rejects_malformed_token_amounts() {
+        let mock = Arc::new(MockHeliusClient::new());
+        let store = Arc::new(InMemoryIndexStore::new());
+        let mut malformed = transaction(1);
+        malformed.account_data[0].token_balance_changes.push(
+            serde_json::from_value(json!({
+                "userAccount": ADDRESS,
+                "tokenAccount": OTHER_ADDRESS,
+                "mint": "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v",
+                "rawTokenAmount": { "tokenAmount": "12.5", "decimals": 6 }
+            }))
+            .unwrap(),
+        );
+
+        let error = indexer(&mock, &store).ingest(&[malformed]).await.unwrap_err();
+
+        assert!(error.to_string().contains(&signature(1)));
+        assert!(store.is_empty());
+    }
+
+    #[tokio::test]
+    async fn ingests_only_indexed_addresses() {
+        let mock = Arc::new(MockHeliusClient::new());
+        let store = Arc::new(InMemoryIndexStore::new());
+        let indexer = Indexer::new(mock, store.clone(), vec![Pubkey::new_unique()]);
+
+        assert_eq!(indexer.ingest(&[transaction(1)]).await.unwrap(), 0);
+        assert!(store.is_empty());
+    }
+
+    /// A SQLite store in a fresh database file in the temporary directory, and the path of the file.
+    #[cfg(feature = "sqlite")]
+    fn sqlite_store(name: &str) -> (Arc<helius_rust_client::indexer::sqlite::SqliteIndexStore>, std::path::PathBuf) {
+        let path = std::env::temp_dir().join(format!("helius-index-{}-{}.sqlite", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let store = helius_rust_client::indexer::sqlite::SqliteIndexStore::open(&path).unwrap();
+        (Arc::new(store), path)
+    }
+
+    #[cfg(feature = "sqlite")]
+    fn count(connection: &rusqlite::Connection, table: &str) -> i64 {
+        connection
+            .query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn sqlite_store_upserts_rows() {
+        let (store, path) = sqlite_store("upsert");
+        let mock = Arc::new(MockHeliusClient::new());
+        mock.push_response(HeliusMethod::GetEnrichedTransactions, &vec![transaction(3), transaction(2)]);
+        mock.push_response(HeliusMethod::GetEnrichedTransactions, &vec![transaction(1)]);
+        let indexer = Indexer::new(mock.clone(), store.clone(), vec![Pubkey::from_str(ADDRESS).unwrap()])
+            .with_page_size(2);
+
+        assert_eq!(indexer.backfill().await.unwrap(), 3);
+        let other = Indexer::new(mock, store, vec![Pubkey::from_str(OTHER_ADDRESS).unwrap()]);
+        assert_eq!(other.ingest(&[transaction(2)]).await.unwrap(), 1);
+
+        let connection = rusqlite::Connection::open(&path).unwrap();
+        assert_eq!(count(&connection, "transactions"), 3);
+        assert_eq!(count(&connection, "address_transactions"), 4);
+        assert_eq!(count(&connection, "native_transfers"), 3);
+        assert_eq!(count(&connection, "balance_changes"), 6);
+        assert_eq!(count(&connection, "nft_events"), 3);
+        let (slot, transaction_type, amount): (i64, String, i64) = connection
+            .query_row(
+                "SELECT t.slot, t.transaction_type, n.amount FROM transactions t
+                 JOIN native_transfers n ON n.signature = t.signature WHERE t.signature = ?1",
+                [signature(2)],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!((slot, transaction_type.as_str(), amount), (2, "TRANSFER", 2000000000));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn sqlite_store_resumes_interrupted_backfill() {
+        let (store, path) = sqlite_store("resume");
+        let mock = Arc::new(MockHeliusClient::new());
+        mock.push_response(HeliusMethod::GetEnrichedTransactions, &vec![transaction(3), transaction(2)]);
+        mock.push_error(HeliusMethod::GetEnrichedTransactions, "rate limited");
+        let indexer = Indexer::new(mock, store, vec![Pubkey::from_str(ADDRESS).unwrap()]).with_page_size(2);
+        assert!(indexer.backfill().await.is_err());
+
+        let store = Arc::new(helius_rust_client::indexer::sqlite::SqliteIndexStore::open(&path).unwrap());
+        let mock = Arc::new(MockHeliusClient::new());
+        mock.push_response(HeliusMethod::GetEnrichedTransactions, &vec![transaction(1)]);
+        let indexer = Indexer::new(mock.clone(), store.clone(), vec![Pubkey::from_str(ADDRESS).unwrap()])
+            .with_page_size(2);
+        assert_eq!(indexer.backfill().await.unwrap(), 1);
+
+        let calls = mock.calls_to(HeliusMethod::GetEnrichedTransactions);
+        assert!(calls[0].arguments[0].contains(&format!("before: Some({})", signature(2))));
+        assert_eq!(
+            store.cursor(ADDRESS).await.unwrap(),
+            Some(Cursor {
+                newest: Some(signature(3)),
+                oldest: Some(signature(1)),
+                backfilled: true
+            })
+        );
+        assert_eq!(store.cursor(OTHER_ADDRESS).await.unwrap(), None);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
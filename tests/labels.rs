@@ -0,0 +1,123 @@
+#[cfg(test)]
+mod labels {
+    use helius_rust_client::{
+        labels::{Label, LabelCategory, LabelRegistry, TransferClass, TransferClassifier},
+        models::enriched_transaction::EnrichedTransaction,
+    };
+    use serde_json::{json, Value};
+    use solana_program::pubkey::Pubkey;
+    use std::str::FromStr;
+
+    const ADDRESS: &str = "86xCnPeV69n6t3DnyGvkKobf9FdN2H9oiVDdaMpo2MMY";
+    const OTHER_ADDRESS: &str = "4zvwRjXUKGfvwnParsHAS3HuSVzV5cA4McphgmoCtajS";
+    const BINANCE: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+    const MAGIC_EDEN: &str = "1BWutmTvYPwDtmw9abTkS4Ssr8no61spGAvW1X6NDix";
+    const PROGRAM: &str = "Prog1111111111111111111111111111111111111111";
+    const STRANGER: &str = "Strangr111111111111111111111111111111111111";
+
+    /// The enriched transaction fixture of the mock server with a native transfer for each `(from, to)` pair, and an
+    /// instruction of `PROGRAM`.
+    fn transaction(transfers: &[(&str, &str)]) -> EnrichedTransaction {
+        let mut transaction: Value =
+            serde_json::from_str::<Vec<Value>>(include_str!("../helius-mock-server/fixtures/enriched_transactions.json"))
+                .unwrap()
+                .remove(0);
+        transaction["nativeTransfers"] = transfers
+            .iter()
+            .map(|(from, to)| json!({ "fromUserAccount": from, "toUserAccount": to, "amount": 1 }))
+            .collect();
+        transaction["instructions"][0]["innerInstructions"] = json!([
+            { "accounts": [], "data": "", "programId": PROGRAM }
+        ]);
+        serde_json::from_value(transaction).unwrap()
+    }
+
+    #[test]
+    fn bundles_well_known_addresses() {
+        let registry = LabelRegistry::bundled();
+
+        assert!(!registry.is_empty());
+        for label in registry.labels() {
+            assert!(Pubkey::from_str(&label.address).is_ok(), "{}", label.address);
+        }
+        let token_program = registry.get("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA").unwrap();
+        assert_eq!(token_program.category, LabelCategory::Program);
+        assert_eq!(registry.get(BINANCE).unwrap().category, LabelCategory::Exchange);
+        assert_eq!(registry.get(MAGIC_EDEN).unwrap().category, LabelCategory::Marketplace);
+    }
+
+    #[test]
+    fn user_labels_override_bundled_ones() {
+        let path = std::env::temp_dir().join(format!("helius-labels-{}.json", std::process::id()));
+        std::fs::write(
+            &path,
+            json!([
+                { "address": BINANCE, "name": "Binance deposits", "category": "exchange" },
+                { "address": OTHER_ADDRESS, "name": "OTC desk", "category": "exchange" }
+            ])
+            .to_string(),
+        )
+        .unwrap();
+        let mut registry = LabelRegistry::bundled();
+        let size = registry.len();
+
+        registry.merge(LabelRegistry::from_file(&path).unwrap());
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(registry.len(), size + 1);
+        assert_eq!(registry.get(BINANCE).unwrap().name, "Binance deposits");
+        assert_eq!(
+            registry.get(OTHER_ADDRESS),
+            Some(&Label {
+                address: OTHER_ADDRESS.to_string(),
+                name: "OTC desk".to_string(),
+                category: LabelCategory::Exchange
+            })
+        );
+        assert!(LabelRegistry::from_json(r#"[{ "address": "x", "name": "y", "category": "bank" }]"#).is_err());
+    }
+
+    #[test]
+    fn classifies_transfers_by_counterparty() {
+        let classifier =
+            TransferClassifier::new(LabelRegistry::bundled()).with_own_addresses([ADDRESS, OTHER_ADDRESS]);
+        let transaction = transaction(&[
+            (ADDRESS, OTHER_ADDRESS),
+            (ADDRESS, BINANCE),
+            (MAGIC_EDEN, ADDRESS),
+            (ADDRESS, PROGRAM),
+            (STRANGER, OTHER_ADDRESS),
+        ]);
+
+        let classified = classifier.classify(&transaction);
+
+        let classes: Vec<(TransferClass, Option<&str>)> = classified
+            .iter()
+            .map(|transfer| (transfer.class, transfer.counterparty.as_deref()))
+            .collect();
+        assert_eq!(
+            classes,
+            [
+                (TransferClass::Internal, None),
+                (TransferClass::Exchange, Some(BINANCE)),
+                (TransferClass::Marketplace, Some(MAGIC_EDEN)),
+                (TransferClass::Program, Some(PROGRAM)),
+                (TransferClass::Unknown, Some(STRANGER)),
+            ]
+        );
+        assert_eq!(classified[1].label.as_ref().unwrap().name, "Binance Hot Wallet");
+        assert!(classified[3].label.is_none());
+    }
+
+    #[test]
+    fn picks_the_labeled_side_between_foreign_addresses() {
+        let classifier = TransferClassifier::new(LabelRegistry::bundled());
+
+        let classified = classifier.classify(&transaction(&[(BINANCE, STRANGER), (STRANGER, ADDRESS)]));
+
+        assert_eq!(classified[0].class, TransferClass::Exchange);
+        assert_eq!(classified[0].counterparty.as_deref(), Some(BINANCE));
+        assert_eq!(classified[1].class, TransferClass::Unknown);
+        assert_eq!(classified[1].counterparty.as_deref(), Some(ADDRESS));
+    }
+}
@@ -1,31 +1,454 @@
+#![cfg(feature = "rpc")]
+
 #[cfg(test)]
 mod misc {
-    use std::str::FromStr;
-    use helius_rust_client::{client::{init::HeliusClient, webhooks::{WebhookType, CreateWebhookRequest}}, models::{raw_transaction::GetRawTransactionsRequestConfig, nft::{MintListRequestConfig, ActiveListingsRequestConfig, NftMetadata}, enums::{TransactionType, TransactionSource}, enriched_transaction::RequestConfig}};
-    use solana_program::pubkey::Pubkey;
-    use solana_sdk::commitment_config::CommitmentLevel;
+    use std::{io::Write, num::NonZeroUsize, str::FromStr, sync::{Arc, Mutex}, time::{Duration, Instant}};
+    use helius_mock_server::{Method, MockResponse, MockServer};
+    use helius_rust_client::client::{cache::{Cache, CacheFamily, CacheTtls, InMemoryCache}, credits::{BudgetEnforcement, CreditBudget, CreditCostTable}, failover::RpcFailoverConfig, init::HeliusClient, keys::{KeyPoolConfig, PooledKey, RotationStrategy}, secret::{ApiKey, ApiKeyPlacement, API_KEY_HEADER}, vcr::Vcr};
+    use serde_json::json;
+    use helius_rust_client::{error::{ClientError, ClientErrorKind}, types::{ClusterType, CommitmentLevel, Signature}};
+    use solana_sdk::pubkey::Pubkey;
     use tokio;
+    use tracing_subscriber::{filter::Targets, fmt::MakeWriter, layer::SubscriberExt};
+
+    const ADDRESS: &str = "86xCnPeV69n6t3DnyGvkKobf9FdN2H9oiVDdaMpo2MMY";
+
+    fn mock_client(server: &MockServer) -> HeliusClient {
+        HeliusClient::new_with_urls(
+            "test-api-key".to_string(),
+            ClusterType::MainnetBeta,
+            server.url(),
+            server.url(),
+        )
+    }
 
     #[tokio::test]
     async fn get_slot() {
-        let client = HeliusClient::new(
-            "your-api-key".to_string(),
-            solana_sdk::genesis_config::ClusterType::MainnetBeta,
-        );
+        let server = MockServer::start().await;
+        let client = mock_client(&server);
+
         let slot = client.rpc_client.get_slot().await;
-        assert!(slot.unwrap() > 0);
+
+        assert_eq!(slot.unwrap(), 171942732);
+        assert_eq!(server.rpc_requests("getSlot").len(), 1);
     }
 
     #[tokio::test]
-    async fn get_naming_service_names() {
-        let client = HeliusClient::new(
-            "your-api-key".to_string(),
-            solana_sdk::genesis_config::ClusterType::MainnetBeta,
+    async fn get_slot_rpc_error() {
+        let server = MockServer::start().await;
+        server.mock_rpc("getSlot", MockResponse::rpc_error(-32005, "Node is behind"));
+        let client = mock_client(&server);
+
+        let slot = client.rpc_client.get_slot().await;
+
+        assert!(slot.is_err());
+    }
+
+    #[tokio::test]
+    async fn rpc_errors_and_types_convert_to_crate_types() {
+        let server = MockServer::start().await;
+        server.mock_rpc("getSlot", MockResponse::rpc_error(-32005, "Node is behind"));
+        let client = mock_client(&server);
+
+        let error = ClientError::from(client.rpc_client.get_slot().await.unwrap_err());
+
+        match error.kind() {
+            ClientErrorKind::RpcResponse(response) => {
+                assert_eq!((response.code, response.message.as_str()), (-32005, "Node is behind"))
+            }
+            kind => panic!("unexpected error kind {:?}", kind),
+        }
+        let error = solana_client::client_error::ClientError::from(error);
+        assert!(error.to_string().starts_with("RPC response error -32005: Node is behind"));
+
+        let signature = Signature::from([7; 64]);
+        let solana_signature = solana_sdk::signature::Signature::from(signature);
+        assert_eq!(solana_signature.to_string(), signature.to_string());
+        assert_eq!(Signature::from(solana_signature), signature);
+        assert_eq!(
+            CommitmentLevel::from(solana_sdk::commitment_config::CommitmentLevel::Confirmed),
+            CommitmentLevel::Confirmed
+        );
+        assert_eq!(
+            ClusterType::from(solana_sdk::genesis_config::ClusterType::from(ClusterType::Devnet)),
+            ClusterType::Devnet
         );
+    }
+
+    #[tokio::test]
+    async fn get_naming_service_names() {
+        let server = MockServer::start().await;
+        let client = mock_client(&server);
+
         let x = client
-            .get_naming_service_names("YourAddress".to_string())
+            .get_naming_service_names(ADDRESS.to_string())
             .await;
 
-        println!("{:?}", x.unwrap());
+        assert_eq!(x.unwrap(), vec!["mock.sol".to_string()]);
+        let requests = server.requests_to(Method::GET, &format!("/v0/addresses/{}/names", ADDRESS));
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].query.as_deref(), Some("api-key=test-api-key"));
+    }
+
+    #[tokio::test]
+    async fn get_naming_service_names_cached() {
+        let server = MockServer::start().await;
+        let client = mock_client(&server).with_cache(
+            Arc::new(InMemoryCache::new(NonZeroUsize::new(16).unwrap())),
+            CacheTtls::default(),
+        );
+
+        let first = client.get_naming_service_names(ADDRESS.to_string()).await.unwrap();
+        let second = client.get_naming_service_names(ADDRESS.to_string()).await.unwrap();
+
+        assert_eq!(first, second);
+        assert_eq!(server.requests().len(), 1);
+        let metrics = client.cache_metrics().unwrap();
+        assert_eq!(metrics.hits(CacheFamily::NamingService), 1);
+        assert_eq!(metrics.misses(CacheFamily::NamingService), 1);
+    }
+
+    #[tokio::test]
+    async fn get_naming_service_names_error() {
+        let server = MockServer::start().await;
+        server.mock(
+            Method::GET,
+            "/v0/addresses/{address}/names",
+            MockResponse::error(500, "Internal server error").with_delay(Duration::from_millis(20)),
+        );
+        let client = mock_client(&server);
+
+        let x = client.get_naming_service_names(ADDRESS.to_string()).await;
+
+        assert!(x.is_err());
+    }
+
+    #[tokio::test]
+    async fn api_key_is_redacted() {
+        let key = ApiKey::from("test-api-key");
+        assert!(!format!("{:?} {}", key, key).contains("test-api-key"));
+
+        let client = HeliusClient::new_with_urls(
+            "test-api-key".to_string(),
+            ClusterType::MainnetBeta,
+            "http://127.0.0.1:9".to_string(),
+            "http://127.0.0.1:9".to_string(),
+        );
+        assert!(!client.rpc_client.url().contains("test-api-key"));
+
+        let rest_error = client.get_token_balances(ADDRESS.to_string()).await.unwrap_err();
+        let rpc_error = client.rpc_client.get_slot().await.unwrap_err();
+        for output in [
+            format!("{:?}", rest_error),
+            rest_error.to_string(),
+            format!("{:?}", rpc_error),
+            rpc_error.to_string(),
+        ] {
+            assert!(!output.contains("test-api-key"), "{}", output);
+        }
+    }
+
+    #[tokio::test]
+    async fn api_key_sent_as_header() {
+        let server = MockServer::start().await;
+        let client = mock_client(&server).with_api_key_placement(ApiKeyPlacement::Header);
+
+        client.get_naming_service_names(ADDRESS.to_string()).await.unwrap();
+
+        let requests = server.requests();
+        assert_eq!(requests[0].query, None);
+        assert_eq!(requests[0].headers[API_KEY_HEADER], "test-api-key");
+    }
+
+    #[derive(Clone, Default)]
+    struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for CapturedLogs {
+        type Writer = CapturedLogs;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    #[tokio::test]
+    async fn requests_are_traced() {
+        let logs = CapturedLogs::default();
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_subscriber::fmt::layer().with_ansi(false).with_writer(logs.clone()))
+            .with(Targets::new().with_target("helius_rust_client", tracing::Level::DEBUG));
+        let _guard = tracing::subscriber::set_default(subscriber);
+        let server = MockServer::start().await;
+        let client = mock_client(&server);
+
+        client.get_naming_service_names(ADDRESS.to_string()).await.unwrap();
+        client.rpc_client.get_slot().await.unwrap();
+
+        let output = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        assert!(output.contains("helius.request"), "{}", output);
+        assert!(output.contains("get_naming_service_names"), "{}", output);
+        assert!(output.contains("status=200"), "{}", output);
+        assert!(output.contains("getSlot"), "{}", output);
+        assert!(!output.contains("test-api-key"), "{}", output);
+    }
+
+    #[tokio::test]
+    async fn vcr_replays_recorded_cassette() {
+        let cassette = std::env::temp_dir().join(format!("helius-vcr-{}.json", std::process::id()));
+        let server = MockServer::start().await;
+        let recorder = mock_client(&server).with_vcr(Vcr::record(&cassette));
+
+        let names = recorder.get_naming_service_names(ADDRESS.to_string()).await.unwrap();
+        let balances = recorder.get_token_balances(ADDRESS.to_string()).await.unwrap();
+        drop(server);
+
+        let contents = std::fs::read_to_string(&cassette).unwrap();
+        assert!(!contents.contains("test-api-key"));
+
+        let replayer = HeliusClient::new_with_urls(
+            "another-api-key".to_string(),
+            ClusterType::MainnetBeta,
+            "http://127.0.0.1:9".to_string(),
+            "http://127.0.0.1:9".to_string(),
+        )
+        .with_vcr(Vcr::replay(&cassette).unwrap());
+
+        assert_eq!(replayer.get_token_balances(ADDRESS.to_string()).await.unwrap(), balances);
+        assert_eq!(replayer.get_naming_service_names(ADDRESS.to_string()).await.unwrap(), names);
+        assert!(replayer.get_naming_service_names(ADDRESS.to_string()).await.is_err());
+        std::fs::remove_file(cassette).unwrap();
+    }
+
+    #[tokio::test]
+    async fn credits_are_tracked_and_budget_enforced() {
+        let server = MockServer::start().await;
+        let client = mock_client(&server).with_credit_accounting(
+            CreditCostTable::default(),
+            Some(CreditBudget {
+                limit: 112,
+                enforcement: BudgetEnforcement::Hard,
+            }),
+        );
+
+        client.get_naming_service_names(ADDRESS.to_string()).await.unwrap();
+        client.get_transactions_by_hash(vec!["signature".to_string()], None).await.unwrap();
+        // The RPC client asks for the node version before its first request.
+        client.rpc_client.get_slot().await.unwrap();
+        assert_eq!(client.credits_used(), 112);
+        assert_eq!(client.credit_usage()["getVersion"].credits, 1);
+        assert_eq!(client.credit_usage()["getSlot"].credits, 1);
+
+        assert!(client.rpc_client.get_slot().await.is_err());
+        assert!(client.get_webhooks().await.is_err());
+        assert_eq!(server.requests().len(), 4);
+
+        client.reset_credit_usage();
+        assert_eq!(client.credits_used(), 0);
+        assert!(client.rpc_client.get_slot().await.is_ok());
+    }
+
+    #[test]
+    fn in_memory_cache_expires_entries() {
+        let cache = InMemoryCache::new(NonZeroUsize::new(2).unwrap());
+        cache.insert("fresh", b"names".to_vec(), Duration::from_secs(60));
+        cache.insert("stale", b"names".to_vec(), Duration::ZERO);
+
+        assert_eq!(cache.get("fresh"), Some(b"names".to_vec()));
+        assert_eq!(cache.get("stale"), None);
+        assert_eq!(cache.get("missing"), None);
+    }
+
+    #[tokio::test]
+    async fn key_pool_rotates_away_from_rate_limited_key() {
+        let server = MockServer::start().await;
+        server.reject_key("key-a", MockResponse::error(429, "Too many requests"));
+        let client = mock_client(&server)
+            .with_key_pool(KeyPoolConfig::new(["key-a", "key-b"], RotationStrategy::RoundRobin));
+
+        let names = client.get_naming_service_names(ADDRESS.to_string()).await;
+        assert_eq!(names.unwrap(), vec!["mock.sol".to_string()]);
+        client.get_naming_service_names(ADDRESS.to_string()).await.unwrap();
+
+        let queries: Vec<Option<String>> = server.requests().into_iter().map(|request| request.query).collect();
+        assert_eq!(
+            queries,
+            vec![
+                Some("api-key=key-a".to_string()),
+                Some("api-key=key-b".to_string()),
+                Some("api-key=key-b".to_string()),
+            ]
+        );
+        let stats = client.key_stats();
+        assert_eq!(stats[0].rate_limited, 1);
+        assert!(stats[0].cooling_down);
+        assert_eq!(stats[0].credits, 0);
+        assert_eq!(stats[1].requests, 2);
+        assert_eq!(stats[1].credits, 20);
+    }
+
+    #[tokio::test]
+    async fn key_pool_fails_over_rpc_on_unauthorized_key() {
+        let server = MockServer::start().await;
+        server.reject_key("key-a", MockResponse::error(401, "Unauthorized"));
+        let client = mock_client(&server)
+            .with_key_pool(KeyPoolConfig::new(["key-a", "key-b"], RotationStrategy::Failover));
+
+        assert_eq!(client.rpc_client.get_slot().await.unwrap(), 171942732);
+        assert_eq!(client.rpc_client.get_slot().await.unwrap(), 171942732);
+
+        // The node version query that precedes the first request is the one rejected.
+        let queries: Vec<(String, Option<String>)> = server
+            .requests()
+            .into_iter()
+            .map(|request| (request.body["method"].as_str().unwrap().to_string(), request.query))
+            .collect();
+        assert_eq!(
+            queries,
+            vec![
+                ("getVersion".to_string(), Some("api-key=key-a".to_string())),
+                ("getVersion".to_string(), Some("api-key=key-b".to_string())),
+                ("getSlot".to_string(), Some("api-key=key-b".to_string())),
+                ("getSlot".to_string(), Some("api-key=key-b".to_string())),
+            ]
+        );
+        assert!(client.key_stats()[0].unauthorized);
+        assert!(!client.rpc_client.url().contains("key-"));
+    }
+
+    #[tokio::test]
+    async fn key_pool_enforces_credit_quotas() {
+        let server = MockServer::start().await;
+        let client = mock_client(&server).with_key_pool(KeyPoolConfig::new(
+            [PooledKey::from("key-a").with_credit_quota(10), PooledKey::from("key-b").with_credit_quota(10)],
+            RotationStrategy::LeastUsed,
+        ));
+
+        client.get_naming_service_names(ADDRESS.to_string()).await.unwrap();
+        client.get_naming_service_names(ADDRESS.to_string()).await.unwrap();
+        assert!(client.get_naming_service_names(ADDRESS.to_string()).await.is_err());
+        assert_eq!(server.requests().len(), 2);
+
+        client.reset_credit_usage();
+        assert!(client.get_naming_service_names(ADDRESS.to_string()).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn requests_refused_for_lack_of_key_quota_are_not_charged() {
+        let server = MockServer::start().await;
+        let client = mock_client(&server)
+            .with_key_pool(KeyPoolConfig::new(
+                [PooledKey::from("key-a").with_credit_quota(10)],
+                RotationStrategy::RoundRobin,
+            ))
+            .with_credit_accounting(CreditCostTable::default(), None);
+        client.get_naming_service_names(ADDRESS.to_string()).await.unwrap();
+
+        assert!(client.get_naming_service_names(ADDRESS.to_string()).await.is_err());
+        assert!(client.rpc_client.get_slot().await.is_err());
+
+        assert_eq!(server.requests().len(), 1);
+        assert_eq!(client.credits_used(), 10);
+        assert_eq!(client.credit_usage().len(), 1);
+        assert_eq!(client.key_stats()[0].requests, 1);
+    }
+
+    #[tokio::test]
+    async fn rpc_fails_over_to_healthy_endpoint() {
+        let primary = MockServer::start().await;
+        primary.mock_rpc("getSlot", MockResponse::rpc_error(-32005, "Node is unhealthy"));
+        let fallback = MockServer::start().await;
+        fallback.mock_rpc("getSlot", MockResponse::json(json!(171942800)));
+        let client = mock_client(&primary).with_rpc_failover(RpcFailoverConfig::new(vec![fallback.url()]));
+
+        assert_eq!(client.rpc_client.get_slot().await.unwrap(), 171942800);
+
+        let health = client.rpc_endpoint_health();
+        assert!(!health[0].healthy);
+        assert!(health[1].healthy);
+        assert_eq!(health[1].slot, Some(171942800));
+        assert_eq!(primary.rpc_requests("getSlot").len(), 1);
+        assert_eq!(fallback.rpc_requests("getSlot").len(), 2);
+    }
+
+    #[tokio::test]
+    async fn rpc_health_checks_do_not_use_key_quota() {
+        let primary = MockServer::start().await;
+        let fallback = MockServer::start().await;
+        // Enough for the node version query and one request, but not for the health checks as well.
+        let client = mock_client(&primary)
+            .with_key_pool(KeyPoolConfig::new(
+                [PooledKey::new("key-a").with_credit_quota(2)],
+                RotationStrategy::Failover,
+            ))
+            .with_rpc_failover(RpcFailoverConfig::new(vec![fallback.url()]));
+
+        assert_eq!(client.rpc_client.get_slot().await.unwrap(), 171942732);
+
+        assert_eq!(primary.rpc_requests("getHealth").len(), 1);
+        assert_eq!(primary.rpc_requests("getSlot").len(), 2);
+        let stats = client.key_stats();
+        assert_eq!((stats[0].requests, stats[0].credits), (2, 2));
+    }
+
+    #[tokio::test]
+    async fn rpc_routes_around_lagging_endpoint() {
+        let primary = MockServer::start().await;
+        primary.mock_rpc("getSlot", MockResponse::json(json!(171942000)));
+        let fallback = MockServer::start().await;
+        let client = mock_client(&primary).with_rpc_failover(RpcFailoverConfig::new(vec![fallback.url()]));
+
+        let balance = client.rpc_client.get_balance(&Pubkey::from_str(ADDRESS).unwrap()).await;
+
+        assert_eq!(balance.unwrap(), 1500000000);
+        assert_eq!(client.rpc_endpoint_health()[0].slots_behind, 732);
+        assert!(primary.rpc_requests("getBalance").is_empty());
+        assert_eq!(fallback.rpc_requests("getBalance").len(), 1);
+    }
+
+    #[tokio::test]
+    async fn rpc_mock_client_fails_over() {
+        let fallback = MockServer::start().await;
+        // The mock RPC client answers `getSlot` with slot 0, so it trails the fallback.
+        let client = HeliusClient::new_mock("test-api-key".to_string(), ClusterType::MainnetBeta)
+            .with_rpc_failover(RpcFailoverConfig::new(vec![fallback.url()]));
+
+        let balance = client.rpc_client.get_balance(&Pubkey::from_str(ADDRESS).unwrap()).await;
+
+        assert_eq!(balance.unwrap(), 1500000000);
+        assert_eq!(client.rpc_endpoint_health()[0].slots_behind, 171942732);
+        assert_eq!(fallback.rpc_requests("getBalance").len(), 1);
+    }
+
+    #[tokio::test]
+    async fn rpc_hedges_slow_reads() {
+        let primary = MockServer::start().await;
+        primary.mock_rpc(
+            "getBalance",
+            MockResponse::json(json!({ "context": { "slot": 171942732 }, "value": 1 })).with_delay(Duration::from_secs(2)),
+        );
+        let fallback = MockServer::start().await;
+        let client = mock_client(&primary).with_rpc_failover(
+            RpcFailoverConfig::new(vec![fallback.url()]).with_hedge_delay(Duration::from_millis(50)),
+        );
+        client.rpc_client.get_slot().await.unwrap();
+
+        let started = Instant::now();
+        let balance = client.rpc_client.get_balance(&Pubkey::from_str(ADDRESS).unwrap()).await;
+
+        assert_eq!(balance.unwrap(), 1500000000);
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert_eq!(primary.rpc_requests("getBalance").len(), 1);
+        assert_eq!(fallback.rpc_requests("getBalance").len(), 1);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,198 @@
+#[cfg(test)]
+mod portfolio {
+    use helius_mock_server::MockServer;
+    use helius_rust_client::{
+        accounting::PriceTable,
+        client::{
+            init::HeliusClient,
+            mock::{HeliusMethod, MockHeliusClient},
+            portfolio::portfolio,
+        },
+        models::{
+            das::{Asset, AssetsByOwner, NativeBalance, PriceInfo, TokenInfo},
+            nft::{NftInfo, TokenBalance, TokenBalancesResponse},
+        },
+        types::ClusterType,
+    };
+
+    const ADDRESS: &str = "86xCnPeV69n6t3DnyGvkKobf9FdN2H9oiVDdaMpo2MMY";
+    const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+    const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+
+    fn client(server: &MockServer) -> HeliusClient {
+        HeliusClient::new_with_urls(
+            "test-api-key".to_string(),
+            ClusterType::MainnetBeta,
+            server.url(),
+            server.url(),
+        )
+    }
+
+    fn token(mint: &str, amount: u64, decimals: u8) -> TokenBalance {
+        TokenBalance {
+            token_account: format!("{}-{}", mint, amount),
+            mint: mint.to_string(),
+            amount,
+            decimals,
+        }
+    }
+
+    fn asset(mint: &str, price: Option<f64>) -> Asset {
+        Asset {
+            id: mint.to_string(),
+            interface: "FungibleToken".to_string(),
+            token_info: Some(TokenInfo {
+                symbol: None,
+                balance: Some(1),
+                decimals: Some(0),
+                price_info: price.map(|price| PriceInfo {
+                    price_per_token: price,
+                    total_price: Some(price),
+                    currency: "USDC".to_string(),
+                }),
+            }),
+        }
+    }
+
+    fn assets(items: Vec<Asset>, price_per_sol: Option<f64>) -> AssetsByOwner {
+        AssetsByOwner {
+            total: items.len() as u32,
+            limit: 1000,
+            page: 1,
+            items,
+            native_balance: Some(NativeBalance {
+                lamports: 0,
+                price_per_sol,
+                total_price: None,
+            }),
+        }
+    }
+
+    #[tokio::test]
+    async fn merges_balances_metadata_and_nfts() {
+        let server = MockServer::start().await;
+
+        let portfolio = client(&server).portfolio(ADDRESS.to_string()).await.unwrap();
+
+        assert_eq!(portfolio.owner, ADDRESS);
+        assert_eq!(portfolio.sol.ui_amount, 1.5);
+        let tokens: Vec<(&str, Option<&str>, f64)> = portfolio
+            .tokens
+            .iter()
+            .map(|token| (token.mint.as_deref().unwrap(), token.symbol.as_deref(), token.ui_amount))
+            .collect();
+        // The metadata fixture only knows USDC, and the assets fixture only has prices for SOL and USDC.
+        assert_eq!(tokens, [(USDC, Some("USDC"), 25.0), (BONK, None, 1000.0)]);
+        assert_eq!(portfolio.collections.len(), 1);
+        assert_eq!(portfolio.collections[0].name, "Mock Monkeys");
+        assert_eq!(portfolio.nft_count(), 1);
+        assert_eq!(portfolio.sol.price_usd, Some(150.0));
+        assert_eq!(portfolio.tokens[0].value_usd, Some(25.0));
+        assert_eq!(portfolio.unpriced(), 1);
+        assert_eq!(portfolio.total_value_usd(), 250.0);
+        assert_eq!(server.rpc_requests("getAssetsByOwner").len(), 1);
+    }
+
+    #[tokio::test]
+    async fn values_holdings_with_prices() {
+        let server = MockServer::start().await;
+        let prices = PriceTable::new()
+            .with_price(None, 0, 150.0)
+            .with_price(Some(USDC), 0, 1.0);
+
+        let portfolio = client(&server)
+            .portfolio_with_prices(ADDRESS.to_string(), &prices)
+            .await
+            .unwrap();
+
+        assert!(server.rpc_requests("getAssetsByOwner").is_empty());
+        assert_eq!(portfolio.sol.value_usd, Some(225.0));
+        assert_eq!(portfolio.tokens[0].mint.as_deref(), Some(USDC));
+        assert_eq!(portfolio.tokens[0].price_usd, Some(1.0));
+        assert_eq!(portfolio.tokens[0].value_usd, Some(25.0));
+        assert_eq!(portfolio.tokens[1].value_usd, None);
+        assert_eq!(portfolio.total_value_usd(), 250.0);
+        assert_eq!(portfolio.unpriced(), 1);
+    }
+
+    #[tokio::test]
+    async fn sums_token_accounts_and_leaves_out_nfts() {
+        let mock = MockHeliusClient::new();
+        mock.push_response(
+            HeliusMethod::GetTokenBalances,
+            &TokenBalancesResponse {
+                native_balance: 0,
+                tokens: vec![token(USDC, 1000000, 6), token(USDC, 500000, 6), token(BONK, 0, 5), token("nft", 1, 0)],
+            },
+        );
+        mock.push_response(
+            HeliusMethod::GetAllNfts,
+            &[NftInfo {
+                name: "Mock Monkey #2".to_string(),
+                token_address: "nft".to_string(),
+                collection_address: "collection".to_string(),
+                collection_name: "Mock Monkeys".to_string(),
+                image_url: String::new(),
+                traits: Vec::new(),
+            }],
+        );
+        mock.push_response(HeliusMethod::GetTokensMetadata, &Vec::<()>::new());
+        mock.push_response(HeliusMethod::GetAssetsByOwner, &assets(Vec::new(), None));
+
+        let portfolio = portfolio(&mock, ADDRESS.to_string(), None).await.unwrap();
+
+        assert_eq!(portfolio.tokens.len(), 1);
+        assert_eq!(portfolio.tokens[0].amount, 1500000);
+        assert_eq!(portfolio.tokens[0].ui_amount, 1.5);
+        assert_eq!(portfolio.nft_count(), 1);
+        let calls = mock.calls_to(HeliusMethod::GetTokensMetadata);
+        assert_eq!(calls[0].arguments[0], format!("{:?}", [USDC]));
+    }
+
+    #[tokio::test]
+    async fn values_holdings_with_das_prices_of_every_page() {
+        let mock = MockHeliusClient::new();
+        mock.push_response(
+            HeliusMethod::GetTokenBalances,
+            &TokenBalancesResponse {
+                native_balance: 2000000000,
+                tokens: vec![token(USDC, 1000000, 6), token(BONK, 100000, 5)],
+            },
+        );
+        mock.push_response(HeliusMethod::GetAllNfts, &Vec::<()>::new());
+        mock.push_response(HeliusMethod::GetTokensMetadata, &Vec::<()>::new());
+        let mut first_page: Vec<Asset> = (0..999).map(|i| asset(&format!("mint-{}", i), None)).collect();
+        first_page.push(asset(USDC, Some(1.0)));
+        mock.push_response(HeliusMethod::GetAssetsByOwner, &assets(first_page, Some(150.0)));
+        mock.push_response(HeliusMethod::GetAssetsByOwner, &assets(vec![asset(BONK, Some(0.00002))], None));
+
+        let portfolio = portfolio(&mock, ADDRESS.to_string(), None).await.unwrap();
+
+        let calls = mock.calls_to(HeliusMethod::GetAssetsByOwner);
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[1].arguments[1], "2");
+        assert_eq!(portfolio.sol.value_usd, Some(300.0));
+        assert_eq!(portfolio.tokens[0].mint.as_deref(), Some(USDC));
+        assert_eq!(portfolio.tokens[0].value_usd, Some(1.0));
+        assert_eq!(portfolio.tokens[1].price_usd, Some(0.00002));
+        assert_eq!(portfolio.unpriced(), 0);
+    }
+
+    #[tokio::test]
+    async fn rejects_overflowing_amounts() {
+        let mock = MockHeliusClient::new();
+        mock.push_response(
+            HeliusMethod::GetTokenBalances,
+            &TokenBalancesResponse {
+                native_balance: 0,
+                tokens: vec![token(BONK, u64::MAX, 5), token(BONK, 1, 5)],
+            },
+        );
+        mock.push_response(HeliusMethod::GetAllNfts, &Vec::<()>::new());
+        mock.push_response(HeliusMethod::GetAssetsByOwner, &assets(Vec::new(), None));
+
+        let error = portfolio(&mock, ADDRESS.to_string(), None).await.unwrap_err();
+
+        assert!(error.to_string().contains(BONK));
+    }
+}
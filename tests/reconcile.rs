@@ -0,0 +1,70 @@
+#[cfg(test)]
+mod reconcile {
+    use helius_rust_client::{
+        client::mock::{HeliusMethod, MockHeliusClient},
+        models::enriched_transaction::EnrichedTransaction,
+        reconcile::{ReconcileEvent, Reconciler},
+        types::Signature,
+    };
+    use serde_json::Value;
+    use std::{sync::Arc, time::Duration};
+
+    fn signature(n: u8) -> String {
+        Signature::from([n; 64]).to_string()
+    }
+
+    /// The enriched transaction fixture of the mock server in slot `n`, with the signature `signature(n)`.
+    fn transaction(n: u8) -> EnrichedTransaction {
+        let fixtures: Vec<Value> =
+            serde_json::from_str(include_str!("../helius-mock-server/fixtures/enriched_transactions.json")).unwrap();
+        let mut transaction = fixtures[0].clone();
+        transaction["signature"] = Value::String(signature(n));
+        transaction["slot"] = n.into();
+        serde_json::from_value(transaction).unwrap()
+    }
+
+    #[tokio::test]
+    async fn finalizes_transactions_found_at_finalized_commitment() {
+        let mock = Arc::new(MockHeliusClient::new());
+        mock.push_response(HeliusMethod::GetEnrichedTransactionsByHash, &vec![transaction(2)]);
+        let reconciler = Reconciler::new(mock.clone());
+        reconciler.track_all([transaction(1), transaction(2)]);
+
+        let events = reconciler.reconcile().await.unwrap().into_result().unwrap();
+
+        assert_eq!(events, vec![ReconcileEvent::Finalized(transaction(2))]);
+        assert!(reconciler.is_tracked(&signature(1)));
+        assert_eq!(reconciler.pending(), 1);
+        let calls = mock.calls_to(HeliusMethod::GetEnrichedTransactionsByHash);
+        assert_eq!(calls[0].arguments[1], "Some(Finalized)");
+    }
+
+    #[tokio::test]
+    async fn drops_transactions_not_finalized_in_time() {
+        let mock = Arc::new(MockHeliusClient::new());
+        mock.push_response(HeliusMethod::GetEnrichedTransactionsByHash, &vec![transaction(3)]);
+        let reconciler = Reconciler::new(mock.clone()).with_drop_after(Duration::ZERO);
+        reconciler.track_all([transaction(3), transaction(1)]);
+
+        let events = reconciler.reconcile().await.unwrap().into_result().unwrap();
+
+        assert_eq!(
+            events,
+            vec![ReconcileEvent::Dropped(transaction(1)), ReconcileEvent::Finalized(transaction(3))]
+        );
+        assert_eq!(reconciler.pending(), 0);
+        assert!(reconciler.reconcile().await.unwrap().results.is_empty());
+        assert_eq!(mock.calls().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn keeps_tracking_when_lookup_fails() {
+        let mock = Arc::new(MockHeliusClient::new());
+        mock.push_error(HeliusMethod::GetEnrichedTransactionsByHash, "service unavailable");
+        let reconciler = Reconciler::new(mock).with_drop_after(Duration::ZERO);
+        reconciler.track(transaction(1));
+
+        assert!(reconciler.reconcile().await.is_err());
+        assert!(reconciler.is_tracked(&signature(1)));
+    }
+}
@@ -1,19 +1,34 @@
 #[cfg(test)]
 mod nfts {
-    use std::str::FromStr;
-    use helius_rust_client::{client::{init::HeliusClient}, models::{nft::{MintListRequestConfig, ActiveListingsRequestConfig}, enriched_transaction::RequestConfig}};
+    use std::{str::FromStr, time::Duration};
+    use futures::future::join;
+    use helius_mock_server::{Method, MockResponse, MockServer};
+    use helius_rust_client::{client::{coalesce::CoalescingConfig, init::HeliusClient}, models::{nft::{MintListRequestConfig, ActiveListingsRequestConfig}, enriched_transaction::RequestConfig, enums::NftEventType}};
+    use serde_json::json;
     use solana_program::pubkey::Pubkey;
-    use solana_sdk::commitment_config::CommitmentLevel;
+    use helius_rust_client::error::ClientErrorKind;
+    use helius_rust_client::types::{ClusterType, CommitmentLevel};
     use tokio;
 
+    const ADDRESS: &str = "86xCnPeV69n6t3DnyGvkKobf9FdN2H9oiVDdaMpo2MMY";
+    const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+    const NFT_MINT: &str = "M2mx93ekt1fmXSVkTrUL9xVFHkmME8HTUi5Cyc5aF7K";
+
+    fn mock_client(server: &MockServer) -> HeliusClient {
+        HeliusClient::new_with_urls(
+            "test-api-key".to_string(),
+            ClusterType::MainnetBeta,
+            server.url(),
+            server.url(),
+        )
+    }
+
     #[tokio::test]
     async fn get_nft_events() {
-        let client = HeliusClient::new(
-            "your-api-key".to_string(),
-            solana_sdk::genesis_config::ClusterType::MainnetBeta,
-        );
+        let server = MockServer::start().await;
+        let client = mock_client(&server);
         let config = RequestConfig {
-            address: Pubkey::from_str("YourAddress").unwrap(),
+            address: Pubkey::from_str(ADDRESS).unwrap(),
             source: None,
             transaction_type: None,
             before: None,
@@ -21,18 +36,22 @@ mod nfts {
             limit: Some(20),
             commitment: Some(CommitmentLevel::Confirmed),
         };
-        let x = client.get_nft_events(config).await;
+        let x = client.get_nft_events(config).await.unwrap();
 
-        println!("{:?}", x.unwrap());
+        assert_eq!(x.len(), 1);
+        assert_eq!(x[0].event_type, NftEventType::NFT_SALE);
+        assert_eq!(x[0].nfts[0].mint, NFT_MINT);
+        let requests = server.requests_to(Method::GET, &format!("/v0/addresses/{}/nft-events", ADDRESS));
+        let query = requests[0].query.clone().unwrap();
+        assert!(query.contains("limit=20"));
+        assert!(query.contains("commitment=confirmed"));
     }
 
 
     #[tokio::test]
     async fn get_mint_list() {
-        let client = HeliusClient::new(
-            "your-api-key".to_string(),
-            solana_sdk::genesis_config::ClusterType::MainnetBeta,
-        );
+        let server = MockServer::start().await;
+        let client = mock_client(&server);
 
         let config = MintListRequestConfig {
             verified_collection_addresses: None,
@@ -43,17 +62,21 @@ mod nfts {
             pagination_token: None,
         };
 
-        let x = client.get_mint_list(config).await;
+        let x = client.get_mint_list(config).await.unwrap();
 
-        println!("{:?}", x.unwrap());
+        assert_eq!(x.result[0].mint, NFT_MINT);
+        assert_eq!(x.pagination_token, "next-page");
+        let requests = server.requests_to(Method::POST, "/v1/mintlist");
+        assert_eq!(
+            requests[0].body["query"]["firstVerifiedCreators"],
+            json!(["A4FM6h8T5Fmh9z2g3fKUrKfZn6BNFEgByR8QGpdbQhk1"])
+        );
     }
 
     #[tokio::test]
     async fn get_active_nft_listings() {
-        let client = HeliusClient::new(
-            "your-api-key".to_string(),
-            solana_sdk::genesis_config::ClusterType::MainnetBeta,
-        );
+        let server = MockServer::start().await;
+        let client = mock_client(&server);
 
         let config = ActiveListingsRequestConfig {
             marketplaces: vec!["MAGIC_EDEN".to_string()],
@@ -65,67 +88,229 @@ mod nfts {
             pagination_token: None,
         };
 
-        let x = client.get_active_nft_listings(config).await;
+        let x = client.get_active_nft_listings(config).await.unwrap();
 
-        println!("{:?}", x.unwrap());
+        assert_eq!(x.result.len(), 1);
+        assert_eq!(x.result[0].active_listings[0].marketplace, "MAGIC_EDEN");
+        let requests = server.requests_to(Method::POST, "/v1/active-listings");
+        assert_eq!(requests[0].body["options"]["limit"], json!(20));
     }
 
     #[tokio::test]
     async fn get_tokens_metadata() {
-        let client = HeliusClient::new(
-            "your-api-key".to_string(),
-            solana_sdk::genesis_config::ClusterType::MainnetBeta,
-        );
+        let server = MockServer::start().await;
+        let client = mock_client(&server);
         let x = client
             .get_tokens_metadata(vec![
-                "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string(),
+                USDC_MINT.to_string(),
             ])
-            .await;
+            .await
+            .unwrap();
 
-        println!("{:?}", x.unwrap());
+        assert!(x.is_complete());
+        assert_eq!(x.results.len(), 1);
+        assert_eq!(x.results[0].on_chain_data.as_ref().unwrap().data.symbol, "USDC");
     }
 
     #[tokio::test]
-    async fn get_token_balances() {
-        let client = HeliusClient::new(
-            "your-api-key".to_string(),
-            solana_sdk::genesis_config::ClusterType::MainnetBeta,
+    async fn get_tokens_metadata_in_chunks() {
+        let server = MockServer::start().await;
+        server.mock(
+            Method::POST,
+            "/v0/tokens/metadata",
+            MockResponse::error(429, "Too many requests"),
+        );
+        let client = mock_client(&server);
+        let mints: Vec<String> = (0..150).map(|i| format!("mint-{}", i)).collect();
+
+        let x = client.get_tokens_metadata(mints).await.unwrap();
+
+        let requests = server.requests_to(Method::POST, "/v0/tokens/metadata");
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].body["mintAccounts"].as_array().unwrap().len(), 100);
+        assert_eq!(requests[1].body["mintAccounts"].as_array().unwrap().len(), 50);
+        assert_eq!(x.failed_chunks.len(), 2);
+        assert_eq!(x.failed_chunks[1].inputs.len(), 50);
+    }
+
+    #[tokio::test]
+    async fn get_tokens_metadata_keeps_successful_chunks() {
+        let server = MockServer::start().await;
+        server.mock_once(
+            Method::POST,
+            "/v0/tokens/metadata",
+            MockResponse::error(400, "Invalid mint"),
         );
+        let mut client = mock_client(&server);
+        client.max_concurrent_requests = 1;
+        let mints: Vec<String> = (0..150).map(|i| format!("mint-{}", i)).collect();
+
+        let x = client.get_tokens_metadata(mints).await.unwrap();
+
+        assert_eq!(server.requests_to(Method::POST, "/v0/tokens/metadata").len(), 2);
+        assert!(!x.is_complete());
+        assert_eq!(x.results.len(), 1);
+        assert_eq!(x.results[0].on_chain_data.as_ref().unwrap().data.symbol, "USDC");
+        assert_eq!(x.failed_chunks.len(), 1);
+        assert_eq!(x.failed_chunks[0].inputs.len(), 100);
+        assert_eq!(x.failed_chunks[0].inputs[0], "mint-0");
+    }
+
+    #[tokio::test]
+    async fn get_tokens_metadata_coalesced() {
+        let server = MockServer::start().await;
+        let client = mock_client(&server).with_coalescing(CoalescingConfig {
+            batch_window: Duration::from_millis(50),
+        });
+
+        let (first, second) = join(
+            client.get_tokens_metadata(vec![USDC_MINT.to_string()]),
+            client.get_tokens_metadata(vec![USDC_MINT.to_string(), NFT_MINT.to_string()]),
+        )
+        .await;
+
+        assert_eq!(server.requests_to(Method::POST, "/v0/tokens/metadata").len(), 1);
+        assert_eq!(first.unwrap().results.len(), 1);
+        assert_eq!(second.unwrap().results[0].mint, USDC_MINT);
+    }
+
+    #[tokio::test]
+    async fn get_token_balances() {
+        let server = MockServer::start().await;
+        let client = mock_client(&server);
         let x = client
-            .get_token_balances("YourAddress".to_string())
-            .await;
+            .get_token_balances(ADDRESS.to_string())
+            .await
+            .unwrap();
 
-        println!("{:?}", x.unwrap());
+        assert_eq!(x.native_balance, 1500000000);
+        assert_eq!(x.tokens.len(), 2);
+        assert_eq!(x.tokens[0].mint, USDC_MINT);
+        assert_eq!(x.tokens[0].decimals, 6);
     }
 
     #[tokio::test]
-    async fn get_nfts_metadata() {
-        let client = HeliusClient::new(
-            "your-api-key".to_string(),
-            solana_sdk::genesis_config::ClusterType::MainnetBeta,
+    async fn get_token_balances_coalesced() {
+        let server = MockServer::start().await;
+        server.set_latency(Duration::from_millis(50));
+        let client = mock_client(&server).with_coalescing(CoalescingConfig::default());
+
+        let (first, second) = join(
+            client.get_token_balances(ADDRESS.to_string()),
+            client.get_token_balances(ADDRESS.to_string()),
+        )
+        .await;
+
+        assert_eq!(first.unwrap(), second.unwrap());
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn get_token_balances_coalesced_error() {
+        let server = MockServer::start().await;
+        server.set_latency(Duration::from_millis(50));
+        server.mock(
+            Method::GET,
+            "/v0/addresses/{address}/balances",
+            MockResponse::error(500, "Internal error"),
         );
+        let client = mock_client(&server).with_coalescing(CoalescingConfig::default());
+
+        let (first, second) = join(
+            client.get_token_balances(ADDRESS.to_string()),
+            client.get_token_balances(ADDRESS.to_string()),
+        )
+        .await;
+
+        assert_eq!(server.requests().len(), 1);
+        for error in [first.unwrap_err(), second.unwrap_err()] {
+            match error.kind() {
+                ClientErrorKind::Reqwest(e) => assert!(e.is_decode()),
+                kind => panic!("unexpected error kind {:?}", kind),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn get_token_balances_error() {
+        let server = MockServer::start().await;
+        server.mock(
+            Method::GET,
+            "/v0/addresses/{address}/balances",
+            MockResponse::error(401, "Unauthorized"),
+        );
+        let client = mock_client(&server);
+
+        let x = client.get_token_balances(ADDRESS.to_string()).await;
+
+        assert!(x.is_err());
+    }
+
+    #[tokio::test]
+    async fn get_nfts_metadata() {
+        let server = MockServer::start().await;
+        let client = mock_client(&server);
         let x = client
             .get_nfts_metadata(vec![
-                "NftAddress".to_string()
+                NFT_MINT.to_string()
             ])
-            .await;
+            .await
+            .unwrap();
 
-        println!("{:?}", x.unwrap());
+        assert_eq!(x.results.len(), 1);
+        assert_eq!(x.results[0].name, "Mock Monkey #1");
+        assert!(!x.results[0].burned);
     }
 
     #[tokio::test]
     async fn get_nfts_for_address() {
-        let client = HeliusClient::new(
-            "your-api-key".to_string(),
-            solana_sdk::genesis_config::ClusterType::MainnetBeta,
-        );
+        let server = MockServer::start().await;
+        let client = mock_client(&server);
         let x = client
             .get_nfts(
-                "YourAddress".to_string(),
+                ADDRESS.to_string(),
                 Some(1),
             )
-            .await;
+            .await
+            .unwrap();
+
+        assert_eq!(x.len(), 1);
+        assert_eq!(x[0].token_address, NFT_MINT);
+        assert_eq!(x[0].traits[0].trait_type, "Background");
+    }
+
+    #[tokio::test]
+    async fn get_all_nfts_for_address() {
+        let server = MockServer::start().await;
+        server.mock(
+            Method::GET,
+            "/v0/addresses/{address}/nfts",
+            MockResponse::json(json!({
+                "numberOfPages": 3,
+                "nfts": [{
+                    "name": "Mock Monkey",
+                    "tokenAddress": NFT_MINT,
+                    "collectionAddress": "A4FM6h8T5Fmh9z2g3fKUrKfZn6BNFEgByR8QGpdbQhk1",
+                    "collectionName": "Mock Monkeys",
+                    "imageUrl": "https://example.com/monkey.png",
+                    "traits": []
+                }]
+            })),
+        );
+        let client = mock_client(&server);
+        let x = client
+            .get_all_nfts(ADDRESS.to_string())
+            .await
+            .unwrap();
 
-        println!("{:?}", x.unwrap());
+        assert_eq!(x.len(), 3);
+        let mut pages: Vec<String> = server
+            .requests()
+            .into_iter()
+            .map(|request| request.query.unwrap())
+            .collect();
+        pages.sort();
+        assert!(pages[0].starts_with("pageNumber=1"));
+        assert!(pages[2].starts_with("pageNumber=3"));
     }
 }
@@ -1,76 +1,132 @@
 #[cfg(test)]
 mod tests {
-    use std::str::FromStr;
-    use helius_rust_client::{client::{init::HeliusClient}, models::{raw_transaction::GetRawTransactionsRequestConfig, enums::{TransactionSource}, enriched_transaction::RequestConfig}};
+    use std::{num::NonZeroUsize, str::FromStr, sync::Arc};
+    use helius_mock_server::{Method, MockResponse, MockServer};
+    use helius_rust_client::{client::{cache::{CacheTtls, InMemoryCache}, init::HeliusClient}, models::{raw_transaction::GetRawTransactionsRequestConfig, enums::{TransactionSource, TransactionType}, enriched_transaction::RequestConfig}};
     use solana_program::pubkey::Pubkey;
-    use solana_sdk::commitment_config::CommitmentLevel;
+    use helius_rust_client::types::{ClusterType, CommitmentLevel};
     use tokio;
 
+    const ADDRESS: &str = "86xCnPeV69n6t3DnyGvkKobf9FdN2H9oiVDdaMpo2MMY";
+    const SIGNATURE: &str = "5h6xBEauJ3PK6SWCZ1PGjBvj8vDdWG3KpwATGy1ARAXFSDwt8GFXM7W5Ncn16wmqokgpiKRLuS83KUxyZyv2sUYv";
+
+    fn mock_client(server: &MockServer) -> HeliusClient {
+        HeliusClient::new_with_urls(
+            "test-api-key".to_string(),
+            ClusterType::MainnetBeta,
+            server.url(),
+            server.url(),
+        )
+    }
+
     #[tokio::test]
     async fn get_transactions() {
-        let client = HeliusClient::new(
-            "your-api-key".to_string(),
-            solana_sdk::genesis_config::ClusterType::MainnetBeta,
-        );
+        let server = MockServer::start().await;
+        let client = mock_client(&server);
 
         let config = GetRawTransactionsRequestConfig {
-            address: Pubkey::from_str("YourAddress").unwrap(),
+            address: Pubkey::from_str(ADDRESS).unwrap(),
             before: None,
             until: None,
             limit: Some(2),
             commitment: Some(CommitmentLevel::Confirmed),
         };
 
-        let x = client.get_transactions_for_address(config).await;
+        let x = client.get_transactions_for_address(config).await.unwrap();
 
-        println!("{:?}", x.unwrap());
+        assert_eq!(x.len(), 1);
+        assert_eq!(x[0].slot, 171942732);
+        assert_eq!(x[0].transaction.signatures[0], SIGNATURE);
+        let requests = server.requests_to(Method::GET, &format!("/v0/addresses/{}/raw-transactions", ADDRESS));
+        assert!(requests[0].query.as_ref().unwrap().contains("limit=2"));
     }
 
     #[tokio::test]
     async fn get_transactions_by_hash() {
-        let client = HeliusClient::new(
-            "your-api-key".to_string(),
-            solana_sdk::genesis_config::ClusterType::MainnetBeta,
-        );
+        let server = MockServer::start().await;
+        let client = mock_client(&server);
 
-        let x = client.get_transactions_by_hash(vec!["YourTxnHash".to_string()], None).await;
+        let x = client.get_transactions_by_hash(vec![SIGNATURE.to_string()], None).await.unwrap();
 
-        println!("{:?}", x.unwrap());
+        assert!(x.is_complete());
+        assert_eq!(x.results[0].transaction.signatures[0], SIGNATURE);
+        let requests = server.requests_to(Method::POST, "/v0/raw-transactions");
+        assert!(requests[0].query.as_ref().unwrap().contains("commitment=finalized"));
     }
 
     #[tokio::test]
     async fn get_enriched_transactions() {
-        let client = HeliusClient::new(
-            "your-api-key".to_string(),
-            solana_sdk::genesis_config::ClusterType::MainnetBeta,
-        );
+        let server = MockServer::start().await;
+        let client = mock_client(&server);
         let config = RequestConfig {
-            address: Pubkey::from_str("YourAddress").unwrap(),
+            address: Pubkey::from_str(ADDRESS).unwrap(),
             before: None,
             until: None,
             limit: Some(5),
-            source: Some(TransactionSource::TENSOR),
+            source: Some(TransactionSource::SYSTEM_PROGRAM),
             transaction_type: None,
             commitment: Some(CommitmentLevel::Confirmed),
         };
 
-        let x = client.get_enriched_transactions(config).await;
+        let x = client.get_enriched_transactions(config).await.unwrap();
 
-        println!("{:?}", x.unwrap());
+        assert_eq!(x.len(), 1);
+        assert_eq!(x[0].transaction_type, TransactionType::TRANSFER);
+        assert_eq!(x[0].native_transfers[0].amount, 1000000000);
+        let requests = server.requests_to(Method::GET, &format!("/v0/addresses/{}/transactions", ADDRESS));
+        assert!(requests[0].query.as_ref().unwrap().contains("source=SYSTEM_PROGRAM"));
     }
 
     #[tokio::test]
     async fn get_enriched_transactions_by_hash() {
-        let client = HeliusClient::new(
-            "your-api-key".to_string(),
-            solana_sdk::genesis_config::ClusterType::MainnetBeta,
+        let server = MockServer::start().await;
+        let client = mock_client(&server);
+
+        let x = client
+            .get_enriched_transactions_by_hash(vec![SIGNATURE.to_string()], Some(CommitmentLevel::Confirmed))
+            .await
+            .unwrap();
+
+        assert_eq!(x.results[0].signature, SIGNATURE);
+        let requests = server.requests_to(Method::POST, "/v0/transactions");
+        assert!(requests[0].query.as_ref().unwrap().contains("commitment=confirmed"));
+    }
+
+    #[tokio::test]
+    async fn get_enriched_transactions_by_hash_cached_when_finalized() {
+        let server = MockServer::start().await;
+        let client = mock_client(&server).with_cache(
+            Arc::new(InMemoryCache::new(NonZeroUsize::new(16).unwrap())),
+            CacheTtls::default(),
         );
+        let hashes = vec![SIGNATURE.to_string()];
+
+        client.get_enriched_transactions_by_hash(hashes.clone(), None).await.unwrap();
+        client.get_enriched_transactions_by_hash(hashes.clone(), None).await.unwrap();
+        assert_eq!(server.requests().len(), 1);
+
+        client.get_enriched_transactions_by_hash(hashes.clone(), Some(CommitmentLevel::Confirmed)).await.unwrap();
+        client.get_enriched_transactions_by_hash(hashes, Some(CommitmentLevel::Confirmed)).await.unwrap();
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn get_enriched_transactions_by_hash_error() {
+        let server = MockServer::start().await;
+        server.mock(
+            Method::POST,
+            "/v0/transactions",
+            MockResponse::error(500, "Internal server error"),
+        );
+        let client = mock_client(&server);
 
         let x = client
-            .get_enriched_transactions_by_hash(vec!["YourTxnHash".to_string()], Some(CommitmentLevel::Confirmed))
-            .await;
+            .get_enriched_transactions_by_hash(vec![SIGNATURE.to_string()], None)
+            .await
+            .unwrap();
 
-        println!("{:?}", x.unwrap());
+        assert!(x.results.is_empty());
+        assert_eq!(x.failed_chunks[0].inputs, vec![SIGNATURE.to_string()]);
+        assert!(x.into_result().is_err());
     }
 }
-
@@ -0,0 +1,117 @@
+#[cfg(test)]
+mod watch {
+    use futures::StreamExt;
+    use helius_mock_server::{Method, MockServer};
+    use helius_rust_client::{
+        client::{
+            init::HeliusClient,
+            mock::{HeliusMethod, MockHeliusClient},
+            watch::watch_addresses,
+        },
+        models::enriched_transaction::EnrichedTransaction,
+        types::{ClusterType, Signature},
+    };
+    use serde_json::Value;
+    use solana_program::pubkey::Pubkey;
+    use std::{str::FromStr, time::Duration};
+
+    const ADDRESS: &str = "86xCnPeV69n6t3DnyGvkKobf9FdN2H9oiVDdaMpo2MMY";
+    const OTHER_ADDRESS: &str = "4zvwRjXUKGfvwnParsHAS3HuSVzV5cA4McphgmoCtajS";
+    const SIGNATURE: &str = "5h6xBEauJ3PK6SWCZ1PGjBvj8vDdWG3KpwATGy1ARAXFSDwt8GFXM7W5Ncn16wmqokgpiKRLuS83KUxyZyv2sUYv";
+    const INTERVAL: Duration = Duration::from_millis(10);
+
+    fn signature(n: u8) -> String {
+        Signature::from([n; 64]).to_string()
+    }
+
+    /// The enriched transaction fixture of the mock server in slot `n`, with the signature `signature(n)`.
+    fn transaction(n: u8) -> EnrichedTransaction {
+        let fixtures: Vec<Value> =
+            serde_json::from_str(include_str!("../helius-mock-server/fixtures/enriched_transactions.json")).unwrap();
+        let mut transaction = fixtures[0].clone();
+        transaction["signature"] = Value::String(signature(n));
+        transaction["slot"] = n.into();
+        serde_json::from_value(transaction).unwrap()
+    }
+
+    fn pages(mock: &MockHeliusClient, pages: &[&[u8]]) {
+        for page in pages {
+            let transactions: Vec<EnrichedTransaction> = page.iter().map(|&n| transaction(n)).collect();
+            mock.push_response(HeliusMethod::GetEnrichedTransactions, &transactions);
+        }
+    }
+
+    fn addresses() -> Vec<Pubkey> {
+        vec![Pubkey::from_str(ADDRESS).unwrap(), Pubkey::from_str(OTHER_ADDRESS).unwrap()]
+    }
+
+    #[tokio::test]
+    async fn merges_new_transactions_by_slot() {
+        let mock = MockHeliusClient::new();
+        // The newest transactions when the watch starts, then a poll of each address, then nothing new.
+        pages(&mock, &[&[1], &[1], &[4, 2], &[3, 2], &[]]);
+
+        let slots: Vec<u64> = watch_addresses(&mock, addresses(), INTERVAL)
+            .take(3)
+            .map(|transaction| transaction.unwrap().slot)
+            .collect()
+            .await;
+
+        assert_eq!(slots, [2, 3, 4]);
+        let calls = mock.calls_to(HeliusMethod::GetEnrichedTransactions);
+        assert!(calls[0].arguments[0].contains("until: None"));
+        assert!(calls[0].arguments[0].contains("limit: Some(1)"));
+        assert!(calls[2].arguments[0].contains(&format!("until: Some({})", signature(1))));
+    }
+
+    #[tokio::test]
+    async fn polls_after_the_last_seen_transaction() {
+        let mock = MockHeliusClient::new();
+        pages(&mock, &[&[1], &[2], &[3], &[]]);
+        let watch = watch_addresses(&mock, addresses()[..1].to_vec(), INTERVAL);
+        futures::pin_mut!(watch);
+
+        assert_eq!(watch.next().await.unwrap().unwrap().slot, 2);
+        assert_eq!(watch.next().await.unwrap().unwrap().slot, 3);
+
+        let calls = mock.calls_to(HeliusMethod::GetEnrichedTransactions);
+        assert!(calls[2].arguments[0].contains(&format!("until: Some({})", signature(2))));
+    }
+
+    #[tokio::test]
+    async fn yields_errors_and_retries() {
+        let mock = MockHeliusClient::new();
+        pages(&mock, &[&[1]]);
+        mock.push_error(HeliusMethod::GetEnrichedTransactions, "rate limited");
+        pages(&mock, &[&[2], &[]]);
+        let watch = watch_addresses(&mock, addresses()[..1].to_vec(), INTERVAL);
+        futures::pin_mut!(watch);
+
+        assert!(watch.next().await.unwrap().is_err());
+        assert_eq!(watch.next().await.unwrap().unwrap().slot, 2);
+
+        let calls = mock.calls_to(HeliusMethod::GetEnrichedTransactions);
+        assert!(calls[2].arguments[0].contains(&format!("until: Some({})", signature(1))));
+    }
+
+    #[tokio::test]
+    async fn client_polls_until_last_seen_signature() {
+        let server = MockServer::start().await;
+        let client = HeliusClient::new_with_urls(
+            "test-api-key".to_string(),
+            ClusterType::MainnetBeta,
+            server.url(),
+            server.url(),
+        );
+
+        let watch = client.watch_addresses(addresses()[..1].to_vec(), INTERVAL);
+        futures::pin_mut!(watch);
+        // The fixture is the newest transaction on every poll, so nothing new is yielded.
+        assert!(tokio::time::timeout(Duration::from_millis(100), watch.next()).await.is_err());
+
+        let requests = server.requests_to(Method::GET, &format!("/v0/addresses/{}/transactions", ADDRESS));
+        assert!(requests.len() > 1);
+        assert!(!requests[0].query.as_ref().unwrap().contains("until="));
+        assert!(requests[1].query.as_ref().unwrap().contains(&format!("until={}", SIGNATURE)));
+    }
+}
@@ -1,86 +1,154 @@
 #[cfg(test)]
 mod webhooks {
-    use helius_rust_client::{client::{init::HeliusClient, webhooks::{WebhookType, CreateWebhookRequest}}, models::enums::TransactionType};
+    use helius_mock_server::{Method, MockResponse, MockServer};
+    use helius_rust_client::{client::{api::HeliusApi, init::HeliusClient, mock::{HeliusMethod, MockHeliusClient}, webhooks::{Webhook, WebhookType, CreateWebhookRequest}}, models::enums::TransactionType};
+    use serde_json::json;
+    use helius_rust_client::types::ClusterType;
     use tokio;
 
+    const ADDRESS: &str = "86xCnPeV69n6t3DnyGvkKobf9FdN2H9oiVDdaMpo2MMY";
+
+    fn mock_client(server: &MockServer) -> HeliusClient {
+        HeliusClient::new_with_urls(
+            "test-api-key".to_string(),
+            ClusterType::MainnetBeta,
+            server.url(),
+            server.url(),
+        )
+    }
+
     #[tokio::test]
     async fn create_webhook() {
-        let client = HeliusClient::new(
-            "your-api-key".to_string(),
-            solana_sdk::genesis_config::ClusterType::MainnetBeta,
-        );
+        let server = MockServer::start().await;
+        let client = mock_client(&server);
 
         let example_hook_request = CreateWebhookRequest {
             webhook_url: "https://discord.com/api/webhooks/12345".to_string(),
             transaction_types: vec![TransactionType::NFT_BID],
-            account_addresses: vec!["YourAddress".to_string()],
+            account_addresses: vec![ADDRESS.to_string()],
             webhook_type: WebhookType::discord,
             auth_header: "HEADER".to_owned(),
         };
-        let x = client.create_webhook(example_hook_request).await;
+        let x = client.create_webhook(example_hook_request).await.unwrap();
 
-        println!("{:?}", x.unwrap());
+        assert_eq!(x.webhook_id, "mock-webhook-id");
+        let requests = server.requests_to(Method::POST, "/v0/webhooks");
+        assert_eq!(requests[0].body["webhookURL"], json!("https://discord.com/api/webhooks/12345"));
+        assert_eq!(requests[0].body["transactionTypes"], json!(["NFT_BID"]));
     }
 
     #[tokio::test]
     async fn get_webhooks() {
-        let client = HeliusClient::new(
-            "your-api-key".to_string(),
-            solana_sdk::genesis_config::ClusterType::MainnetBeta,
-        );
+        let server = MockServer::start().await;
+        let client = mock_client(&server);
 
-        let x = client.get_webhooks().await;
+        let x = client.get_webhooks().await.unwrap();
 
-        println!("{:?}", x.unwrap());
+        assert_eq!(x.len(), 1);
+        assert_eq!(x[0].webhook_type, WebhookType::discord);
     }
 
     #[tokio::test]
     async fn get_webhook() {
-        let client = HeliusClient::new(
-            "your-api-key".to_string(),
-            solana_sdk::genesis_config::ClusterType::MainnetBeta,
+        let server = MockServer::start().await;
+        let client = mock_client(&server);
+
+        let x = client
+            .get_webhook("mock-webhook-id".to_string())
+            .await
+            .unwrap();
+
+        assert_eq!(x.account_addresses, vec![ADDRESS.to_string()]);
+    }
+
+    #[tokio::test]
+    async fn get_webhook_not_found() {
+        let server = MockServer::start().await;
+        server.mock(
+            Method::GET,
+            "/v0/webhooks/{webhook_id}",
+            MockResponse::error(404, "Webhook not found"),
         );
+        let client = mock_client(&server);
 
         let x = client
-            .get_webhook("webhook-id".to_string())
+            .get_webhook("missing-webhook-id".to_string())
             .await;
 
-        println!("{:?}", x.unwrap());
+        assert!(x.is_err());
     }
 
     #[tokio::test]
     async fn edit_webhook() {
-        let client = HeliusClient::new(
-            "your-api-key".to_string(),
-            solana_sdk::genesis_config::ClusterType::MainnetBeta,
-        );
+        let server = MockServer::start().await;
+        let client = mock_client(&server);
         let goo = CreateWebhookRequest {
             webhook_url: "https://discord.com/api/webhooks/12345".to_string(),
             transaction_types: vec![TransactionType::NFT_AUCTION_CANCELLED],
-            account_addresses: vec!["YourAddress".to_string()],
+            account_addresses: vec![ADDRESS.to_string()],
             webhook_type: WebhookType::discord,
             auth_header: "HEADER".to_owned(),
         };
         let x = client
-            .edit_webhook("webhook-id".to_string(), goo)
+            .edit_webhook("mock-webhook-id".to_string(), goo)
             .await;
 
-        println!("{:?}", x.unwrap());
+        assert!(x.is_ok());
+        let requests = server.requests_to(Method::PUT, "/v0/webhooks/mock-webhook-id");
+        assert_eq!(requests[0].body["transactionTypes"], json!(["NFT_AUCTION_CANCELLED"]));
     }
 
     #[tokio::test]
     async fn delete_webhook() {
-        let client = HeliusClient::new(
-            "your-api-key".to_string(),
-            solana_sdk::genesis_config::ClusterType::MainnetBeta,
-        );
+        let server = MockServer::start().await;
+        let client = mock_client(&server);
         let x = client
-            .delete_webhook("webhook-id".to_string())
+            .delete_webhook("mock-webhook-id".to_string())
             .await;
 
-        println!("{:?}", x.unwrap());
+        assert!(x.is_ok());
+        assert_eq!(server.requests_to(Method::DELETE, "/v0/webhooks/mock-webhook-id").len(), 1);
     }
 
+    #[tokio::test]
+    async fn delete_webhook_error() {
+        let server = MockServer::start().await;
+        server.mock(
+            Method::DELETE,
+            "/v0/webhooks/{webhook_id}",
+            MockResponse::error(500, "Internal server error"),
+        );
+        let client = mock_client(&server);
+        let x = client
+            .delete_webhook("mock-webhook-id".to_string())
+            .await;
 
-}
+        assert!(x.is_err());
+    }
 
+    #[tokio::test]
+    async fn mock_client_returns_canned_webhooks() {
+        let mock = MockHeliusClient::new();
+        let webhook = Webhook {
+            webhook_id: "webhook-id".to_string(),
+            wallet: "YourWallet".to_string(),
+            webhook_url: "https://discord.com/api/webhooks/12345".to_string(),
+            transaction_types: vec![TransactionType::NFT_SALE],
+            account_addresses: vec!["YourAddress".to_string()],
+            webhook_type: WebhookType::discord,
+            auth_header: "HEADER".to_owned(),
+        };
+        mock.push_response(HeliusMethod::GetWebhook, &webhook);
+        mock.push_error(HeliusMethod::DeleteWebhook, "webhook not found");
+
+        let api: &dyn HeliusApi = &mock;
+        assert_eq!(api.get_webhook("webhook-id".to_string()).await.unwrap(), webhook);
+        assert!(api.delete_webhook("webhook-id".to_string()).await.is_err());
+        assert!(api.get_webhooks().await.is_err());
+
+        let calls = mock.calls_to(HeliusMethod::GetWebhook);
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].arguments, vec!["\"webhook-id\"".to_string()]);
+        assert_eq!(mock.calls().len(), 3);
+    }
+}